@@ -0,0 +1,102 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, Instant};
+
+/// Checks of a token with a deadline per read of the clock. Reading it costs
+/// more than checking the flag, and the search checks once per node.
+#[cfg(not(target_arch = "wasm32"))]
+const CLOCK_CHECK_INTERVAL: u32 = 64;
+
+/// A cheaply clonable flag used to abort a running crack from another thread.
+///
+/// All clones share the same flag: calling [`cancel`](CancelToken::cancel) on
+/// any of them is observed by every long-running loop holding another clone.
+/// Cancellation is cooperative, the enumeration checks the flag once per
+/// search node and returns whatever it has collected so far.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken {
+    flag: Arc<AtomicBool>,
+    /// Instant after which this clone reads as cancelled.
+    #[cfg(not(target_arch = "wasm32"))]
+    deadline: Option<Arc<Deadline>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+struct Deadline {
+    at: Instant,
+    /// Checks so far, to read the clock every [`CLOCK_CHECK_INTERVAL`] of them.
+    checks: AtomicU32,
+    /// Whether the clock has been seen past `at`.
+    passed: AtomicBool,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        CancelToken::default()
+    }
+
+    /// A clone sharing this token's flag that also reads as cancelled once
+    /// `timeout` has elapsed. Other clones are not affected by the deadline.
+    /// The clock is read every few checks, so the deadline is noticed a few
+    /// search nodes late.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        let at = Instant::now() + timeout;
+        let deadline = Deadline {
+            at: self.deadline.as_ref().map_or(at, |d| d.at.min(at)),
+            checks: AtomicU32::new(0),
+            passed: AtomicBool::new(false),
+        };
+        CancelToken { flag: self.flag.clone(), deadline: Some(Arc::new(deadline)) }
+    }
+
+    /// Request cancellation.
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+
+    /// Check whether cancellation has been requested.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        if self.flag.load(Ordering::Relaxed) {
+            return true;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(deadline) = &self.deadline {
+            let read_clock = deadline.checks.fetch_add(1, Ordering::Relaxed) % CLOCK_CHECK_INTERVAL == 0;
+            if read_clock && Instant::now() >= deadline.at {
+                deadline.passed.store(true, Ordering::Relaxed);
+            }
+            return deadline.passed.load(Ordering::Relaxed);
+        }
+        false
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeout_only_cancels_its_clone() {
+        let token = CancelToken::new();
+        let expired = token.with_timeout(Duration::ZERO);
+        assert!(expired.is_cancelled());
+        assert!(!token.is_cancelled());
+
+        // Between clock reads the deadline is still seen once it passed.
+        for _ in 0..2 * CLOCK_CHECK_INTERVAL {
+            assert!(expired.is_cancelled());
+        }
+        let later = token.with_timeout(Duration::from_secs(3600));
+        assert!(!later.is_cancelled());
+        assert!(later.with_timeout(Duration::ZERO).is_cancelled());
+
+        token.cancel();
+        assert!(later.is_cancelled());
+    }
+}
@@ -0,0 +1,51 @@
+use crate::dungeon::reverse_dungeon::BiomeType;
+use crate::mc::chunk_rand::MCVersion;
+use std::fmt;
+
+// Optional last pipeline stage: world seeds expanded from a structure seed
+// differ in their upper 16 bits, which only biome generation depends on, so
+// checking the spawner's biome rejects most of them. The crate has no biome
+// generator of its own; one is plugged in through `BiomeCheck`.
+
+/// A biome source telling whether a world seed generates a desert at a position.
+pub trait BiomeCheck: fmt::Debug + Send + Sync {
+    /// Whether `world_seed` has a desert at block `x`, `z`, or `None` if unknown.
+    fn is_desert(&self, world_seed: i64, version: MCVersion, x: i32, z: i32) -> Option<bool>;
+}
+
+/// Whether `world_seed` may have `biome` at the spawner according to `check`.
+/// An unknown biome on either side keeps the seed.
+pub fn biome_matches(
+    check: &dyn BiomeCheck,
+    world_seed: i64,
+    spawner_x: i32,
+    spawner_z: i32,
+    version: MCVersion,
+    biome: BiomeType,
+) -> bool {
+    let expect_desert = match biome {
+        BiomeType::Desert => true,
+        BiomeType::NotDesert => false,
+        BiomeType::Unknown => return true,
+    };
+    check
+        .is_desert(world_seed, version, spawner_x, spawner_z)
+        .is_none_or(|is_desert| is_desert == expect_desert)
+}
+
+/// Stage after [`world_seeds_from_structure_seed`](crate::dungeon::reverse_dungeon::world_seeds_from_structure_seed):
+/// keep the world seeds whose biome at the spawner matches `biome`.
+pub fn filter_world_seeds_by_biome(
+    world_seeds: &[i64],
+    spawner_x: i32,
+    spawner_z: i32,
+    version: MCVersion,
+    biome: BiomeType,
+    check: &dyn BiomeCheck,
+) -> Vec<i64> {
+    world_seeds
+        .iter()
+        .copied()
+        .filter(|&ws| biome_matches(check, ws, spawner_x, spawner_z, version, biome))
+        .collect()
+}
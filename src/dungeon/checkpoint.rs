@@ -0,0 +1,288 @@
+use crate::cancel::CancelToken;
+use crate::dungeon::biome_check::BiomeCheck;
+use crate::dungeon::crack_request::CrackRequest;
+use crate::dungeon::reverse_dungeon::{self, BiomeType, CrackResult, SpawnerMob};
+use crate::dungeon::seed_filter::WorldSeedFilter;
+use crate::mc::chunk_rand::MCVersion;
+use crate::mc::end_pillars::{PillarLayout, PILLAR_COUNT};
+use crate::mc::structure_filter::{KnownStructure, StructureType};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+// Checkpointing for long cracks.
+//
+// Depth-0 branches are cracked in order, so the progress of a run is the
+// index of the first branch not yet done plus the seeds found before it.
+// The reduced lattice is not stored: it is rebuilt deterministically from the
+// request on resume, and the saved branch counts make sure it came out the same.
+
+/// Minimum time between two checkpoint saves.
+const SAVE_INTERVAL: Duration = Duration::from_secs(30);
+/// Version of the checkpoint file layout.
+const FILE_FORMAT: u32 = 1;
+
+/// The state of a partially completed crack.
+#[derive(Clone, Debug)]
+pub struct Checkpoint {
+    /// The request being cracked, with its salts resolved.
+    pub(crate) request: CrackRequest,
+    pub(crate) branches_per_possibility: Vec<i64>,
+    /// Global index of the first branch not yet cracked.
+    pub(crate) next_branch: i64,
+    pub(crate) dungeon_seeds: BTreeSet<i64>,
+    /// Structure seeds found so far, already verified.
+    pub(crate) structure_seeds: BTreeSet<i64>,
+}
+
+impl Checkpoint {
+    /// A checkpoint at the start of `request`, running its prepare step.
+    pub fn new(request: &CrackRequest) -> Result<Self, String> {
+        let prepared = request.prepare()?;
+        let request = request.clone().salts(request.resolved_salts());
+        Ok(Checkpoint {
+            request,
+            branches_per_possibility: prepared.branches_per_possibility,
+            next_branch: 0,
+            dungeon_seeds: BTreeSet::new(),
+            structure_seeds: BTreeSet::new(),
+        })
+    }
+
+    /// Re-attach the biome check of the original request after [`load`](Self::load).
+    pub fn biome_check(mut self, check: impl BiomeCheck + 'static) -> Self {
+        self.request = self.request.biome_check(check);
+        self
+    }
+
+    pub fn request(&self) -> &CrackRequest {
+        &self.request
+    }
+
+    pub fn next_branch(&self) -> i64 {
+        self.next_branch
+    }
+
+    pub fn total_branches(&self) -> i64 {
+        self.branches_per_possibility.iter().sum()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.next_branch >= self.total_branches()
+    }
+
+    /// The seeds found so far, `cancelled` unless every branch is done.
+    pub fn result(&self) -> CrackResult {
+        let world_seeds: BTreeSet<i64> = self
+            .structure_seeds
+            .iter()
+            .flat_map(|&ss| self.request.world_seeds_for(ss))
+            .collect();
+        CrackResult {
+            dungeon_seeds: self.dungeon_seeds.iter().copied().collect(),
+            structure_seeds: self.structure_seeds.iter().copied().collect(),
+            world_seeds: world_seeds.into_iter().collect(),
+            cancelled: !self.is_complete(),
+        }
+    }
+
+    /// Write the checkpoint to `path` as JSON. The file is replaced
+    /// atomically, so a crash mid-save leaves the previous checkpoint.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        let request = &self.request;
+        let file = CheckpointFile {
+            format: FILE_FORMAT,
+            spawner_x: request.spawner_x,
+            spawner_y: request.spawner_y,
+            spawner_z: request.spawner_z,
+            version: request.version.to_string(),
+            biome: biome_name(request.biome).to_string(),
+            floor_sequence: request.floor_sequence.clone(),
+            mob: request.mob.map(|mob| mob_name(mob).to_string()),
+            threads: request.threads,
+            salts: request.resolved_salts(),
+            max_possibilities: request.max_possibilities,
+            seed_min: request.world_seed_filter.min,
+            seed_max: request.world_seed_filter.max,
+            seed_bits: request.world_seed_filter.bits.clone(),
+            text_seeds: request.world_seed_filter.text_seeds.clone(),
+            pillar_heights: request.end_pillars.as_ref().map(|layout| layout.heights),
+            structures: request
+                .known_structures
+                .iter()
+                .map(|known| (structure_name(known.structure).to_string(), known.chunk_x, known.chunk_z))
+                .collect(),
+            slime_chunks: request.slime_chunks.clone(),
+            branches_per_possibility: self.branches_per_possibility.clone(),
+            next_branch: self.next_branch,
+            dungeon_seeds: self.dungeon_seeds.clone(),
+            structure_seeds: self.structure_seeds.clone(),
+        };
+        let json = serde_json::to_string(&file).map_err(|e| format!("Failed to serialize checkpoint: {}", e))?;
+
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, json).map_err(|e| format!("Failed to write checkpoint {}: {}", tmp.display(), e))?;
+        fs::rename(&tmp, path).map_err(|e| format!("Failed to write checkpoint {}: {}", path.display(), e))
+    }
+
+    /// Read a checkpoint written by [`save`](Self::save).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path).map_err(|e| format!("Failed to read checkpoint {}: {}", path.display(), e))?;
+        let file: CheckpointFile =
+            serde_json::from_str(&json).map_err(|e| format!("Invalid checkpoint {}: {}", path.display(), e))?;
+        if file.format != FILE_FORMAT {
+            return Err(format!("Unsupported checkpoint format {}", file.format));
+        }
+
+        let version: MCVersion =
+            file.version.parse().map_err(|e| format!("Invalid checkpoint {}: {}", path.display(), e))?;
+        let biome = BIOMES
+            .iter()
+            .find(|(_, name)| *name == file.biome)
+            .map(|&(biome, _)| biome)
+            .ok_or_else(|| format!("Unknown biome in checkpoint: {}", file.biome))?;
+
+        let mut request = CrackRequest::new(file.spawner_x, file.spawner_y, file.spawner_z)
+            .version(version)
+            .biome(biome)
+            .floor(file.floor_sequence)
+            .salts(file.salts)
+            .max_possibilities(file.max_possibilities)
+            .world_seed_filter(WorldSeedFilter {
+                min: file.seed_min,
+                max: file.seed_max,
+                bits: file.seed_bits,
+                text_seeds: file.text_seeds,
+            });
+        if let Some(name) = file.mob {
+            let mob = MOBS
+                .iter()
+                .find(|(_, mob_name)| *mob_name == name)
+                .map(|&(mob, _)| mob)
+                .ok_or_else(|| format!("Unknown mob in checkpoint: {}", name))?;
+            request = request.mob(mob);
+        }
+        if let Some(heights) = file.pillar_heights {
+            request = request.end_pillars(PillarLayout { heights });
+        }
+        for (name, chunk_x, chunk_z) in file.structures {
+            let structure = STRUCTURES
+                .iter()
+                .find(|(_, structure_name)| *structure_name == name)
+                .map(|&(structure, _)| structure)
+                .ok_or_else(|| format!("Unknown structure in checkpoint: {}", name))?;
+            request = request.known_structure(KnownStructure::new(structure, chunk_x, chunk_z));
+        }
+        for (chunk_x, chunk_z) in file.slime_chunks {
+            request = request.slime_chunk(chunk_x, chunk_z);
+        }
+        if let Some(threads) = file.threads {
+            request = request.threads(threads);
+        }
+
+        Ok(Checkpoint {
+            request,
+            branches_per_possibility: file.branches_per_possibility,
+            next_branch: file.next_branch,
+            dungeon_seeds: file.dungeon_seeds,
+            structure_seeds: file.structure_seeds,
+        })
+    }
+}
+
+/// Continue the crack recorded in `checkpoint`, saving it to `path` at most
+/// every 30 seconds and once more when the crack completes or is cancelled.
+/// A cancelled run can be resumed again from the saved file.
+pub fn crack_dungeon_resume(
+    mut checkpoint: Checkpoint,
+    path: impl AsRef<Path>,
+    cancel: &CancelToken,
+) -> Result<CrackResult, String> {
+    let path = path.as_ref();
+    let request = checkpoint.request.clone();
+    request.in_thread_pool(|| {
+        let mut last_save = Instant::now();
+        reverse_dungeon::crack_resume_core(&mut checkpoint, cancel, &mut |checkpoint| {
+            if last_save.elapsed() >= SAVE_INTERVAL {
+                checkpoint.save(path)?;
+                last_save = Instant::now();
+            }
+            Ok(())
+        })?;
+        checkpoint.save(path)?;
+        Ok(checkpoint.result())
+    })
+}
+
+/// On-disk layout of a [`Checkpoint`].
+#[derive(Serialize, Deserialize)]
+struct CheckpointFile {
+    format: u32,
+    spawner_x: i32,
+    spawner_y: i32,
+    spawner_z: i32,
+    version: String,
+    biome: String,
+    floor_sequence: String,
+    mob: Option<String>,
+    threads: Option<usize>,
+    salts: Vec<i64>,
+    max_possibilities: usize,
+    #[serde(default)]
+    seed_min: Option<i64>,
+    #[serde(default)]
+    seed_max: Option<i64>,
+    #[serde(default)]
+    seed_bits: Vec<(i64, i64)>,
+    #[serde(default)]
+    text_seeds: Option<BTreeSet<i64>>,
+    #[serde(default)]
+    pillar_heights: Option<[Option<i32>; PILLAR_COUNT]>,
+    #[serde(default)]
+    structures: Vec<(String, i32, i32)>,
+    #[serde(default)]
+    slime_chunks: Vec<(i32, i32)>,
+    branches_per_possibility: Vec<i64>,
+    next_branch: i64,
+    dungeon_seeds: BTreeSet<i64>,
+    structure_seeds: BTreeSet<i64>,
+}
+
+const BIOMES: [(BiomeType, &str); 3] = [
+    (BiomeType::NotDesert, "notdesert"),
+    (BiomeType::Desert, "desert"),
+    (BiomeType::Unknown, "unknown"),
+];
+
+const MOBS: [(SpawnerMob, &str); 3] = [
+    (SpawnerMob::Skeleton, "skeleton"),
+    (SpawnerMob::Zombie, "zombie"),
+    (SpawnerMob::Spider, "spider"),
+];
+
+const STRUCTURES: [(StructureType, &str); 8] = [
+    (StructureType::Village, "village"),
+    (StructureType::DesertPyramid, "desert_pyramid"),
+    (StructureType::JungleTemple, "jungle_temple"),
+    (StructureType::SwampHut, "swamp_hut"),
+    (StructureType::Igloo, "igloo"),
+    (StructureType::OceanMonument, "ocean_monument"),
+    (StructureType::WoodlandMansion, "woodland_mansion"),
+    (StructureType::BuriedTreasure, "buried_treasure"),
+];
+
+fn biome_name(biome: BiomeType) -> &'static str {
+    BIOMES.iter().find(|(b, _)| *b == biome).unwrap().1
+}
+
+fn mob_name(mob: SpawnerMob) -> &'static str {
+    MOBS.iter().find(|(m, _)| *m == mob).unwrap().1
+}
+
+fn structure_name(structure: StructureType) -> &'static str {
+    STRUCTURES.iter().find(|(s, _)| *s == structure).unwrap().1
+}
@@ -0,0 +1,302 @@
+use crate::cancel::CancelToken;
+use crate::dungeon::biome_check::{biome_matches, BiomeCheck};
+use crate::dungeon::dungeon_data_parser::DEFAULT_MAX_POSSIBILITIES;
+use crate::dungeon::reverse_dungeon::{
+    self, get_salts, get_sequence, BiomeType, CrackProgress, CrackResult, FloorSize, PrepareResult, SpawnerMob,
+};
+use crate::dungeon::seed_filter::WorldSeedFilter;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::dungeon::checkpoint::{crack_dungeon_resume, Checkpoint};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::dungeon::reverse_dungeon::SeedStream;
+use crate::mc::chunk_rand::MCVersion;
+use crate::mc::end_pillars::PillarLayout;
+use crate::mc::slime::is_slime_chunk;
+use crate::mc::structure_filter::{structure_matches, KnownStructure};
+use crate::reverser::random_reverser::PreparedReverser;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+use std::sync::Arc;
+
+/// Everything needed to crack one dungeon, built up one option at a time:
+///
+/// ```no_run
+/// # use dungeon_cracker::dungeon::crack_request::CrackRequest;
+/// # use dungeon_cracker::dungeon::reverse_dungeon::{BiomeType, SpawnerMob};
+/// # use dungeon_cracker::mc::chunk_rand::MCVersion;
+/// # let sequence = "101000111000011001010010100000001010000010001110100000000011000";
+/// let result = CrackRequest::new(126, 132, -117)
+///     .version(MCVersion::V1_16)
+///     .biome(BiomeType::Desert)
+///     .floor(sequence)
+///     .mob(SpawnerMob::Skeleton)
+///     .crack()?;
+/// # Ok::<(), String>(())
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CrackRequest {
+    pub(crate) spawner_x: i32,
+    pub(crate) spawner_y: i32,
+    pub(crate) spawner_z: i32,
+    pub(crate) version: MCVersion,
+    pub(crate) biome: BiomeType,
+    pub(crate) floor_sequence: String,
+    pub(crate) mob: Option<SpawnerMob>,
+    pub(crate) threads: Option<usize>,
+    pub(crate) salts: Option<Vec<i64>>,
+    pub(crate) max_possibilities: usize,
+    pub(crate) world_seed_filter: WorldSeedFilter,
+    pub(crate) end_pillars: Option<PillarLayout>,
+    pub(crate) known_structures: Vec<KnownStructure>,
+    pub(crate) slime_chunks: Vec<(i32, i32)>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) prepared_reversers: Vec<PreparedReverser>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) biome_check: Option<Arc<dyn BiomeCheck>>,
+}
+
+impl CrackRequest {
+    /// A request for the spawner at the given block position. Defaults to
+    /// 1.17, an unknown biome, an empty floor and no mob.
+    pub fn new(spawner_x: i32, spawner_y: i32, spawner_z: i32) -> Self {
+        CrackRequest {
+            spawner_x,
+            spawner_y,
+            spawner_z,
+            version: MCVersion::V1_17,
+            biome: BiomeType::Unknown,
+            floor_sequence: String::new(),
+            mob: None,
+            threads: None,
+            salts: None,
+            max_possibilities: DEFAULT_MAX_POSSIBILITIES,
+            world_seed_filter: WorldSeedFilter::new(),
+            end_pillars: None,
+            known_structures: Vec::new(),
+            slime_chunks: Vec::new(),
+            prepared_reversers: Vec::new(),
+            biome_check: None,
+        }
+    }
+
+    pub fn version(mut self, version: MCVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn biome(mut self, biome: BiomeType) -> Self {
+        self.biome = biome;
+        self
+    }
+
+    /// Set the floor from a sequence string (see [`get_sequence`]).
+    pub fn floor(mut self, floor_sequence: impl Into<String>) -> Self {
+        self.floor_sequence = floor_sequence.into();
+        self
+    }
+
+    /// Set the floor from a `[z][x]` tile grid.
+    pub fn floor_grid(self, floor: &[[u8; 9]; 9], floor_size: FloorSize) -> Self {
+        self.floor(get_sequence(floor, floor_size))
+    }
+
+    /// Constrain the spawner mob, see [`reverse_dungeon::crack_dungeon`].
+    pub fn mob(mut self, mob: SpawnerMob) -> Self {
+        self.mob = Some(mob);
+        self
+    }
+
+    /// Number of threads used to enumerate lattice points.
+    /// Only has an effect with the `rayon` feature.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Use these decorator salts (1.13+) instead of the vanilla ones from
+    /// [`get_salts`], e.g. for modded or data-pack-changed generation.
+    pub fn salts(mut self, salts: impl Into<Vec<i64>>) -> Self {
+        self.salts = Some(salts.into());
+        self
+    }
+
+    /// Give up on floors whose unknown tiles expand into more than this many
+    /// interpretations. Defaults to [`DEFAULT_MAX_POSSIBILITIES`].
+    pub fn max_possibilities(mut self, max_possibilities: usize) -> Self {
+        self.max_possibilities = max_possibilities;
+        self
+    }
+
+    /// Only return world seeds that pass `filter`.
+    pub fn world_seed_filter(mut self, filter: WorldSeedFilter) -> Self {
+        self.world_seed_filter = filter;
+        self
+    }
+
+    /// Drop seeds whose End pillars differ from `layout`. Only applies from
+    /// 1.9, before which the pillars are placed randomly.
+    pub fn end_pillars(mut self, layout: PillarLayout) -> Self {
+        self.end_pillars = Some(layout);
+        self
+    }
+
+    /// Drop seeds that do not place `structure` where it was seen. Can be
+    /// given several times; each known structure cuts most wrong seeds.
+    pub fn known_structure(mut self, structure: KnownStructure) -> Self {
+        self.known_structures.push(structure);
+        self
+    }
+
+    /// Drop seeds for which chunk `chunk_x`, `chunk_z` is not a slime chunk.
+    pub fn slime_chunk(mut self, chunk_x: i32, chunk_z: i32) -> Self {
+        self.slime_chunks.push((chunk_x, chunk_z));
+        self
+    }
+
+    /// Reuse the lattices reduced by [`prepare`](Self::prepare) for the same
+    /// request, from [`PrepareResult::prepared_reversers`], so partial cracks
+    /// skip LLL.
+    pub fn prepared_reversers(mut self, prepared: Vec<PreparedReverser>) -> Self {
+        self.prepared_reversers = prepared;
+        self
+    }
+
+    /// Drop world seeds whose biome at the spawner does not match
+    /// [`biome`](Self::biome) according to `check`. Not saved in checkpoints.
+    pub fn biome_check(mut self, check: impl BiomeCheck + 'static) -> Self {
+        self.biome_check = Some(Arc::new(check));
+        self
+    }
+
+    /// The decorator salts this request cracks with.
+    pub fn resolved_salts(&self) -> Vec<i64> {
+        self.salts.clone().unwrap_or_else(|| get_salts(self.version, self.biome))
+    }
+
+    /// Run the crack to completion.
+    pub fn crack(&self) -> Result<CrackResult, String> {
+        self.crack_with_progress(|_| {})
+    }
+
+    /// Run the crack, reporting progress through `on_progress`.
+    pub fn crack_with_progress(&self, on_progress: impl FnMut(CrackProgress)) -> Result<CrackResult, String> {
+        self.crack_cancellable(&CancelToken::new(), on_progress)
+    }
+
+    /// Run the crack until it completes or `cancel` is triggered.
+    pub fn crack_cancellable(
+        &self,
+        cancel: &CancelToken,
+        mut on_progress: impl FnMut(CrackProgress),
+    ) -> Result<CrackResult, String> {
+        self.in_thread_pool(|| reverse_dungeon::crack_dungeon_core(self, cancel, &mut on_progress, &mut |_| {}))
+    }
+
+    /// Run the crack on a background thread, yielding seeds as they are found.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn crack_iter(&self) -> SeedStream {
+        reverse_dungeon::spawn_seed_stream(self.clone())
+    }
+
+    /// Run the crack, saving a [`Checkpoint`] to `path` as it goes so an
+    /// interrupted run can be continued with [`crack_dungeon_resume`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn crack_checkpointed(&self, path: impl AsRef<Path>, cancel: &CancelToken) -> Result<CrackResult, String> {
+        crack_dungeon_resume(Checkpoint::new(self)?, path, cancel)
+    }
+
+    /// Count the depth-0 branches that can be split across workers.
+    pub fn prepare(&self) -> Result<PrepareResult, String> {
+        reverse_dungeon::prepare_crack_core(self)
+    }
+
+    /// Crack only the depth-0 branches in `[branch_start, branch_end)`.
+    pub fn crack_partial(&self, branch_start: i64, branch_end: i64) -> Result<CrackResult, String> {
+        reverse_dungeon::crack_partial_core(self, branch_start, branch_end)
+    }
+
+    /// The world seeds of `structure_seed` that pass the filter, the End
+    /// pillar layout, the known structures and slime chunks and the biome
+    /// check, if there are any.
+    pub(crate) fn world_seeds_for(&self, structure_seed: i64) -> Vec<i64> {
+        if !self.world_seed_filter.may_accept_structure_seed(structure_seed) {
+            return Vec::new();
+        }
+        if !self.known_structures.iter().all(|known| structure_matches(structure_seed, known, self.version))
+            || !self.slime_chunks.iter().all(|&(x, z)| is_slime_chunk(structure_seed, x, z))
+        {
+            return Vec::new();
+        }
+        if let Some(layout) = &self.end_pillars {
+            if !self.version.is_older_than(MCVersion::V1_9) && !layout.matches(structure_seed) {
+                return Vec::new();
+            }
+        }
+        let mut world_seeds = self.world_seed_filter.candidates(structure_seed);
+        world_seeds.retain(|&ws| {
+            self.biome_check.as_deref().is_none_or(|check| {
+                biome_matches(check, ws, self.spawner_x, self.spawner_z, self.version, self.biome)
+            })
+        });
+        world_seeds
+    }
+
+    /// Run `f` on a dedicated pool when a thread count was requested.
+    pub(crate) fn in_thread_pool(
+        &self,
+        f: impl FnOnce() -> Result<CrackResult, String>,
+    ) -> Result<CrackResult, String> {
+        #[cfg(feature = "rayon")]
+        if let Some(threads) = self.threads {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .map_err(|e| format!("Failed to start thread pool: {}", e))?;
+            return crate::lattice::enumerate::with_thread_pool(std::sync::Arc::new(pool), f);
+        }
+        f()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dungeon::reverse_dungeon::SALT_1_16;
+    use crate::dungeon::simulate::simulate_chunk;
+    use crate::math::mth;
+    use crate::mc::seed_text::{hash_seed, Wordlist};
+
+    /// Crack the first dungeon attempt of chunk (3, -5) in the world `world_seed`.
+    fn crack_generated(world_seed: i64, filter: WorldSeedFilter) -> CrackResult {
+        let dungeon = &simulate_chunk(world_seed, 3, -5, MCVersion::V1_16, &[SALT_1_16])[0];
+        CrackRequest::new(dungeon.x, dungeon.y, dungeon.z)
+            .version(MCVersion::V1_16)
+            .biome(BiomeType::NotDesert)
+            .floor_grid(&dungeon.full_floor(), dungeon.floor_size)
+            .world_seed_filter(filter)
+            .crack()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_wordlist_finds_text_seed() {
+        let wordlist = Wordlist::from_words(["world", "hello", "seed"]);
+        let result = crack_generated(hash_seed("hello"), WorldSeedFilter::new().wordlist(&wordlist));
+        assert_eq!(result.world_seeds, [hash_seed("hello")]);
+    }
+
+    #[test]
+    fn test_pinned_filter_keeps_typed_seed() {
+        // 12345 is no nextLong output, so only the sister seeds can find it.
+        let seed = 12345;
+        let range = CrackRequest::new(0, 0, 0).world_seed_filter(WorldSeedFilter::new().range(-100_000, 100_000));
+        assert_eq!(range.world_seeds_for(seed), [seed]);
+        let upper_bits = CrackRequest::new(0, 0, 0).world_seed_filter(WorldSeedFilter::new().bits(!mth::MASK_48, 0));
+        assert_eq!(upper_bits.world_seeds_for(seed), [seed]);
+        let loose = CrackRequest::new(0, 0, 0).world_seed_filter(WorldSeedFilter::new().positive());
+        assert!(!loose.world_seeds_for(seed).contains(&seed));
+    }
+}
@@ -1,5 +1,8 @@
 use super::reverser_instruction::{InstructionType, ReverserInstruction};
 
+/// Default cap on the instruction lists a floor may expand into.
+pub const DEFAULT_MAX_POSSIBILITIES: usize = 128;
+
 /// Port of Kludwisz DungeonDataParser.
 /// Parses a floor tile sequence into ReverserInstructions, then generates
 /// all possible instruction lists (due to MUTABLE_SKIPs branching).
@@ -7,56 +10,85 @@ pub struct DungeonDataParser;
 
 impl DungeonDataParser {
     /// Parse the floor sequence string and generate all possible instruction lists.
-    /// Returns None if there are too many possibilities (>128).
-    pub fn get_all_possibilities(sequence: &str) -> Option<Vec<Vec<ReverserInstruction>>> {
-        // Build initial instruction list, merging consecutive unknowns
+    /// Returns None if there are more than `max_possibilities` of them.
+    pub fn get_all_possibilities(sequence: &str, max_possibilities: usize) -> Option<Vec<Vec<ReverserInstruction>>> {
+        if Self::possibility_count(sequence) > max_possibilities as u64 {
+            return None;
+        }
+        let instructions = Self::parse_instructions(sequence);
+
+        // Generate all possibilities by expanding MUTABLE_SKIPs
+        let mut result: Vec<Vec<ReverserInstruction>> = Vec::new();
+        Self::generate_recursive(&instructions, &mut Vec::new(), 0, &mut result);
+        Some(result)
+    }
+
+    /// Parse the floor sequence string into one instruction list, with
+    /// trailing skips removed. MUTABLE_SKIPs are left unexpanded.
+    pub fn parse_instructions(sequence: &str) -> Vec<ReverserInstruction> {
+        // Build initial instruction list. Runs of unknowns (across air) only
+        // matter through their total call count, so they are merged into one
+        // skip; expanding them separately would give equivalent lists.
         let mut instructions: Vec<ReverserInstruction> = Vec::new();
-        let mut last_char: Option<char> = None;
 
         for ch in sequence.chars() {
-            if ch == '2' {
+            let index = ch.to_digit(10).unwrap() as u8;
+            let instr = match ReverserInstruction::from_tile_index(index) {
+                Some(instr) => instr,
                 // Air: doesn't produce a call but doesn't interrupt sequences
-                continue;
-            }
+                None => continue,
+            };
 
-            if !instructions.is_empty()
-                && (ch == '3' || ch == '4')
-                && last_char == Some(ch)
-            {
-                // Merge consecutive unknowns
-                let last = instructions.last_mut().unwrap();
-                last.max_call_count += 1;
-            } else {
-                let index = ch.to_digit(10).unwrap() as u8;
-                if let Some(instr) = ReverserInstruction::from_tile_index(index) {
-                    instructions.push(instr);
+            match instructions.last_mut() {
+                Some(last) if is_skip(last) && is_skip(&instr) => {
+                    last.min_call_count += instr.min_call_count;
+                    last.max_call_count += instr.max_call_count;
+                    last.instruction_type = if last.min_call_count == last.max_call_count {
+                        InstructionType::Skip
+                    } else {
+                        InstructionType::MutableSkip
+                    };
                 }
+                _ => instructions.push(instr),
             }
-
-            last_char = Some(ch);
         }
 
         // Remove trailing SKIP and MUTABLE_SKIP instructions
-        while let Some(last) = instructions.last() {
-            if last.instruction_type == InstructionType::Skip
-                || last.instruction_type == InstructionType::MutableSkip
-            {
-                instructions.pop();
-            } else {
-                break;
-            }
+        while instructions.last().is_some_and(is_skip) {
+            instructions.pop();
         }
 
-        // Generate all possibilities by expanding MUTABLE_SKIPs
-        let mut result: Vec<Vec<ReverserInstruction>> = Vec::new();
-        let mut counter = 0;
-        Self::generate_recursive(&instructions, &mut Vec::new(), 0, &mut result, &mut counter);
+        instructions
+    }
 
-        if counter > 128 {
-            return None;
-        }
+    /// Number of instruction lists [`get_all_possibilities`](Self::get_all_possibilities)
+    /// would expand the sequence into, without the cap (saturating).
+    pub fn possibility_count(sequence: &str) -> u64 {
+        Self::parse_instructions(sequence)
+            .iter()
+            .filter(|instr| instr.instruction_type == InstructionType::MutableSkip)
+            .fold(1u64, |count, instr| {
+                count.saturating_mul((instr.max_call_count - instr.min_call_count + 1) as u64)
+            })
+    }
 
-        Some(result)
+    /// Range of RNG calls made by the floor tiles after the last known tile
+    /// (those trimmed from every instruction list), as `(min, max)`.
+    pub fn trailing_call_range(sequence: &str) -> (i32, i32) {
+        let mut min = 0;
+        let mut max = 0;
+        for ch in sequence.chars().rev() {
+            let index = ch.to_digit(10).unwrap() as u8;
+            match ReverserInstruction::from_tile_index(index) {
+                Some(instr) if is_skip(&instr) => {
+                    min += instr.min_call_count;
+                    max += instr.max_call_count;
+                }
+                Some(_) => break,
+                None => {}
+            }
+        }
+        (min, max)
     }
 
     fn generate_recursive(
@@ -64,12 +96,7 @@ impl DungeonDataParser {
         current: &mut Vec<ReverserInstruction>,
         ix: usize,
         result: &mut Vec<Vec<ReverserInstruction>>,
-        counter: &mut i32,
     ) {
-        if *counter > 128 {
-            return;
-        }
-
         let mut idx = ix;
         while idx < original.len() {
             let instr = &original[idx];
@@ -86,10 +113,9 @@ impl DungeonDataParser {
                         ));
                     }
                     if idx + 1 < original.len() {
-                        Self::generate_recursive(original, &mut new_list, idx + 1, result, counter);
+                        Self::generate_recursive(original, &mut new_list, idx + 1, result);
                     } else {
                         result.push(new_list);
-                        *counter += 1;
                     }
                 }
                 return;
@@ -98,9 +124,12 @@ impl DungeonDataParser {
                 idx += 1;
                 if idx >= original.len() {
                     result.push(current.clone());
-                    *counter += 1;
                 }
             }
         }
     }
 }
+
+fn is_skip(instr: &ReverserInstruction) -> bool {
+    instr.instruction_type == InstructionType::Skip || instr.instruction_type == InstructionType::MutableSkip
+}
@@ -0,0 +1,108 @@
+use crate::dungeon::reverse_dungeon::{get_sequence, FloorSize};
+use crate::dungeon::reverser_instruction::MAX_TILE_INDEX;
+use std::fmt;
+
+/// An observed dungeon floor: a `[z][x]` tile grid and its size.
+///
+/// The text form is one line per z row with one digit per tile (see
+/// [`ReverserInstruction::from_tile_index`](crate::dungeon::reverser_instruction::ReverserInstruction::from_tile_index)),
+/// which [`Floor::parse`] reads and `Display` writes back.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Floor {
+    tiles: [[u8; 9]; 9],
+    size: FloorSize,
+}
+
+impl Floor {
+    /// Wrap a `[z][x]` grid; cells outside `size` are ignored.
+    pub fn from_grid(tiles: [[u8; 9]; 9], size: FloorSize) -> Self {
+        Floor { tiles, size }
+    }
+
+    /// Parse a multi-line grid of 7 or 9 rows, its size found by
+    /// [`FloorSize::detect`]. Blank lines and whitespace inside rows are
+    /// ignored. Every bad row and cell is reported, one per line, e.g.
+    /// `row 3 col 5: invalid symbol 'x'`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let rows: Vec<Vec<char>> = text
+            .lines()
+            .map(|line| line.chars().filter(|c| !c.is_whitespace()).collect::<Vec<char>>())
+            .filter(|row| !row.is_empty())
+            .collect();
+
+        let size = FloorSize::detect(&rows.iter().map(|row| row.len()).collect::<Vec<_>>())?;
+        let width = size.x_max() - size.x_min();
+
+        let mut errors = Vec::new();
+        let mut tiles = [[2u8; 9]; 9];
+        for (row_idx, row) in rows.iter().enumerate() {
+            if row.len() != width {
+                errors.push(format!("row {}: expected {} tiles, got {}", row_idx + 1, width, row.len()));
+                continue;
+            }
+            for (col_idx, &ch) in row.iter().enumerate() {
+                match ch.to_digit(10) {
+                    Some(d) if d <= MAX_TILE_INDEX as u32 => {
+                        tiles[size.z_min() + row_idx][size.x_min() + col_idx] = d as u8;
+                    }
+                    _ => errors.push(format!("row {} col {}: invalid symbol '{}'", row_idx + 1, col_idx + 1, ch)),
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors.join("\n"));
+        }
+        Ok(Floor { tiles, size })
+    }
+
+    pub fn size(&self) -> FloorSize {
+        self.size
+    }
+
+    /// The `[z][x]` grid, 2 (air) outside the floor when parsed.
+    pub fn grid(&self) -> &[[u8; 9]; 9] {
+        &self.tiles
+    }
+
+    /// Tile at grid position `x`, `z`.
+    pub fn tile(&self, x: usize, z: usize) -> u8 {
+        self.tiles[z][x]
+    }
+
+    /// The column-major sequence string the cracker takes.
+    pub fn to_sequence(&self) -> String {
+        get_sequence(&self.tiles, self.size)
+    }
+}
+
+impl fmt::Display for Floor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for z in self.size.z_min()..self.size.z_max() {
+            if z != self.size.z_min() {
+                writeln!(f)?;
+            }
+            for x in self.size.x_min()..self.size.x_max() {
+                write!(f, "{}", self.tiles[z][x])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_round_trip() {
+        let text = "0000010\n0000000\n0000001\n0110100\n0000011\n0000001\n1000100\n0000000\n0000000";
+        let floor = Floor::parse(text).unwrap();
+        assert_eq!(floor.size(), FloorSize::_7x9);
+        assert_eq!(floor.to_string(), text);
+        assert_eq!(Floor::parse(&floor.to_string()).unwrap(), floor);
+
+        let err = Floor::parse(&text.replacen("0110100", "01x0100", 1)).unwrap_err();
+        assert_eq!(err, "row 4 col 3: invalid symbol 'x'");
+    }
+}
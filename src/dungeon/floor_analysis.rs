@@ -0,0 +1,139 @@
+use crate::dungeon::dungeon_data_parser::DungeonDataParser;
+use crate::dungeon::reverse_dungeon::{FloorSize, POSITION_INFO_BITS};
+use crate::dungeon::reverser_instruction::{InstructionType, ReverserInstruction, MAX_TILE_INDEX};
+
+// Per-tile breakdown of the information a floor gives the cracker.
+//
+// The crack needs roughly 48 bits (less the possibility count) to single out
+// the structure seed. Known tiles add their bits directly; for each unknown
+// tile the floor is re-scored as if it had been dug out as mossy or cobble,
+// which also accounts for the floor interpretations it stops branching into.
+
+/// Chance that a solid floor tile is mossy (`nextInt(4) != 0`).
+const MOSSY_CHANCE: f32 = 0.75;
+
+/// Information contributed by one observed tile.
+#[derive(Clone, Debug)]
+pub struct TileInfo {
+    /// Position in the `[z][x]` grid used by [`get_sequence`](crate::dungeon::reverse_dungeon::get_sequence).
+    pub x: usize,
+    pub z: usize,
+    /// Tile index: 0 = mossy, 1 = cobble, 2 = air.
+    pub tile: u8,
+    pub info_bits: f32,
+}
+
+/// Information an unknown tile would add once uncovered.
+#[derive(Clone, Debug)]
+pub struct UnknownTileGain {
+    pub x: usize,
+    pub z: usize,
+    /// Tile index of an unknown or covered tile (3 to 7).
+    pub tile: u8,
+    /// Change in effective bits if the tile turns out mossy.
+    pub if_mossy: f32,
+    /// Change in effective bits if the tile turns out cobble.
+    pub if_cobble: f32,
+    /// Expected change assuming the tile is solid.
+    pub expected_gain: f32,
+}
+
+/// Result of [`analyze_floor`].
+#[derive(Clone, Debug)]
+pub struct FloorAnalysis {
+    /// Position and floor bits, as reported by the prepare step.
+    pub info_bits: f32,
+    /// Floor interpretations the unknown tiles expand into.
+    pub possibilities: u64,
+    /// `info_bits` less the bits spent trying every interpretation.
+    pub effective_bits: f32,
+    /// Every observed tile, in sequence order.
+    pub tiles: Vec<TileInfo>,
+    /// Every unknown tile, most informative first.
+    pub suggestions: Vec<UnknownTileGain>,
+}
+
+/// Break down the information of a floor sequence tile by tile, and rank
+/// the unknown tiles by how much digging them out would help.
+pub fn analyze_floor(floor_sequence: &str, floor_size: FloorSize) -> Result<FloorAnalysis, String> {
+    let width = floor_size.x_max() - floor_size.x_min();
+    let depth = floor_size.z_max() - floor_size.z_min();
+    let tiles: Vec<u8> = floor_sequence
+        .chars()
+        .map(|ch| match ch.to_digit(10) {
+            Some(d) if d <= MAX_TILE_INDEX as u32 => Ok(d as u8),
+            _ => Err(format!("Invalid floor tile '{}'", ch)),
+        })
+        .collect::<Result<_, _>>()?;
+    if tiles.len() != width * depth {
+        return Err(format!(
+            "Floor sequence has {} tiles, expected {} for a {}x{} floor",
+            tiles.len(),
+            width * depth,
+            width,
+            depth
+        ));
+    }
+
+    // The sequence runs x outer, z inner.
+    let position = |i: usize| (floor_size.x_min() + i / depth, floor_size.z_min() + i % depth);
+    let (info_bits, possibilities) = score(floor_sequence);
+    let effective_bits = effective(info_bits, possibilities);
+
+    let mut known = Vec::new();
+    let mut suggestions = Vec::new();
+    for (i, &tile) in tiles.iter().enumerate() {
+        let (x, z) = position(i);
+        let instr = ReverserInstruction::from_tile_index(tile);
+        let is_unknown = instr.as_ref().is_some_and(|instr| {
+            matches!(instr.instruction_type, InstructionType::Skip | InstructionType::MutableSkip)
+        });
+        if !is_unknown {
+            let info_bits = instr.map_or(0.0, |instr| instr.info_bits());
+            known.push(TileInfo { x, z, tile, info_bits });
+            continue;
+        }
+
+        let gain_as = |replacement: char| {
+            let mut uncovered: Vec<char> = floor_sequence.chars().collect();
+            uncovered[i] = replacement;
+            let (bits, count) = score(&uncovered.into_iter().collect::<String>());
+            effective(bits, count) - effective_bits
+        };
+        let if_mossy = gain_as('0');
+        let if_cobble = gain_as('1');
+        suggestions.push(UnknownTileGain {
+            x,
+            z,
+            tile,
+            if_mossy,
+            if_cobble,
+            expected_gain: MOSSY_CHANCE * if_mossy + (1.0 - MOSSY_CHANCE) * if_cobble,
+        });
+    }
+    suggestions.sort_by(|a, b| b.expected_gain.total_cmp(&a.expected_gain));
+
+    Ok(FloorAnalysis {
+        info_bits,
+        possibilities,
+        effective_bits,
+        tiles: known,
+        suggestions,
+    })
+}
+
+/// Info bits and possibility count of a floor sequence.
+fn score(floor_sequence: &str) -> (f32, u64) {
+    let floor_bits: f32 = DungeonDataParser::parse_instructions(floor_sequence)
+        .iter()
+        .map(|instr| instr.info_bits())
+        .sum();
+    (
+        POSITION_INFO_BITS + floor_bits,
+        DungeonDataParser::possibility_count(floor_sequence),
+    )
+}
+
+fn effective(info_bits: f32, possibilities: u64) -> f32 {
+    info_bits - (possibilities as f32).log2()
+}
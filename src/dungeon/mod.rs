@@ -1,3 +1,13 @@
 pub mod reverser_instruction;
 pub mod dungeon_data_parser;
 pub mod reverse_dungeon;
+pub mod crack_request;
+pub mod simulate;
+pub mod floor_analysis;
+pub mod floor;
+pub mod biome_check;
+pub mod seed_filter;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod checkpoint;
+
+pub use floor::Floor;
@@ -1,17 +1,29 @@
-use crate::dungeon::dungeon_data_parser::DungeonDataParser;
+use crate::cancel::CancelToken;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::dungeon::checkpoint::Checkpoint;
+use crate::dungeon::crack_request::CrackRequest;
+use crate::dungeon::dungeon_data_parser::{DungeonDataParser, DEFAULT_MAX_POSSIBILITIES};
 use crate::dungeon::reverser_instruction::{InstructionType, ReverserInstruction};
+use crate::dungeon::simulate::DungeonVerifier;
 use crate::lcg::lcg::LCG;
-use crate::lcg::rand::Rand;
+use crate::lcg::rand::JavaRng;
 use crate::math::mth;
 use crate::mc::chunk_rand::{ChunkRand, MCVersion};
 use crate::mc::next_long_reverser;
 use crate::mc::population_reverser;
-use crate::reverser::filtered_skip::FilteredSkip;
-use crate::reverser::random_reverser::JavaRandomReverser;
-use std::collections::HashSet;
+use crate::reverser::dynamic_program::{DynamicProgram, NextInt};
+use crate::reverser::random_reverser::{JavaRandomReverser, PreparedReverser};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::mpsc;
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread::JoinHandle;
 
 /// Biome type affecting salt values.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum BiomeType {
     NotDesert,
     Desert,
@@ -20,6 +32,7 @@ pub enum BiomeType {
 
 /// Floor size options.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum FloorSize {
     _9x9,
     _7x9,
@@ -55,21 +68,87 @@ impl FloorSize {
             _ => 9,
         }
     }
+
+    /// The size with `width` tiles along x and `depth` along z.
+    pub fn from_dimensions(width: usize, depth: usize) -> Option<FloorSize> {
+        match (width, depth) {
+            (9, 9) => Some(FloorSize::_9x9),
+            (7, 9) => Some(FloorSize::_7x9),
+            (9, 7) => Some(FloorSize::_9x7),
+            (7, 7) => Some(FloorSize::_7x7),
+            _ => None,
+        }
+    }
+
+    /// Infer the size from the tile count of each z row. The width is the
+    /// length most rows share, and a tie between lengths is ambiguous; rows
+    /// of another length are left for the caller to report.
+    pub fn detect(row_lengths: &[usize]) -> Result<FloorSize, String> {
+        let depth = row_lengths.len();
+        if depth != 7 && depth != 9 {
+            return Err(format!("expected 7 or 9 rows, got {}", depth));
+        }
+
+        let count = |len: usize| row_lengths.iter().filter(|&&l| l == len).count();
+        let width = row_lengths.iter().copied().max_by_key(|&len| count(len)).unwrap();
+        if let Some(&other) = row_lengths.iter().find(|&&len| len != width && count(len) == count(width)) {
+            return Err(format!("ambiguous floor width: as many rows have {} tiles as {}", width, other));
+        }
+        FloorSize::from_dimensions(width, depth)
+            .ok_or_else(|| format!("expected rows of 7 or 9 tiles, got {}", width))
+    }
+}
+
+/// Mob type of the dungeon spawner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SpawnerMob {
+    Skeleton,
+    Zombie,
+    Spider,
+}
+
+impl SpawnerMob {
+    /// Accepted `nextInt(4)` results: the mob table is skeleton, zombie, zombie, spider.
+    fn call_range(&self) -> (i32, i32) {
+        match self {
+            SpawnerMob::Skeleton => (0, 0),
+            SpawnerMob::Zombie => (1, 2),
+            SpawnerMob::Spider => (3, 3),
+        }
+    }
 }
 
-/// The result of a dungeon cracking operation.
+/// The result of a dungeon cracking operation. Each seed list is sorted
+/// ascending, so results of separate runs and workers compare directly.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CrackResult {
     pub dungeon_seeds: Vec<i64>,
     pub structure_seeds: Vec<i64>,
     pub world_seeds: Vec<i64>,
+    /// `true` if the crack was cancelled and the seed lists are incomplete.
+    pub cancelled: bool,
 }
 
 /// Info about the search space, returned by the prepare step.
+/// Branches of all possibilities form one global range, possibility by
+/// possibility, that [`crack_dungeon_partial`] workers split between them.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PrepareResult {
     pub total_branches: i64,
+    /// Depth-0 branch count of each floor interpretation, in order.
+    pub branches_per_possibility: Vec<i64>,
     pub possibilities: usize,
     pub dimensions: usize,
     pub info_bits: f32,
+    /// Expected number of lattice points to enumerate on top of the true seed,
+    /// summed over all interpretations.
+    pub expected_candidates: f64,
+    /// Chance that the true seed is not lost to `nextInt` rejection sampling.
+    pub success_chance: f64,
+    /// Reduced reverser of each floor interpretation, in order. Pass them to
+    /// [`CrackRequest::prepared_reversers`] so partial cracks skip LLL.
+    pub prepared_reversers: Vec<PreparedReverser>,
 }
 
 /// Convert a 2D floor grid (row-major: [z][x], 9x9) into the column-major sequence string.
@@ -84,8 +163,55 @@ pub fn get_sequence(floor: &[[u8; 9]; 9], floor_size: FloorSize) -> String {
     seq
 }
 
+/// Stage of a running crack, reported through [`CrackProgress`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CrackPhase {
+    /// Building the lattice and running LLL reduction for one floor interpretation.
+    LatticeReduction,
+    /// Enumerating lattice points, one depth-0 branch at a time.
+    Enumeration,
+    /// Reversing candidate dungeon seeds to structure seeds.
+    PopulationReversal,
+    /// Expanding structure seeds to world seeds.
+    WorldSeeds,
+}
+
+/// A progress report emitted by [`crack_dungeon_with_progress`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CrackProgress {
+    pub phase: CrackPhase,
+    /// 0-based index of the floor interpretation being processed.
+    pub possibility: usize,
+    /// Total number of floor interpretations.
+    pub possibilities: usize,
+    /// Depth-0 branches explored so far for the current interpretation.
+    pub branches_done: i64,
+    /// Depth-0 branches in the current interpretation (0 until known).
+    pub total_branches: i64,
+    /// Distinct dungeon seeds found so far, across all interpretations,
+    /// counted as enumeration finds them; never decreases.
+    pub candidates_found: usize,
+    /// Structure seeds found so far.
+    pub structure_seeds_found: usize,
+}
+
+/// A seed produced while a crack is still running, see [`crack_dungeon_iter`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SeedCandidate {
+    /// Internal RNG state at the start of the dungeon (not yet a world seed).
+    DungeonSeed(i64),
+    /// 48-bit structure seed.
+    StructureSeed(i64),
+    /// Full 64-bit world seed reachable through `nextLong`.
+    WorldSeed(i64),
+}
+
 /// Main cracking function.
 /// `floor_sequence` is the sequence string (from get_sequence or directly provided).
+/// `mob`, if known, adds the spawner mob roll as one more observed call. The
+/// chests generated in between make a variable number of calls, so every floor
+/// interpretation is then cracked once per possible gap; it is mostly useful
+/// for floors that do not carry enough information on their own.
 pub fn crack_dungeon(
     spawner_x: i32,
     spawner_y: i32,
@@ -93,128 +219,157 @@ pub fn crack_dungeon(
     version: MCVersion,
     biome: BiomeType,
     floor_sequence: &str,
+    mob: Option<SpawnerMob>,
+) -> Result<CrackResult, String> {
+    request_for(spawner_x, spawner_y, spawner_z, version, biome, floor_sequence, mob).crack()
+}
+
+/// Same as [`crack_dungeon`], but calls `on_progress` as the crack moves
+/// through its phases and after every explored depth-0 branch.
+#[allow(clippy::too_many_arguments)]
+pub fn crack_dungeon_with_progress(
+    spawner_x: i32,
+    spawner_y: i32,
+    spawner_z: i32,
+    version: MCVersion,
+    biome: BiomeType,
+    floor_sequence: &str,
+    mob: Option<SpawnerMob>,
+    on_progress: impl FnMut(CrackProgress),
+) -> Result<CrackResult, String> {
+    request_for(spawner_x, spawner_y, spawner_z, version, biome, floor_sequence, mob)
+        .crack_with_progress(on_progress)
+}
+
+/// Same as [`crack_dungeon_with_progress`], but stops as soon as `cancel` is
+/// triggered. The seeds collected up to that point are still converted and
+/// returned, with [`CrackResult::cancelled`] set.
+#[allow(clippy::too_many_arguments)]
+pub fn crack_dungeon_cancellable(
+    spawner_x: i32,
+    spawner_y: i32,
+    spawner_z: i32,
+    version: MCVersion,
+    biome: BiomeType,
+    floor_sequence: &str,
+    mob: Option<SpawnerMob>,
+    cancel: &CancelToken,
+    on_progress: impl FnMut(CrackProgress),
+) -> Result<CrackResult, String> {
+    request_for(spawner_x, spawner_y, spawner_z, version, biome, floor_sequence, mob)
+        .crack_cancellable(cancel, on_progress)
+}
+
+/// Build the [`CrackRequest`] behind the positional entry points.
+fn request_for(
+    spawner_x: i32,
+    spawner_y: i32,
+    spawner_z: i32,
+    version: MCVersion,
+    biome: BiomeType,
+    floor_sequence: &str,
+    mob: Option<SpawnerMob>,
+) -> CrackRequest {
+    let mut request = CrackRequest::new(spawner_x, spawner_y, spawner_z)
+        .version(version)
+        .biome(biome)
+        .floor(floor_sequence);
+    request.mob = mob;
+    request
+}
+
+/// Body of [`CrackRequest::crack_cancellable`]. Every seed is passed to
+/// `on_candidate` once, as soon as it is known.
+pub(crate) fn crack_dungeon_core(
+    request: &CrackRequest,
+    cancel: &CancelToken,
+    on_progress: &mut dyn FnMut(CrackProgress),
+    on_candidate: &mut dyn FnMut(SeedCandidate),
 ) -> Result<CrackResult, String> {
-    let salts = get_salts(version, biome);
+    let CrackRequest { spawner_x, spawner_y, spawner_z, version, mob, max_possibilities, .. } = *request;
+    let floor_sequence = request.floor_sequence.as_str();
+    let salts = request.resolved_salts();
+    if salts.is_empty() && !version.is_older_than(MCVersion::V1_13) {
+        return Err("No decorator salts to crack with".to_string());
+    }
 
-    let possibilities = DungeonDataParser::get_all_possibilities(floor_sequence)
-        .ok_or_else(|| "Too many possibilities (>128 unknown permutations)".to_string())?;
+    let possibilities = get_variants(floor_sequence, version, mob, max_possibilities)?;
+    let verifier = DungeonVerifier::new(spawner_x, spawner_y, spawner_z, version, &salts, floor_sequence, max_possibilities)?;
 
     verbose_eprintln!("[info] Generated {} floor interpretation(s)", possibilities.len());
 
-    // For pre-1.13, chunk population is offset by +8 blocks,
-    // so the spawner coordinates need to be adjusted by -8.
-    let (adj_x, adj_z) = if version.is_older_than(MCVersion::V1_13) {
-        (spawner_x - 8, spawner_z - 8)
-    } else {
-        (spawner_x, spawner_z)
-    };
+    let mut struct_seeds_set = BTreeSet::new();
+    let mut dungeon_seeds_set = BTreeSet::new();
 
-    let offset_x = adj_x & 15;
-    let y = spawner_y;
-    let offset_z = adj_z & 15;
-    verbose_eprintln!("[info] Offsets: x={}, y={}, z={}", offset_x, y, offset_z);
-
-    let mut struct_seeds_set = HashSet::new();
-    let mut dungeon_seeds_set = HashSet::new();
-
-    for (poss_idx, program) in possibilities.iter().enumerate() {
-        verbose_eprintln!("[progress] Processing possibility {}/{} ({} instructions)...", poss_idx + 1, possibilities.len(), program.len());
-        // Build the DynamicProgram equivalent
-        let mut filtered_skips: Vec<FilteredSkip> = Vec::new();
-        let mut call_sequence: Vec<CallEntry> = Vec::new();
-        let mut current_index: i64 = 0;
-
-        // Spawner position calls
-        if version.is_between(MCVersion::V1_8, MCVersion::V1_14) {
-            // x, y, z order
-            call_sequence.push(CallEntry::NextInt { bound: 16, value: offset_x });
-            current_index += 1;
-            call_sequence.push(CallEntry::NextInt { bound: 256, value: y });
-            current_index += 1;
-            call_sequence.push(CallEntry::NextInt { bound: 16, value: offset_z });
-            current_index += 1;
-        } else {
-            // x, z, y order (1.15+)
-            call_sequence.push(CallEntry::NextInt { bound: 16, value: offset_x });
-            current_index += 1;
-            call_sequence.push(CallEntry::NextInt { bound: 16, value: offset_z });
-            current_index += 1;
-            call_sequence.push(CallEntry::NextInt { bound: 256, value: y });
-            current_index += 1;
-        }
+    let mut progress = CrackProgress {
+        phase: CrackPhase::LatticeReduction,
+        possibility: 0,
+        possibilities: possibilities.len(),
+        branches_done: 0,
+        total_branches: 0,
+        candidates_found: 0,
+        structure_seeds_found: 0,
+    };
 
-        // Skip 2 calls
-        call_sequence.push(CallEntry::Skip { count: 2 });
-        current_index += 2;
-
-        // Floor calls
-        let mut info_bits: f32 = 16.0;
-        for instr in program {
-            match instr.instruction_type {
-                InstructionType::NextInt => {
-                    call_sequence.push(CallEntry::NextIntEq { bound: 4, value: 0 });
-                    info_bits += 2.0;
-                    current_index += 1;
-                }
-                InstructionType::FilteredSkip => {
-                    let idx = current_index;
-                    filtered_skips.push(FilteredSkip::new(
-                        idx,
-                        Box::new(|r: &mut Rand| r.next_int(4) != 0),
-                    ));
-                    call_sequence.push(CallEntry::Skip { count: 1 });
-                    info_bits += 0.4;
-                    current_index += 1;
-                }
-                InstructionType::Skip => {
-                    let count = instr.max_call_count as i64;
-                    call_sequence.push(CallEntry::Skip { count });
-                    current_index += count;
-                }
-                InstructionType::MutableSkip => {
-                    // Should not appear after expansion
-                    return Err("Mutable skip encountered during reverser setup".to_string());
-                }
-            }
+    for (poss_idx, variant) in possibilities.iter().enumerate() {
+        if cancel.is_cancelled() {
+            break;
         }
+        verbose_eprintln!("[progress] Processing possibility {}/{} ({} instructions)...", poss_idx + 1, possibilities.len(), variant.program.len());
+        let (mut reverser, info_bits) = build_reverser(spawner_x, spawner_y, spawner_z, version, variant)?;
 
         if info_bits <= 32.0 {
             return Err("Not enough information in the floor pattern".to_string());
         }
 
-        // Build the JavaRandomReverser
-        let mut reverser = JavaRandomReverser::new(filtered_skips);
-        for entry in &call_sequence {
-            match entry {
-                CallEntry::NextInt { bound, value } => {
-                    reverser.add_next_int_call(*bound, *value, *value);
-                }
-                CallEntry::NextIntEq { bound, value } => {
-                    reverser.add_next_int_call(*bound, *value, *value);
-                }
-                CallEntry::Skip { count } => {
-                    reverser.add_unmeasured_seeds(*count);
-                }
-            }
-        }
+        progress.phase = CrackPhase::LatticeReduction;
+        progress.possibility = poss_idx;
+        progress.branches_done = 0;
+        progress.total_branches = 0;
+        on_progress(progress);
 
         verbose_eprintln!("[progress]   Built reverser with {} dimensions, info_bits={:.1}, success_chance={:.6}",
                  reverser.dimensions(), info_bits, reverser.success_chance());
         verbose_eprintln!("[progress]   Running find_all_valid_seeds (lattice reduction + enumeration)...");
-        let dungeon_seeds_xored = reverser.find_all_valid_seeds();
+        let dungeon_seeds_xored = reverser.find_all_valid_seeds_with_progress(cancel, &mut |branch, seeds| {
+            for seed in seeds {
+                if dungeon_seeds_set.insert(*seed) {
+                    on_candidate(SeedCandidate::DungeonSeed(*seed));
+                }
+            }
+            progress.phase = CrackPhase::Enumeration;
+            progress.branches_done = branch.branches_done;
+            progress.total_branches = branch.total_branches;
+            progress.candidates_found = dungeon_seeds_set.len();
+            on_progress(progress);
+        }).map_err(|e| format!("Search failed: {}", e))?;
         verbose_eprintln!("[progress]   Found {} candidate dungeon seed(s)", dungeon_seeds_xored.len());
         let mut rand = ChunkRand::new();
+        let mut seed_structure_seeds = BTreeSet::new();
 
+        progress.phase = CrackPhase::PopulationReversal;
         for (ds_idx, seed) in dungeon_seeds_xored.iter().enumerate() {
+            if cancel.is_cancelled() {
+                break;
+            }
             if ds_idx % 100 == 0 && ds_idx > 0 {
                 verbose_eprintln!("[progress]   Processing dungeon seed {}/{}...", ds_idx, dungeon_seeds_xored.len());
             }
-            dungeon_seeds_set.insert(*seed);
 
+            seed_structure_seeds.clear();
             dungeon_seed_to_structure_seeds(
                 *seed, spawner_x, spawner_z, version, &salts,
-                &mut struct_seeds_set, &mut rand,
+                &mut seed_structure_seeds, &mut rand,
             );
+            // Drop structure seeds that do not regenerate this dungeon.
+            for ss in seed_structure_seeds.iter().copied().filter(|ss| verifier.verify(*ss)) {
+                if struct_seeds_set.insert(ss) {
+                    on_candidate(SeedCandidate::StructureSeed(ss));
+                }
+            }
+
+            progress.structure_seeds_found = struct_seeds_set.len();
+            on_progress(progress);
         }
     }
 
@@ -222,11 +377,15 @@ pub fn crack_dungeon(
     verbose_eprintln!("[progress] All possibilities processed. {} dungeon seed(s), {} structure seed(s).",
              dungeon_seeds_set.len(), struct_seeds_set.len());
     verbose_eprintln!("[progress] Converting structure seeds to world seeds...");
-    let mut world_seeds_set = HashSet::new();
+    progress.phase = CrackPhase::WorldSeeds;
+    on_progress(progress);
+
+    let mut world_seeds_set = BTreeSet::new();
     for struct_seed in &struct_seeds_set {
-        let equivalents = next_long_reverser::get_next_long_equivalents(*struct_seed);
-        for ws in equivalents {
-            world_seeds_set.insert(ws);
+        for ws in request.world_seeds_for(*struct_seed) {
+            if world_seeds_set.insert(ws) {
+                on_candidate(SeedCandidate::WorldSeed(ws));
+            }
         }
     }
 
@@ -234,9 +393,93 @@ pub fn crack_dungeon(
         dungeon_seeds: dungeon_seeds_set.into_iter().collect(),
         structure_seeds: struct_seeds_set.into_iter().collect(),
         world_seeds: world_seeds_set.into_iter().collect(),
+        cancelled: cancel.is_cancelled(),
     })
 }
 
+/// Iterator over the seeds of a crack running on a background thread,
+/// returned by [`CrackRequest::crack_iter`].
+///
+/// Dropping the stream cancels the crack and waits for the thread to exit.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct SeedStream {
+    rx: mpsc::Receiver<SeedCandidate>,
+    cancel: CancelToken,
+    handle: Option<JoinHandle<Result<CrackResult, String>>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SeedStream {
+    /// Token that stops the background crack when cancelled.
+    pub fn cancel_token(&self) -> &CancelToken {
+        &self.cancel
+    }
+
+    /// Wait for the crack to end and return its full result.
+    /// Seeds not yet read from the iterator are still part of the result.
+    pub fn finish(mut self) -> Result<CrackResult, String> {
+        let handle = self.handle.take().expect("crack thread already joined");
+        handle
+            .join()
+            .unwrap_or_else(|_| Err("Crack thread panicked".to_string()))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Iterator for SeedStream {
+    type Item = SeedCandidate;
+
+    fn next(&mut self) -> Option<SeedCandidate> {
+        self.rx.recv().ok()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for SeedStream {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            self.cancel.cancel();
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Streaming variant of [`crack_dungeon`]: runs the crack on a background
+/// thread and yields every dungeon, structure and world seed as soon as it is
+/// found. Call [`SeedStream::finish`] for the error, if any, and the final
+/// [`CrackResult`].
+#[cfg(not(target_arch = "wasm32"))]
+pub fn crack_dungeon_iter(
+    spawner_x: i32,
+    spawner_y: i32,
+    spawner_z: i32,
+    version: MCVersion,
+    biome: BiomeType,
+    floor_sequence: &str,
+    mob: Option<SpawnerMob>,
+) -> SeedStream {
+    request_for(spawner_x, spawner_y, spawner_z, version, biome, floor_sequence, mob).crack_iter()
+}
+
+/// Start `request` on a background thread, see [`CrackRequest::crack_iter`].
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn spawn_seed_stream(request: CrackRequest) -> SeedStream {
+    let (tx, rx) = mpsc::channel();
+    let cancel = CancelToken::new();
+    let worker_cancel = cancel.clone();
+    let handle = std::thread::spawn(move || {
+        request.in_thread_pool(|| {
+            crack_dungeon_core(
+                &request, &worker_cancel, &mut |_| {},
+                &mut |candidate| {
+                    let _ = tx.send(candidate);
+                },
+            )
+        })
+    });
+    SeedStream { rx, cancel, handle: Some(handle) }
+}
+
 /// Prepare the cracking: parse floor, build reverser, get branch count.
 /// Returns the total number of depth-0 branches that can be split across workers.
 pub fn prepare_crack(
@@ -244,34 +487,57 @@ pub fn prepare_crack(
     spawner_y: i32,
     spawner_z: i32,
     version: MCVersion,
-    _biome: BiomeType,
+    biome: BiomeType,
     floor_sequence: &str,
+    mob: Option<SpawnerMob>,
 ) -> Result<PrepareResult, String> {
-    let possibilities = DungeonDataParser::get_all_possibilities(floor_sequence)
-        .ok_or_else(|| "Too many possibilities (>128 unknown permutations)".to_string())?;
+    request_for(spawner_x, spawner_y, spawner_z, version, biome, floor_sequence, mob).prepare()
+}
+
+/// Body of [`CrackRequest::prepare`].
+pub(crate) fn prepare_crack_core(request: &CrackRequest) -> Result<PrepareResult, String> {
+    let CrackRequest { spawner_x, spawner_y, spawner_z, version, mob, max_possibilities, .. } = *request;
+    let possibilities = get_variants(&request.floor_sequence, version, mob, max_possibilities)?;
 
     if possibilities.is_empty() {
         return Err("No valid floor interpretations".to_string());
     }
 
-    // We only parallelize the first possibility's enumeration (the main one).
-    // Multiple possibilities are rare and handled sequentially.
-    let program = &possibilities[0];
-
-    let (reverser, info_bits) = build_reverser(spawner_x, spawner_y, spawner_z, version, program)?;
-    let mut reverser = reverser;
-    let branch_count = reverser.get_branch_count();
+    let mut branches_per_possibility = Vec::with_capacity(possibilities.len());
+    let mut dimensions = 0;
+    let mut info_bits = 0.0;
+    let mut expected_candidates = 0.0;
+    let mut success_chance = 1.0;
+    let mut prepared_reversers = Vec::with_capacity(possibilities.len());
+    for (poss_idx, variant) in possibilities.iter().enumerate() {
+        let (mut reverser, bits) = build_reverser(spawner_x, spawner_y, spawner_z, version, variant)?;
+        branches_per_possibility.push(reverser.get_branch_count().map_err(|e| format!("Search failed: {}", e))?);
+        expected_candidates += reverser.estimate_solution_count();
+        prepared_reversers.push(reverser.prepared().map_err(|e| format!("Lattice reduction failed: {}", e))?);
+        // The remaining figures are reported for the main interpretation.
+        if poss_idx == 0 {
+            dimensions = reverser.dimensions();
+            info_bits = bits;
+            success_chance = reverser.success_chance();
+        }
+    }
 
     Ok(PrepareResult {
-        total_branches: branch_count,
+        total_branches: branches_per_possibility.iter().sum(),
+        branches_per_possibility,
         possibilities: possibilities.len(),
-        dimensions: reverser.dimensions(),
+        dimensions,
         info_bits,
+        expected_candidates,
+        success_chance,
+        prepared_reversers,
     })
 }
 
 /// Crack dungeon for a specific range of depth-0 branches.
-/// Each worker calls this with a different [branch_start, branch_end) range.
+/// Each worker calls this with a different [branch_start, branch_end) range of
+/// the global branch space described by [`PrepareResult`].
+#[allow(clippy::too_many_arguments)]
 pub fn crack_dungeon_partial(
     spawner_x: i32,
     spawner_y: i32,
@@ -279,28 +545,58 @@ pub fn crack_dungeon_partial(
     version: MCVersion,
     biome: BiomeType,
     floor_sequence: &str,
+    mob: Option<SpawnerMob>,
     branch_start: i64,
     branch_end: i64,
 ) -> Result<CrackResult, String> {
-    let salts = get_salts(version, biome);
+    request_for(spawner_x, spawner_y, spawner_z, version, biome, floor_sequence, mob)
+        .crack_partial(branch_start, branch_end)
+}
 
-    let possibilities = DungeonDataParser::get_all_possibilities(floor_sequence)
-        .ok_or_else(|| "Too many possibilities (>128 unknown permutations)".to_string())?;
+/// Body of [`CrackRequest::crack_partial`].
+pub(crate) fn crack_partial_core(
+    request: &CrackRequest,
+    branch_start: i64,
+    branch_end: i64,
+) -> Result<CrackResult, String> {
+    let CrackRequest { spawner_x, spawner_y, spawner_z, version, mob, max_possibilities, .. } = *request;
+    let floor_sequence = request.floor_sequence.as_str();
+    let salts = request.resolved_salts();
+    if salts.is_empty() && !version.is_older_than(MCVersion::V1_13) {
+        return Err("No decorator salts to crack with".to_string());
+    }
 
-    let mut struct_seeds_set = HashSet::new();
-    let mut dungeon_seeds_set = HashSet::new();
+    let possibilities = get_variants(floor_sequence, version, mob, max_possibilities)?;
+    let verifier = DungeonVerifier::new(spawner_x, spawner_y, spawner_z, version, &salts, floor_sequence, max_possibilities)?;
 
-    for (poss_idx, program) in possibilities.iter().enumerate() {
-        let (mut reverser, info_bits) = build_reverser(spawner_x, spawner_y, spawner_z, version, program)?;
+    let mut struct_seeds_set = BTreeSet::new();
+    let mut dungeon_seeds_set = BTreeSet::new();
+
+    // First global branch index of the current possibility
+    let mut possibility_start = 0i64;
+
+    for (poss_idx, variant) in possibilities.iter().enumerate() {
+        let (mut reverser, info_bits) = build_reverser(spawner_x, spawner_y, spawner_z, version, variant)?;
 
         if info_bits <= 32.0 {
             return Err("Not enough information in the floor pattern".to_string());
         }
+        if let Some(prepared) = request.prepared_reversers.get(poss_idx) {
+            reverser.set_prepared(prepared)?;
+        }
+
+        let branch_count = reverser.get_branch_count().map_err(|e| format!("Search failed: {}", e))?;
+        let local_start = branch_start.max(possibility_start) - possibility_start;
+        let local_end = branch_end.min(possibility_start + branch_count) - possibility_start;
+        possibility_start += branch_count;
+        if local_start >= local_end {
+            continue;
+        }
 
         verbose_eprintln!("[worker] Processing possibility {}/{}, branches [{}, {})",
-                 poss_idx + 1, possibilities.len(), branch_start, branch_end);
+                 poss_idx + 1, possibilities.len(), local_start, local_end);
 
-        let dungeon_seeds_xored = reverser.find_seeds_for_branches(branch_start, branch_end);
+        let dungeon_seeds_xored = reverser.find_seeds_for_branches(local_start, local_end).map_err(|e| format!("Search failed: {}", e))?;
         verbose_eprintln!("[worker] Found {} candidate dungeon seed(s)", dungeon_seeds_xored.len());
 
         let mut rand = ChunkRand::new();
@@ -315,22 +611,169 @@ pub fn crack_dungeon_partial(
         }
     }
 
+    let before = struct_seeds_set.len();
+    struct_seeds_set.retain(|ss| verifier.verify(*ss));
+    verbose_eprintln!("[worker] Simulation rejected {} structure seed(s)", before - struct_seeds_set.len());
+
     // Convert structure seeds to world seeds
-    let mut world_seeds_set = HashSet::new();
+    let mut world_seeds_set = BTreeSet::new();
     for struct_seed in &struct_seeds_set {
-        let equivalents = next_long_reverser::get_next_long_equivalents(*struct_seed);
-        for ws in equivalents {
-            world_seeds_set.insert(ws);
-        }
+        world_seeds_set.extend(request.world_seeds_for(*struct_seed));
     }
 
     Ok(CrackResult {
         dungeon_seeds: dungeon_seeds_set.into_iter().collect(),
         structure_seeds: struct_seeds_set.into_iter().collect(),
         world_seeds: world_seeds_set.into_iter().collect(),
+        cancelled: false,
     })
 }
 
+/// Depth-0 branches of one interpretation cracked between two checkpoint saves.
+#[cfg(not(target_arch = "wasm32"))]
+const CHUNKS_PER_POSSIBILITY: i64 = 100;
+
+/// Body of [`crack_dungeon_resume`](crate::dungeon::checkpoint::crack_dungeon_resume):
+/// crack the branches of `checkpoint` from its next branch on, a chunk at a
+/// time, recording the seeds and calling `on_chunk` after every chunk.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn crack_resume_core(
+    checkpoint: &mut Checkpoint,
+    cancel: &CancelToken,
+    on_chunk: &mut dyn FnMut(&Checkpoint) -> Result<(), String>,
+) -> Result<(), String> {
+    let request = checkpoint.request.clone();
+    let CrackRequest { spawner_x, spawner_y, spawner_z, version, mob, max_possibilities, .. } = request;
+    let floor_sequence = request.floor_sequence.as_str();
+    let salts = request.resolved_salts();
+    if salts.is_empty() && !version.is_older_than(MCVersion::V1_13) {
+        return Err("No decorator salts to crack with".to_string());
+    }
+
+    let possibilities = get_variants(floor_sequence, version, mob, max_possibilities)?;
+    let verifier = DungeonVerifier::new(spawner_x, spawner_y, spawner_z, version, &salts, floor_sequence, max_possibilities)?;
+    if possibilities.len() != checkpoint.branches_per_possibility.len() {
+        return Err("Checkpoint does not match the floor interpretations".to_string());
+    }
+
+    let mut rand = ChunkRand::new();
+    let mut possibility_start = 0i64;
+    for (variant, branch_count) in possibilities.iter().zip(checkpoint.branches_per_possibility.clone()) {
+        let possibility_end = possibility_start + branch_count;
+        if checkpoint.next_branch >= possibility_end {
+            possibility_start = possibility_end;
+            continue;
+        }
+
+        let (mut reverser, info_bits) = build_reverser(spawner_x, spawner_y, spawner_z, version, variant)?;
+        if info_bits <= 32.0 {
+            return Err("Not enough information in the floor pattern".to_string());
+        }
+        if reverser.get_branch_count().map_err(|e| format!("Search failed: {}", e))? != branch_count {
+            return Err("Checkpoint does not match the rebuilt lattice".to_string());
+        }
+
+        let chunk = (branch_count / CHUNKS_PER_POSSIBILITY).max(1);
+        while checkpoint.next_branch < possibility_end {
+            if cancel.is_cancelled() {
+                return Ok(());
+            }
+            let local_start = checkpoint.next_branch - possibility_start;
+            let local_end = (local_start + chunk).min(branch_count);
+
+            let mut struct_seeds_set = BTreeSet::new();
+            let dungeon_seeds = reverser.find_seeds_for_branches(local_start, local_end).map_err(|e| format!("Search failed: {}", e))?;
+            for seed in dungeon_seeds {
+                checkpoint.dungeon_seeds.insert(seed);
+                dungeon_seed_to_structure_seeds(
+                    seed, spawner_x, spawner_z, version, &salts,
+                    &mut struct_seeds_set, &mut rand,
+                );
+            }
+            checkpoint.structure_seeds.extend(struct_seeds_set.into_iter().filter(|&ss| verifier.verify(ss)));
+
+            checkpoint.next_branch = possibility_start + local_end;
+            on_chunk(checkpoint)?;
+        }
+        possibility_start = possibility_end;
+    }
+    Ok(())
+}
+
+// ─── Pipeline stages ────────────────────────────────────────────────────
+
+/// First stage: every dungeon seed (internal RNG state before the spawner
+/// position calls) that can produce the floor, across all interpretations,
+/// sorted.
+pub fn dungeon_seeds_from_floor(
+    spawner_x: i32,
+    spawner_y: i32,
+    spawner_z: i32,
+    version: MCVersion,
+    floor_sequence: &str,
+) -> Result<Vec<i64>, String> {
+    let possibilities = get_variants(floor_sequence, version, None, DEFAULT_MAX_POSSIBILITIES)?;
+
+    let mut dungeon_seeds = BTreeSet::new();
+    for variant in &possibilities {
+        let (mut reverser, info_bits) = build_reverser(spawner_x, spawner_y, spawner_z, version, variant)?;
+        if info_bits <= 32.0 {
+            return Err("Not enough information in the floor pattern".to_string());
+        }
+        dungeon_seeds.extend(reverser.find_all_valid_seeds().map_err(|e| format!("Search failed: {}", e))?);
+    }
+    Ok(dungeon_seeds.into_iter().collect())
+}
+
+/// Second stage: the 48-bit structure seeds whose dungeon feature can start
+/// from `dungeon_seed` for the spawner at `spawner_x`/`spawner_z`. The result
+/// is sorted and not yet checked against the floor (see [`DungeonVerifier`]).
+pub fn structure_seeds_from_dungeon_seed(
+    dungeon_seed: i64,
+    spawner_x: i32,
+    spawner_z: i32,
+    version: MCVersion,
+    biome: BiomeType,
+) -> Vec<i64> {
+    let mut struct_seeds_set = BTreeSet::new();
+    dungeon_seed_to_structure_seeds(
+        dungeon_seed, spawner_x, spawner_z, version, &get_salts(version, biome),
+        &mut struct_seeds_set, &mut ChunkRand::new(),
+    );
+    struct_seeds_set.into_iter().collect()
+}
+
+/// Last stage: the world seeds reachable through `nextLong` that share the
+/// lower 48 bits of `structure_seed`.
+pub fn world_seeds_from_structure_seed(structure_seed: i64) -> Vec<i64> {
+    next_long_reverser::get_next_long_equivalents(structure_seed)
+}
+
+/// All 65536 world seeds sharing the lower 48 bits of `structure_seed`, in
+/// increasing order of their upper 16 bits. Unlike
+/// [`world_seeds_from_structure_seed`] this includes seeds that no `nextLong`
+/// produces, e.g. typed-in numeric seeds, for external biome matching.
+/// The iterator is lazy; `collect` it for a list.
+pub fn sister_world_seeds(structure_seed: i64) -> impl Iterator<Item = i64> {
+    let lower = structure_seed & mth::MASK_48;
+    (0..1i64 << 16).map(move |upper| (upper << 48) | lower)
+}
+
+/// Chunk whose population pass generated a spawner at block `spawner_x`, `spawner_z`.
+///
+/// A dungeon is placed at a random offset in `0..16` from its chunk's
+/// population origin, which is the chunk corner from 1.13 and 8 blocks
+/// into the chunk before. Each spawner position therefore has exactly one
+/// generating chunk; near a chunk edge before 1.13 it is a neighbour of the
+/// chunk containing the spawner rather than `spawner >> 4`.
+pub fn generating_chunk(spawner_x: i32, spawner_z: i32, version: MCVersion) -> (i32, i32) {
+    if version.is_older_than(MCVersion::V1_13) {
+        ((spawner_x - 8) >> 4, (spawner_z - 8) >> 4)
+    } else {
+        (spawner_x >> 4, spawner_z >> 4)
+    }
+}
+
 /// Convert a dungeon seed (internal RNG state) to structure seeds (48-bit world seeds).
 /// Mirrors DecoratorSeedProcessor.decoratorSeedsToStructureSeeds from Java
 ///
@@ -352,23 +795,19 @@ fn dungeon_seed_to_structure_seeds(
     spawner_z: i32,
     version: MCVersion,
     salts: &[i64],
-    struct_seeds_set: &mut HashSet<i64>,
+    struct_seeds_set: &mut BTreeSet<i64>,
     rand: &mut ChunkRand,
 ) {
+    let (chunk_x, chunk_z) = generating_chunk(spawner_x, spawner_z, version);
     if version.is_older_than(MCVersion::V1_13) {
-        let adj_x = spawner_x - 8;
-        let adj_z = spawner_z - 8;
-        let chunk_x = adj_x >> 4;
-        let chunk_z = adj_z >> 4;
-
-        let lcg_inv = LCG::JAVA.combine(-1);
+        let lcg_inv = LCG::JAVA_SKIP_NEG_1;
         let mut state = dungeon_seed;
 
         for _ in 0..2000 {
             let pop_seed_candidate = (state ^ LCG::JAVA.multiplier) & mth::MASK_48;
 
             let partial_struct_seeds = population_reverser::reverse_population_seed(
-                pop_seed_candidate, chunk_x, chunk_z, MCVersion::V1_12,
+                pop_seed_candidate, chunk_x, chunk_z, version,
             );
 
             for ss in partial_struct_seeds {
@@ -380,37 +819,98 @@ fn dungeon_seed_to_structure_seeds(
             state = lcg_inv.next_seed(state);
         }
     } else {
-        let chunk_x = (spawner_x >> 4) << 4;
-        let chunk_z = (spawner_z >> 4) << 4;
+        let (block_x, block_z) = (chunk_x << 4, chunk_z << 4);
 
         for &salt in salts {
             rand.jrand.set_seed(dungeon_seed, false);
 
             for _ in 0..8 {
-                let pop_seed = (rand.jrand.get_seed() ^ LCG::JAVA.multiplier) - salt;
+                let decorator_seed = rand.jrand.get_seed() ^ LCG::JAVA.multiplier;
 
-                let partial_struct_seeds =
-                    population_reverser::reverse_population_seed(pop_seed, chunk_x, chunk_z, MCVersion::V1_14);
+                for pop_seed in population_reverser::reverse_decorator_seed(decorator_seed, salt as i32, version) {
+                    let partial_struct_seeds =
+                        population_reverser::reverse_population_seed(pop_seed, block_x, block_z, version);
 
-                for ss in partial_struct_seeds {
-                    let masked = ss & mth::MASK_48;
-                    struct_seeds_set.insert(masked);
+                    for ss in partial_struct_seeds {
+                        let masked = ss & mth::MASK_48;
+                        struct_seeds_set.insert(masked);
+                    }
                 }
 
-                rand.jrand.advance(-5);
+                rand.jrand.advance_lcg(&LCG::JAVA_SKIP_NEG_5);
             }
         }
     }
 }
 
-/// Build a JavaRandomReverser from a program (one possibility).
+/// One floor interpretation to crack. With a known spawner mob, `mob_call`
+/// holds the mob and the number of calls between the program and its roll.
+struct CrackVariant {
+    program: Vec<ReverserInstruction>,
+    mob_call: Option<(SpawnerMob, i64)>,
+}
+
+/// RNG calls made by the dungeon chests between the floor and the mob roll.
+/// Each of the 2 chests makes up to 3 placement attempts (2 calls each), and
+/// a placed chest ends its attempts with a `nextLong` for the loot table.
+const CHEST_CALLS: [i64; 5] = [8, 10, 12, 14, 16];
+
+/// Expand the floor interpretations, and with a known mob, every possible
+/// call gap between the end of the program and the mob roll.
+fn get_variants(
+    floor_sequence: &str,
+    version: MCVersion,
+    mob: Option<SpawnerMob>,
+    max_possibilities: usize,
+) -> Result<Vec<CrackVariant>, String> {
+    let possibilities = DungeonDataParser::get_all_possibilities(floor_sequence, max_possibilities)
+        .ok_or_else(|| format!("Too many possibilities (>{} unknown permutations)", max_possibilities))?;
+
+    let mob = match mob {
+        None => {
+            return Ok(possibilities
+                .into_iter()
+                .map(|program| CrackVariant { program, mob_call: None })
+                .collect());
+        }
+        Some(mob) => mob,
+    };
+
+    if version.is_older_than(MCVersion::V1_9) {
+        return Err("Spawner mob is not supported before 1.9 (chest loot uses a variable number of calls)".to_string());
+    }
+
+    let (trailing_min, trailing_max) = DungeonDataParser::trailing_call_range(floor_sequence);
+    let mut gaps: Vec<i64> = (trailing_min..=trailing_max)
+        .flat_map(|trailing| CHEST_CALLS.iter().map(move |chest| trailing as i64 + chest))
+        .collect();
+    gaps.sort();
+    gaps.dedup();
+
+    let mut variants = Vec::new();
+    for program in possibilities {
+        for &gap in &gaps {
+            variants.push(CrackVariant {
+                program: program.clone(),
+                mob_call: Some((mob, gap)),
+            });
+        }
+    }
+    Ok(variants)
+}
+
+/// Bits of information from the spawner position (4 + 4 for the chunk
+/// offsets, 8 for the height; 1.6 and earlier have one less).
+pub const POSITION_INFO_BITS: f32 = 16.0;
+
+/// Build a JavaRandomReverser from one crack variant.
 /// Returns (reverser, info_bits).
 fn build_reverser(
     spawner_x: i32,
     spawner_y: i32,
     spawner_z: i32,
     version: MCVersion,
-    program: &[ReverserInstruction],
+    variant: &CrackVariant,
 ) -> Result<(JavaRandomReverser, f32), String> {
     // For pre-1.13, chunk population is offset by +8 blocks,
     // so the spawner coordinates need to be adjusted by -8 to get the
@@ -425,54 +925,42 @@ fn build_reverser(
     let y = spawner_y;
     let offset_z = adj_z & 15;
 
-    let mut filtered_skips: Vec<FilteredSkip> = Vec::new();
-    let mut call_sequence: Vec<CallEntry> = Vec::new();
-    let mut current_index: i64 = 0;
+    let mut program = DynamicProgram::new();
 
     // Spawner position calls
-    if version.is_between(MCVersion::V1_8, MCVersion::V1_14) {
-        call_sequence.push(CallEntry::NextInt { bound: 16, value: offset_x });
-        current_index += 1;
-        call_sequence.push(CallEntry::NextInt { bound: 256, value: y });
-        current_index += 1;
-        call_sequence.push(CallEntry::NextInt { bound: 16, value: offset_z });
-        current_index += 1;
+    let y_bound = version.dungeon_height_bound();
+    if !(0..y_bound).contains(&y) {
+        return Err(format!("Spawner y = {} is outside the dungeon height range 0..{}", y, y_bound));
+    }
+    if version.is_older_than(MCVersion::V1_15) {
+        program = program
+            .add(NextInt(16).equal_to(offset_x))
+            .add(NextInt(y_bound).equal_to(y))
+            .add(NextInt(16).equal_to(offset_z));
     } else {
-        call_sequence.push(CallEntry::NextInt { bound: 16, value: offset_x });
-        current_index += 1;
-        call_sequence.push(CallEntry::NextInt { bound: 16, value: offset_z });
-        current_index += 1;
-        call_sequence.push(CallEntry::NextInt { bound: 256, value: y });
-        current_index += 1;
+        program = program
+            .add(NextInt(16).equal_to(offset_x))
+            .add(NextInt(16).equal_to(offset_z))
+            .add(NextInt(y_bound).equal_to(y));
     }
 
     // Skip 2 calls
-    call_sequence.push(CallEntry::Skip { count: 2 });
-    current_index += 2;
+    program = program.skip(2);
 
     // Floor calls
-    let mut info_bits: f32 = 16.0;
-    for instr in program {
+    let mut info_bits: f32 = POSITION_INFO_BITS - (256 / y_bound).ilog2() as f32;
+    for instr in &variant.program {
         match instr.instruction_type {
             InstructionType::NextInt => {
-                call_sequence.push(CallEntry::NextIntEq { bound: 4, value: 0 });
-                info_bits += 2.0;
-                current_index += 1;
+                program = program.add(NextInt(4).equal_to(0));
+                info_bits += instr.info_bits();
             }
             InstructionType::FilteredSkip => {
-                let idx = current_index;
-                filtered_skips.push(FilteredSkip::new(
-                    idx,
-                    Box::new(|r: &mut Rand| r.next_int(4) != 0),
-                ));
-                call_sequence.push(CallEntry::Skip { count: 1 });
-                info_bits += 0.4;
-                current_index += 1;
+                program = program.filtered_skip(|r: &mut dyn JavaRng| r.next_int(4) != 0, 1);
+                info_bits += instr.info_bits();
             }
             InstructionType::Skip => {
-                let count = instr.max_call_count as i64;
-                call_sequence.push(CallEntry::Skip { count });
-                current_index += count;
+                program = program.skip(instr.max_call_count as i64);
             }
             InstructionType::MutableSkip => {
                 return Err("Mutable skip encountered during reverser setup".to_string());
@@ -480,40 +968,34 @@ fn build_reverser(
         }
     }
 
-    // Build the JavaRandomReverser
-    let mut reverser = JavaRandomReverser::new(filtered_skips);
-    for entry in &call_sequence {
-        match entry {
-            CallEntry::NextInt { bound, value } => {
-                reverser.add_next_int_call(*bound, *value, *value);
-            }
-            CallEntry::NextIntEq { bound, value } => {
-                reverser.add_next_int_call(*bound, *value, *value);
-            }
-            CallEntry::Skip { count } => {
-                reverser.add_unmeasured_seeds(*count);
-            }
-        }
+    // Spawner mob roll, after the rest of the floor and the chests
+    if let Some((mob, gap)) = variant.mob_call {
+        let (min, max) = mob.call_range();
+        program = program.skip(gap).add(NextInt(4).between(min, max));
+        info_bits += if min == max { 2.0 } else { 1.0 };
     }
 
+    let reverser = program.reverser();
     Ok((reverser, info_bits))
 }
 
-fn get_salts(version: MCVersion, biome: BiomeType) -> Vec<i64> {
+/// Dungeon decorator salt for 1.13 - 1.15.
+pub const SALT_1_13: i64 = 20003;
+/// Dungeon decorator salt for 1.16+ outside deserts.
+pub const SALT_1_16: i64 = 30002;
+/// Dungeon decorator salt for 1.16+ in deserts.
+pub const SALT_1_16_DESERT: i64 = 30003;
+
+/// Vanilla decorator salts the dungeon feature may use for `version` and `biome`.
+/// Pre-1.13 dungeons have no decorator seed, so salts are ignored there.
+pub fn get_salts(version: MCVersion, biome: BiomeType) -> Vec<i64> {
     if version.is_newer_than(MCVersion::V1_15) {
         match biome {
-            BiomeType::Desert => vec![30003],
-            BiomeType::NotDesert => vec![30002],
-            BiomeType::Unknown => vec![30002, 30003],
+            BiomeType::Desert => vec![SALT_1_16_DESERT],
+            BiomeType::NotDesert => vec![SALT_1_16],
+            BiomeType::Unknown => vec![SALT_1_16, SALT_1_16_DESERT],
         }
     } else {
-        vec![20003]
+        vec![SALT_1_13]
     }
 }
-
-/// Internal representation of call sequence entries.
-enum CallEntry {
-    NextInt { bound: i32, value: i32 },
-    NextIntEq { bound: i32, value: i32 },
-    Skip { count: i64 },
-}
@@ -7,6 +7,9 @@ pub enum InstructionType {
     MutableSkip,
 }
 
+/// Highest floor tile index accepted in sequences.
+pub const MAX_TILE_INDEX: u8 = 7;
+
 /// A single instruction for the reverser.
 #[derive(Clone, Debug)]
 pub struct ReverserInstruction {
@@ -28,18 +31,31 @@ impl ReverserInstruction {
         ReverserInstruction::new(instruction_type, 1, 1)
     }
 
+    /// Bits of information the instruction gives about the seed: a cobble
+    /// tile pins `nextInt(4)` to 0, a mossy tile only rules 0 out.
+    pub fn info_bits(&self) -> f32 {
+        match self.instruction_type {
+            InstructionType::NextInt => 2.0,
+            InstructionType::FilteredSkip => 0.4,
+            InstructionType::Skip | InstructionType::MutableSkip => 0.0,
+        }
+    }
+
     /// Convert a floor tile index to a ReverserInstruction.
     /// 0 = mossy -> FILTEREDSKIP
     /// 1 = cobble -> NEXTINT
     /// 2 = air -> None (skipped)
     /// 3 = unknown -> MUTABLE_SKIP (0 or 1 calls)
     /// 4 = unknown_solid -> SKIP
+    /// 5 = chest -> SKIP (the tile under a chest still rolled)
+    /// 6 = water -> MUTABLE_SKIP (water may fill an air tile)
+    /// 7 = obstructed -> SKIP (fallen blocks rest on a solid tile)
     pub fn from_tile_index(index: u8) -> Option<Self> {
         match index {
             0 => Some(ReverserInstruction::single(InstructionType::FilteredSkip)),
             1 => Some(ReverserInstruction::single(InstructionType::NextInt)),
-            3 => Some(ReverserInstruction::new(InstructionType::MutableSkip, 0, 1)),
-            4 => Some(ReverserInstruction::single(InstructionType::Skip)),
+            3 | 6 => Some(ReverserInstruction::new(InstructionType::MutableSkip, 0, 1)),
+            4 | 5 | 7 => Some(ReverserInstruction::single(InstructionType::Skip)),
             _ => None, // air (2) returns None
         }
     }
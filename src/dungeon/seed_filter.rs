@@ -0,0 +1,132 @@
+use crate::dungeon::reverse_dungeon::world_seeds_from_structure_seed;
+use crate::math::mth;
+use crate::mc::seed_text::Wordlist;
+use std::collections::BTreeSet;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A range or known upper bits that leave at most this many of the 2^16
+/// seeds sharing a structure seed pin the world seed: all of them are kept,
+/// since a typed numeric seed is then as likely as a `nextLong` output.
+pub const MAX_PINNED_SISTERS: usize = 16;
+
+/// Facts known about the world seed, used to narrow down the cracked world
+/// seeds. The default filter accepts every seed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WorldSeedFilter {
+    pub(crate) min: Option<i64>,
+    pub(crate) max: Option<i64>,
+    /// `(mask, value)` pairs: the seed bits under `mask` equal `value`'s.
+    pub(crate) bits: Vec<(i64, i64)>,
+    /// World seeds of the candidate text seeds, if the seed is known to be one of them.
+    pub(crate) text_seeds: Option<BTreeSet<i64>>,
+}
+
+impl WorldSeedFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only keep seeds in `min..=max`. A narrow enough range also keeps
+    /// seeds no `nextLong` produces, see [`MAX_PINNED_SISTERS`].
+    pub fn range(mut self, min: i64, max: i64) -> Self {
+        self.min = Some(self.min.map_or(min, |m| m.max(min)));
+        self.max = Some(self.max.map_or(max, |m| m.min(max)));
+        self
+    }
+
+    /// Only keep seeds `>= 0`.
+    pub fn positive(self) -> Self {
+        self.range(0, i64::MAX)
+    }
+
+    /// Only keep seeds `< 0`.
+    pub fn negative(self) -> Self {
+        self.range(i64::MIN, -1)
+    }
+
+    /// Only keep seeds whose bits under `mask` match `value`, e.g.
+    /// `bits(0xFFFF_FFFF, low)` for known low 32 bits.
+    pub fn bits(mut self, mask: i64, value: i64) -> Self {
+        self.bits.push((mask, value & mask));
+        self
+    }
+
+    /// Only keep seeds typed as one of the words in `wordlist`. Use
+    /// [`Wordlist::words_for`] to find which word a result came from.
+    pub fn wordlist(mut self, wordlist: &Wordlist) -> Self {
+        let seeds: BTreeSet<i64> = wordlist.seeds().collect();
+        self.text_seeds = Some(match self.text_seeds {
+            Some(known) => known.intersection(&seeds).copied().collect(),
+            None => seeds,
+        });
+        self
+    }
+
+    /// Whether `world_seed` fits every known fact.
+    pub fn accepts(&self, world_seed: i64) -> bool {
+        self.min.is_none_or(|min| world_seed >= min)
+            && self.max.is_none_or(|max| world_seed <= max)
+            && self.bits.iter().all(|&(mask, value)| world_seed & mask == value)
+            && self.text_seeds.as_ref().is_none_or(|seeds| seeds.contains(&world_seed))
+    }
+
+    /// The world seeds with the lower 48 bits of `structure_seed` that pass,
+    /// before the checks that need a full world seed. Random seeds are
+    /// `nextLong` outputs, but typed seeds almost never are: with a wordlist
+    /// the candidates are the matching hashed words, and when the range and
+    /// known upper bits pin the seed they are the sister seeds that pass.
+    pub(crate) fn candidates(&self, structure_seed: i64) -> Vec<i64> {
+        let lower = structure_seed & mth::MASK_48;
+        if let Some(seeds) = &self.text_seeds {
+            return seeds.iter().copied().filter(|&seed| seed & mth::MASK_48 == lower && self.accepts(seed)).collect();
+        }
+        if let Some(sisters) = self.pinned_sisters(structure_seed) {
+            return sisters;
+        }
+        let mut world_seeds = world_seeds_from_structure_seed(structure_seed);
+        world_seeds.retain(|&seed| self.accepts(seed));
+        world_seeds
+    }
+
+    /// The sister seeds of `structure_seed` that pass, if there are at most
+    /// [`MAX_PINNED_SISTERS`] of them.
+    fn pinned_sisters(&self, structure_seed: i64) -> Option<Vec<i64>> {
+        let first = self.min.map_or(i16::MIN as i64, |min| min >> 48);
+        let last = self.max.map_or(i16::MAX as i64, |max| max >> 48);
+        let mut sisters = Vec::new();
+        for upper in first..=last {
+            let seed = (upper << 48) | (structure_seed & mth::MASK_48);
+            if self.accepts(seed) {
+                if sisters.len() == MAX_PINNED_SISTERS {
+                    return None;
+                }
+                sisters.push(seed);
+            }
+        }
+        Some(sisters)
+    }
+
+    /// Whether any world seed with the lower 48 bits of `structure_seed` can
+    /// pass, so structure seeds that cannot are never expanded.
+    pub fn may_accept_structure_seed(&self, structure_seed: i64) -> bool {
+        let lower = structure_seed & mth::MASK_48;
+        if self.bits.iter().any(|&(mask, value)| (lower ^ value) & mask & mth::MASK_48 != 0) {
+            return false;
+        }
+        if let Some(seeds) = &self.text_seeds {
+            if !seeds.iter().any(|&seed| seed & mth::MASK_48 == lower) {
+                return false;
+            }
+        }
+        // A range within one block of 2^48 seeds fixes the upper 16 bits.
+        match (self.min, self.max) {
+            (Some(min), Some(max)) if min >> 48 == max >> 48 => {
+                let seed = (min & !mth::MASK_48) | lower;
+                min <= seed && seed <= max
+            }
+            _ => true,
+        }
+    }
+}
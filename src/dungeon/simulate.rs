@@ -0,0 +1,214 @@
+use crate::lcg::rand::JavaRng;
+use crate::dungeon::dungeon_data_parser::DungeonDataParser;
+use crate::dungeon::reverse_dungeon::{generating_chunk, FloorSize};
+use crate::dungeon::reverser_instruction::{InstructionType, ReverserInstruction};
+use crate::mc::chunk_rand::{ChunkRand, MCVersion};
+use crate::mc::jrand::JRand;
+
+// Forward simulation of dungeon generation, used to double-check cracked seeds.
+//
+// For 1.13+ the dungeon feature gets its own decorator seed and makes up to 8
+// attempts, each failed one consuming 5 calls (position + size).
+// Before 1.13 dungeons run after terrain-dependent lakes on the population
+// RNG, so every offset in the window searched by the cracker is simulated.
+
+/// Dungeon attempts per chunk for 1.13+.
+const ATTEMPTS: usize = 8;
+/// Calls consumed by an attempt that fails before placing the floor.
+const CALLS_PER_ATTEMPT: i64 = 5;
+/// Population RNG offsets searched for pre-1.13 dungeons.
+const PRE_1_13_WINDOW: usize = 2000;
+
+/// A dungeon attempt regenerated from a seed.
+#[derive(Clone, Debug)]
+pub struct SimulatedDungeon {
+    /// Block position of the spawner.
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub floor_size: FloorSize,
+    /// RNG right before the first floor roll.
+    floor_rand: JRand,
+}
+
+impl SimulatedDungeon {
+    /// Regenerate one attempt from the internal RNG state before its first call.
+    /// `chunk_x`/`chunk_z` are the block coordinates the position offsets apply to.
+    pub fn from_state(state: i64, chunk_x: i32, chunk_z: i32, version: MCVersion) -> Self {
+        let mut rand = JRand::of_internal_seed(state);
+        let y_bound = version.dungeon_height_bound();
+        let (dx, y, dz) = if version.is_older_than(MCVersion::V1_15) {
+            let dx = rand.next_int(16);
+            let y = rand.next_int(y_bound);
+            (dx, y, rand.next_int(16))
+        } else {
+            let dx = rand.next_int(16);
+            let dz = rand.next_int(16);
+            (dx, rand.next_int(y_bound), dz)
+        };
+        let x_radius = rand.next_int(2) + 2;
+        let z_radius = rand.next_int(2) + 2;
+        let floor_size = match (x_radius, z_radius) {
+            (3, 3) => FloorSize::_9x9,
+            (2, 3) => FloorSize::_7x9,
+            (3, 2) => FloorSize::_9x7,
+            _ => FloorSize::_7x7,
+        };
+
+        SimulatedDungeon {
+            x: chunk_x + dx,
+            y,
+            z: chunk_z + dz,
+            floor_size,
+            floor_rand: rand,
+        }
+    }
+
+    /// Roll the floor assuming every tile is solid, in the same `[z][x]` layout
+    /// as [`get_sequence`](crate::dungeon::reverse_dungeon::get_sequence) expects.
+    /// Tiles are 0 = mossy, 1 = cobble; cells outside the floor are 2 (air).
+    pub fn full_floor(&self) -> [[u8; 9]; 9] {
+        let mut rand = self.floor_rand.clone();
+        let size = self.floor_size;
+        let mut floor = [[2u8; 9]; 9];
+        for x in size.x_min()..size.x_max() {
+            for row in floor.iter_mut().take(size.z_max()).skip(size.z_min()) {
+                row[x] = if rand.next_int(4) != 0 { 0 } else { 1 };
+            }
+        }
+        floor
+    }
+
+    /// Whether the floor rolls of this dungeon reproduce one of `programs`
+    /// (as produced by [`DungeonDataParser::get_all_possibilities`]).
+    pub fn matches_program(&self, programs: &[Vec<ReverserInstruction>]) -> bool {
+        programs.iter().any(|program| {
+            let mut rand = self.floor_rand.clone();
+            program.iter().all(|instr| match instr.instruction_type {
+                InstructionType::NextInt => rand.next_int(4) == 0,
+                InstructionType::FilteredSkip => rand.next_int(4) != 0,
+                InstructionType::Skip => {
+                    rand.advance(instr.max_call_count as i64);
+                    true
+                }
+                InstructionType::MutableSkip => false,
+            })
+        })
+    }
+}
+
+/// Regenerate every dungeon attempt of a chunk for the given world seed.
+/// `chunk_x`/`chunk_z` are chunk coordinates. For 1.13+ this yields 8 attempts
+/// per decorator salt (see [`get_salts`](crate::dungeon::reverse_dungeon::get_salts));
+/// before 1.13 one attempt per searched RNG offset.
+pub fn simulate_chunk(
+    world_seed: i64,
+    chunk_x: i32,
+    chunk_z: i32,
+    version: MCVersion,
+    salts: &[i64],
+) -> Vec<SimulatedDungeon> {
+    let mut rand = ChunkRand::new();
+    let mut dungeons = Vec::new();
+
+    if version.is_older_than(MCVersion::V1_13) {
+        // Pre-1.13 population is offset by +8 blocks.
+        let (block_x, block_z) = ((chunk_x << 4) + 8, (chunk_z << 4) + 8);
+        rand.set_population_seed(world_seed, chunk_x, chunk_z, version);
+        for _ in 0..PRE_1_13_WINDOW {
+            let state = rand.jrand.get_seed();
+            dungeons.push(SimulatedDungeon::from_state(state, block_x, block_z, version));
+            rand.jrand.advance(1);
+        }
+    } else {
+        let (block_x, block_z) = (chunk_x << 4, chunk_z << 4);
+        for &salt in salts {
+            let pop_seed = rand.set_population_seed(world_seed, block_x, block_z, version);
+            rand.set_decorator_seed(pop_seed, salt as i32, version);
+            for _ in 0..ATTEMPTS {
+                let state = rand.jrand.get_seed();
+                dungeons.push(SimulatedDungeon::from_state(state, block_x, block_z, version));
+                rand.jrand.advance(CALLS_PER_ATTEMPT);
+            }
+        }
+    }
+
+    dungeons
+}
+
+/// Checks candidate seeds against an observed dungeon by regenerating it.
+pub struct DungeonVerifier {
+    spawner_x: i32,
+    spawner_y: i32,
+    spawner_z: i32,
+    version: MCVersion,
+    salts: Vec<i64>,
+    programs: Vec<Vec<ReverserInstruction>>,
+    /// Floor size implied by the sequence length, if the length is a full floor.
+    floor_tiles: Option<usize>,
+}
+
+impl DungeonVerifier {
+    pub fn new(
+        spawner_x: i32,
+        spawner_y: i32,
+        spawner_z: i32,
+        version: MCVersion,
+        salts: &[i64],
+        floor_sequence: &str,
+        max_possibilities: usize,
+    ) -> Result<Self, String> {
+        let programs = DungeonDataParser::get_all_possibilities(floor_sequence, max_possibilities)
+            .ok_or_else(|| format!("Too many possibilities (>{} unknown permutations)", max_possibilities))?;
+        let len = floor_sequence.chars().count();
+        let floor_tiles = [49, 63, 81].contains(&len).then_some(len);
+
+        Ok(DungeonVerifier {
+            spawner_x,
+            spawner_y,
+            spawner_z,
+            version,
+            salts: salts.to_vec(),
+            programs,
+            floor_tiles,
+        })
+    }
+
+    /// Whether `seed` (world or structure seed) generates the observed dungeon.
+    pub fn verify(&self, seed: i64) -> bool {
+        let (chunk_x, chunk_z) = generating_chunk(self.spawner_x, self.spawner_z, self.version);
+
+        simulate_chunk(seed, chunk_x, chunk_z, self.version, &self.salts)
+            .iter()
+            .any(|d| {
+                d.x == self.spawner_x
+                    && d.y == self.spawner_y
+                    && d.z == self.spawner_z
+                    && self.floor_tiles.is_none_or(|tiles| {
+                        let size = d.floor_size;
+                        (size.x_max() - size.x_min()) * (size.z_max() - size.z_min()) == tiles
+                    })
+                    && d.matches_program(&self.programs)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dungeon::dungeon_data_parser::DEFAULT_MAX_POSSIBILITIES;
+    use crate::dungeon::reverse_dungeon::SALT_1_16;
+    use crate::math::mth;
+
+    const SEQUENCE: &str = "101000111000011001010010100000001010000010001110100000000011000";
+
+    #[test]
+    fn test_verify_known_seed() {
+        let verifier = DungeonVerifier::new(126, 132, -117, MCVersion::V1_17, &[SALT_1_16], SEQUENCE, DEFAULT_MAX_POSSIBILITIES)
+            .unwrap();
+        let structure_seed = -7884052527727238006i64 & mth::MASK_48;
+        assert!(verifier.verify(structure_seed));
+        assert!(verifier.verify(-7884052527727238006));
+        assert!(!verifier.verify(structure_seed + 1));
+    }
+}
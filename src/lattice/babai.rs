@@ -0,0 +1,29 @@
+use super::sphere::ScaledBox;
+use crate::math::big_matrix::BigMatrix;
+use crate::math::big_vector::BigVector;
+
+/// Babai's nearest-plane point for the box `lower <= point <= upper`: the
+/// lattice point `basis * c + origin` found by rounding each coefficient in
+/// turn against the Gram–Schmidt data of the basis, scaled so that the box
+/// becomes a cube around its center. No search tree is built.
+///
+/// Returns `None` if that point lies outside the box, which does not mean
+/// the box is empty, or if the basis is singular. When the box is small next to the lattice (many info
+/// bits) and the basis is reduced, the point is usually the only one inside,
+/// but nothing here proves that; run a full enumeration when every point
+/// is needed.
+pub fn nearest_point(
+    basis: &BigMatrix,
+    lower: &BigVector,
+    upper: &BigVector,
+    origin: &BigVector,
+) -> Option<BigVector> {
+    let problem = ScaledBox::new(basis, lower, upper, origin)?;
+    let size = problem.size();
+    let mut offsets = vec![0i64; size];
+    for k in (0..size).rev() {
+        offsets[k] = problem.center(k, &offsets).round() as i64;
+    }
+    let point = problem.point(&offsets);
+    problem.contains(&point).then_some(point)
+}
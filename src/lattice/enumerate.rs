@@ -1,9 +1,18 @@
-use crate::math::big_fraction::{BigFraction, FracOps};
+use crate::cancel::CancelToken;
+use crate::math::big_fraction::{BigFraction, FracOps, FractionDisplay, FractionStyle};
 use crate::math::big_matrix::BigMatrix;
 use crate::math::big_vector::BigVector;
+use crate::math::error::MathError;
+use crate::math::gauss_jordan;
 use crate::math::lu_decomposition;
-use crate::math::optimize::{Optimize, OptimizeBuilder};
+use super::lll;
+use super::sphere;
+use crate::math::optimize::{Optimize, OptimizeBuilder, SolveError};
 use crate::math::int_type::{Int, IntOps};
+use std::fmt;
+use std::ops::{ControlFlow, Range};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
 
 /// High-level enumerate function matching Java's Enumerate.enumerate(basis, lower, upper, offset).
 /// This is used by RandomReverser.findAllValidSeeds().
@@ -12,73 +21,340 @@ pub fn enumerate_bounds(
     lower: &BigVector,
     upper: &BigVector,
     origin: &BigVector,
-) -> Vec<BigVector> {
-    let size = basis.row_count();
-    let mut builder = OptimizeBuilder::of_size(size);
-    for i in 0..size {
-        builder = builder
-            .with_lower_bound_idx(i, lower.get(i))
-            .with_upper_bound_idx(i, upper.get(i));
-    }
-    let constraints = builder.build();
-    enumerate(basis, origin, &constraints)
+) -> Result<Vec<BigVector>, SearchError> {
+    enumerate_bounds_with_progress(basis, lower, upper, origin, &CancelToken::new(), &mut |_, _| {})
 }
 
-/// Get the total number of depth-0 branches for the enumeration tree.
-/// Returns (total_branches, narrowest_dimension_index).
-/// Used by the parallel WASM interface to split work across workers.
-pub fn get_branch_count(
+/// Same as [`enumerate_bounds`], reporting depth-0 progress and the points
+/// found in each branch through `on_branch` (see [`enumerate_with_progress`]).
+/// Stops early once `cancel` is triggered, returning the points found so far.
+pub fn enumerate_bounds_with_progress(
     basis: &BigMatrix,
     lower: &BigVector,
     upper: &BigVector,
     origin: &BigVector,
-) -> i64 {
-    let size = basis.row_count();
-    let mut builder = OptimizeBuilder::of_size(size);
-    for i in 0..size {
-        builder = builder
-            .with_lower_bound_idx(i, lower.get(i))
-            .with_upper_bound_idx(i, upper.get(i));
+    cancel: &CancelToken,
+    on_branch: &mut dyn FnMut(BranchProgress, &[BigVector]),
+) -> Result<Vec<BigVector>, SearchError> {
+    Ok(enumerate_bounds_with_stats(basis, lower, upper, origin, cancel, on_branch)?.0)
+}
+
+/// Same as [`enumerate_bounds_with_progress`], also returning counts of the
+/// work the search did.
+pub fn enumerate_bounds_with_stats(
+    basis: &BigMatrix,
+    lower: &BigVector,
+    upper: &BigVector,
+    origin: &BigVector,
+    cancel: &CancelToken,
+    on_branch: &mut dyn FnMut(BranchProgress, &[BigVector]),
+) -> Result<(Vec<BigVector>, EnumerationStats), SearchError> {
+    enumerate_bounds_with_backend(basis, lower, upper, origin, EnumerationBackend::LinearProgram, cancel, on_branch)
+}
+
+/// Same as [`enumerate_bounds_with_stats`], searching with `backend`.
+/// Both backends find the same points; only their order differs.
+pub fn enumerate_bounds_with_backend(
+    basis: &BigMatrix,
+    lower: &BigVector,
+    upper: &BigVector,
+    origin: &BigVector,
+    backend: EnumerationBackend,
+    cancel: &CancelToken,
+    on_branch: &mut dyn FnMut(BranchProgress, &[BigVector]),
+) -> Result<(Vec<BigVector>, EnumerationStats), SearchError> {
+    let stats = StatCounters::default();
+    let sphere_results = match backend {
+        EnumerationBackend::LinearProgram => None,
+        EnumerationBackend::SphereDecoding => {
+            sphere::enumerate_box(basis, lower, upper, origin, cancel, &stats, on_branch)
+        }
+    };
+    let results = match sphere_results {
+        Some(results) => results,
+        None => match bounds_constraints(lower, upper)? {
+            Some(constraints) => enumerate_rt(basis, origin, &constraints, cancel, &stats, on_branch)?,
+            None => Vec::new(),
+        },
+    };
+    Ok((results, stats.snapshot()))
+}
+
+/// Same as [`enumerate_bounds`], keeping only the points that also satisfy
+/// `inequalities * point <= rhs`, one half-space per row, for constraints
+/// that couple dimensions (such as the two halves of a `nextLong`). The box
+/// keeps the region bounded. Returns no points if the constraints leave no
+/// room at all.
+pub fn enumerate_bounds_with_inequalities(
+    basis: &BigMatrix,
+    lower: &BigVector,
+    upper: &BigVector,
+    origin: &BigVector,
+    inequalities: &BigMatrix,
+    rhs: &BigVector,
+) -> Result<Vec<BigVector>, SearchError> {
+    match bounds_builder(lower, upper).with_inequalities(inequalities, rhs).try_build()? {
+        Some(constraints) => enumerate(basis, origin, &constraints),
+        None => Ok(Vec::new()),
     }
-    let constraints = builder.build();
+}
 
-    let root_inverse = lu_decomposition::inverse(basis);
-    let root_origin = root_inverse.multiply_vector(origin);
+/// How [`enumerate_bounds_with_backend`] bounds each level of the search tree.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EnumerationBackend {
+    /// Two exact linear programs per node over the box constraints, as in
+    /// LattiCG. Slow, but every node it visits can still reach the box.
+    #[default]
+    LinearProgram,
+    /// Schnorr–Euchner / Fincke–Pohst recursion over the ball around the
+    /// box, with floating-point intervals and an exact check of each leaf.
+    /// Far cheaper per node; visits more nodes as the dimension grows.
+    /// Always sequential. Needs a square, nonsingular basis, and falls back
+    /// to [`LinearProgram`](Self::LinearProgram) for any other.
+    SphereDecoding,
+}
 
-    // Compute widths and find narrowest dimension (same logic as enumerate)
-    let mut widths: Vec<BigFraction> = Vec::with_capacity(size);
-    let mut order: Vec<usize> = Vec::with_capacity(size);
+/// Progress of the depth-0 sweep of an enumeration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BranchProgress {
+    /// Depth-0 branches fully explored so far.
+    pub branches_done: i64,
+    /// Depth-0 branches that will be explored in this call.
+    pub total_branches: i64,
+    /// Lattice points found so far (before any seed filtering).
+    pub solutions_found: usize,
+}
 
-    for i in 0..size {
-        let gradient = root_inverse.get_row(i);
-        let (_, min_val) = constraints.clone().minimize(&gradient);
-        let (_, max_val) = constraints.clone().maximize(&gradient);
-        let w = max_val.sub_frac(&min_val);
-        widths.push(w);
-        order.push(i);
+/// Work done by an enumeration, for profiling.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EnumerationStats {
+    /// Search tree nodes visited, leaves included.
+    pub nodes_visited: u64,
+    /// Linear programs solved to bound a dimension.
+    pub lp_solves: u64,
+    /// Nodes whose bounds admit no integer value in their dimension.
+    pub branches_pruned: u64,
+    /// Deepest level reached. Equals the dimension count once a point is found.
+    pub max_depth: usize,
+}
+
+/// Counters behind [`EnumerationStats`], shared by every worker.
+#[derive(Default)]
+pub(super) struct StatCounters {
+    nodes_visited: AtomicU64,
+    lp_solves: AtomicU64,
+    branches_pruned: AtomicU64,
+    max_depth: AtomicUsize,
+}
+
+impl StatCounters {
+    pub(super) fn visit(&self, depth: usize) {
+        self.nodes_visited.fetch_add(1, Ordering::Relaxed);
+        self.max_depth.fetch_max(depth, Ordering::Relaxed);
     }
 
-    order.sort_by(|&a, &b| widths[a].cmp(&widths[b]));
+    pub(super) fn solve(&self, count: u64) {
+        self.lp_solves.fetch_add(count, Ordering::Relaxed);
+    }
 
-    // The narrowest dimension is order[0] — that's what depth-0 explores.
-    let index = order[0];
-    let gradient = root_inverse.get_row(index);
-    let offset = root_origin.get(index).clone();
+    pub(super) fn prune(&self) {
+        self.branches_pruned.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(super) fn snapshot(&self) -> EnumerationStats {
+        EnumerationStats {
+            nodes_visited: self.nodes_visited.load(Ordering::Relaxed),
+            lp_solves: self.lp_solves.load(Ordering::Relaxed),
+            branches_pruned: self.branches_pruned.load(Ordering::Relaxed),
+            max_depth: self.max_depth.load(Ordering::Relaxed),
+        }
+    }
+}
 
-    let (_, min_val) = constraints.clone().minimize(&gradient);
-    let (_, max_val) = constraints.clone().maximize(&gradient);
+/// Why an enumeration stopped without its points.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchError {
+    /// A linear program bounding the search failed.
+    Solve(SolveError),
+    /// Exact arithmetic on the basis failed.
+    Math(MathError),
+    /// A [`SearchLimits::timeout`] on a target without a clock (wasm).
+    TimeoutUnsupported,
+}
 
-    let min_int = FracOps::ceil(&min_val.sub_frac(&offset));
-    let max_int = FracOps::floor(&max_val.sub_frac(&offset));
+impl fmt::Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchError::Solve(e) => write!(f, "Bounding LP failed: {}", e),
+            SearchError::Math(e) => write!(f, "Basis arithmetic failed: {}", e),
+            SearchError::TimeoutUnsupported => write!(f, "Search timeouts are not supported on this target"),
+        }
+    }
+}
+
+impl std::error::Error for SearchError {}
+
+impl From<SolveError> for SearchError {
+    fn from(e: SolveError) -> Self {
+        SearchError::Solve(e)
+    }
+}
+
+impl From<MathError> for SearchError {
+    fn from(e: MathError) -> Self {
+        SearchError::Math(e)
+    }
+}
+
+/// Conditions for ending a search before the whole region is explored.
+/// The default has no limits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// Stop once this many solutions have been found.
+    pub max_results: Option<usize>,
+    /// Stop once this much wall-clock time has passed. Searches with a
+    /// timeout fail with [`SearchError::TimeoutUnsupported`] on wasm.
+    pub timeout: Option<Duration>,
+}
+
+/// Solutions of a search under [`SearchLimits`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LimitedResults<T> {
+    /// Solutions found before the search stopped, in enumeration order.
+    pub results: Vec<T>,
+    /// Whether a limit or the stop predicate ended the search early, so
+    /// there may be more solutions.
+    pub truncated: bool,
+}
+
+/// Run a search under `limits`. `run` gets the token to stop on and a sink
+/// for the solutions of each finished branch, which checks the limits and
+/// `stop_when`; solutions reported after the search was stopped are dropped.
+pub(crate) fn collect_limited<T: Clone>(
+    limits: &SearchLimits,
+    stop_when: &mut dyn FnMut(&T) -> bool,
+    run: impl FnOnce(&CancelToken, &mut dyn FnMut(&[T])) -> Result<(), SearchError>,
+) -> Result<LimitedResults<T>, SearchError> {
+    let cancel = CancelToken::new();
+    #[cfg(not(target_arch = "wasm32"))]
+    let cancel = match limits.timeout {
+        Some(timeout) => cancel.with_timeout(timeout),
+        None => cancel,
+    };
+    #[cfg(target_arch = "wasm32")]
+    if limits.timeout.is_some() {
+        return Err(SearchError::TimeoutUnsupported);
+    }
+
+    let mut results = Vec::new();
+    let mut stopped = false;
+    run(&cancel, &mut |found| {
+        for item in found {
+            if stopped {
+                return;
+            }
+            results.push(item.clone());
+            if stop_when(item) || limits.max_results.is_some_and(|max| results.len() >= max) {
+                stopped = true;
+                cancel.cancel();
+            }
+        }
+    })?;
+
+    Ok(LimitedResults { results, truncated: stopped || cancel.is_cancelled() })
+}
+
+/// Get the total number of depth-0 branches for the enumeration tree.
+/// Returns (total_branches, narrowest_dimension_index).
+/// Used by the parallel WASM interface to split work across workers.
+pub fn get_branch_count(
+    basis: &BigMatrix,
+    lower: &BigVector,
+    upper: &BigVector,
+    origin: &BigVector,
+) -> Result<i64, SearchError> {
+    let Some(space) = bounds_search_space(basis, lower, upper, origin)? else {
+        return Ok(0);
+    };
+
+    // Find narrowest dimension (same logic as enumerate)
+    let root = root_node(&space, origin)?;
+    if root.size == 0 {
+        return Ok(1);
+    }
+
+    // The narrowest dimension is order[0] — that's what depth-0 explores.
+    let index = root.order[0];
+    let (min_int, max_int) = integer_range(&root.constraints, &root.inverse.get_row(index), root.origin.get(index))?;
 
     if min_int > max_int {
-        return 0;
+        return Ok(0);
     }
 
     // Number of integer values = max_int - min_int + 1
     let count = max_int.int_sub(&min_int).int_add(&Int::int_one());
     // Clamp to i64 (should always fit for reasonable problems)
-    count.int_to_i64()
+    Ok(count.int_to_i64_checked().unwrap_or(i64::MAX))
+}
+
+/// Estimated work of each depth-0 branch of [`get_branch_count`], in branch
+/// order: how many integer values the next dimension of the enumeration
+/// order can take once the branch's value is fixed (zero if none, one when
+/// there is no next dimension). Subtree sizes differ by orders of magnitude,
+/// which this tracks far better than counting branches, at the cost of two
+/// LPs per branch.
+pub fn get_branch_weights(
+    basis: &BigMatrix,
+    lower: &BigVector,
+    upper: &BigVector,
+    origin: &BigVector,
+) -> Result<Vec<u64>, SearchError> {
+    let Some(space) = bounds_search_space(basis, lower, upper, origin)? else {
+        return Ok(Vec::new());
+    };
+    let mut weights = Vec::new();
+    visit_nodes(&root_node(&space, origin)?, 1, &(0..i64::MAX), &mut 0, &mut |node| {
+        if node.depth == node.size {
+            weights.push(1);
+            return Ok(());
+        }
+        let index = node.order[node.depth];
+        let (min_int, max_int) = integer_range(&node.constraints, &node.inverse.get_row(index), node.origin.get(index))?;
+        let count = max_int.int_sub(&min_int).int_add(&Int::int_one());
+        weights.push(if min_int > max_int { 0 } else { count.int_to_i64_checked().map_or(u64::MAX, |c| c as u64) });
+        Ok(())
+    })?;
+    Ok(weights)
+}
+
+/// Split the branches of `weights` (see [`get_branch_weights`]) into at most
+/// `parts` contiguous `[start, end)` ranges of about equal total weight, for
+/// [`enumerate_bounds_partial`]. Every branch also counts one unit for its
+/// own LPs, so runs of empty branches still get spread out. Each part takes
+/// its share of the weight the earlier parts left, so one heavy branch does
+/// not leave the next part nearly empty.
+pub fn balanced_branch_ranges(weights: &[u64], parts: usize) -> Vec<(i64, i64)> {
+    if weights.is_empty() {
+        return Vec::new();
+    }
+    let parts = parts.clamp(1, weights.len()) as u128;
+    let mut remaining: u128 = weights.iter().map(|&w| w as u128 + 1).sum();
+
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut acc = 0u128;
+    for (i, &w) in weights.iter().enumerate() {
+        acc += w as u128 + 1;
+        let left = parts - ranges.len() as u128;
+        if left > 1 && acc * left >= remaining && i + 1 < weights.len() {
+            ranges.push((start as i64, i as i64 + 1));
+            start = i + 1;
+            remaining -= acc;
+            acc = 0;
+        }
+    }
+    ranges.push((start as i64, weights.len() as i64));
+    ranges
 }
 
 /// Enumerate only a subset of depth-0 branches [branch_start, branch_end).
@@ -91,83 +367,262 @@ pub fn enumerate_bounds_partial(
     origin: &BigVector,
     branch_start: i64,
     branch_end: i64,
-) -> Vec<BigVector> {
-    let size = basis.row_count();
-    let mut builder = OptimizeBuilder::of_size(size);
-    for i in 0..size {
-        builder = builder
-            .with_lower_bound_idx(i, lower.get(i))
-            .with_upper_bound_idx(i, upper.get(i));
+) -> Result<Vec<BigVector>, SearchError> {
+    match bounds_constraints(lower, upper)? {
+        Some(constraints) => enumerate_rt_partial(basis, origin, &constraints, branch_start, branch_end),
+        None => Ok(Vec::new()),
     }
-    let constraints = builder.build();
-    enumerate_partial(basis, origin, &constraints, branch_start, branch_end)
 }
 
-/// Partial enumerate: only processes depth-0 branches in [branch_start, branch_end).
-fn enumerate_partial(
+/// Low-level partial enumerate: only processes depth-0 branches in
+/// [branch_start, branch_end).
+fn enumerate_rt_partial(
     basis: &BigMatrix,
     origin: &BigVector,
     constraints: &Optimize,
     branch_start: i64,
     branch_end: i64,
-) -> Vec<BigVector> {
-    let root_inverse = lu_decomposition::inverse(basis);
-    let root_origin = root_inverse.multiply_vector(origin);
-    enumerate_rt_partial(basis, origin, constraints, &root_inverse, &root_origin, branch_start, branch_end)
+) -> Result<Vec<BigVector>, SearchError> {
+    let Some(space) = SearchSpace::new(basis, origin, constraints)? else {
+        return Ok(Vec::new());
+    };
+
+    // Compute widths and sort (same as full enumerate)
+    verbose_eprintln!("[enumerate-partial] Computing dimension widths for {} dimensions...", space.inverse.row_count());
+    let root = root_node(&space, origin)?;
+
+    // Only explore depth-0 branches in [branch_start, branch_end)
+    let results = collect_solutions_depth0_partial(
+        &root, branch_start, branch_end, &CancelToken::new(), &StatCounters::default(), &mut |_, _| {},
+    )?;
+
+    Ok(results
+        .iter()
+        .map(|fixed| to_lattice_point(&space.basis, origin, fixed))
+        .collect())
 }
 
-/// Low-level partial enumerate.
-fn enumerate_rt_partial(
+/// Number of branches when the work is split over the first `depth + 1`
+/// dimensions of the enumeration order: each branch fixes one integer value
+/// in each of them. `depth == 0` gives [`get_branch_count`].
+pub fn get_branch_count_at_depth(
     basis: &BigMatrix,
+    lower: &BigVector,
+    upper: &BigVector,
     origin: &BigVector,
-    constraints: &Optimize,
-    root_inverse: &BigMatrix,
-    root_origin: &BigVector,
+    depth: usize,
+) -> Result<i64, SearchError> {
+    let Some(space) = bounds_search_space(basis, lower, upper, origin)? else {
+        return Ok(0);
+    };
+    let mut count = 0;
+    visit_nodes(&root_node(&space, origin)?, depth + 1, &(i64::MAX..i64::MAX), &mut count, &mut |_| Ok(()))?;
+    Ok(count)
+}
+
+/// Enumerate only the branches [branch_start, branch_end) of the split at
+/// `depth` (see [`get_branch_count_at_depth`]). Branches are numbered in the
+/// order the full enumeration visits them.
+pub fn enumerate_bounds_partial_at_depth(
+    basis: &BigMatrix,
+    lower: &BigVector,
+    upper: &BigVector,
+    origin: &BigVector,
+    depth: usize,
     branch_start: i64,
     branch_end: i64,
-) -> Vec<BigVector> {
-    let root_size = basis.row_count();
-    let root_fixed = BigVector::new(root_size);
-    let root_constraints = constraints.clone();
+) -> Result<Vec<BigVector>, SearchError> {
+    let Some(space) = bounds_search_space(basis, lower, upper, origin)? else {
+        return Ok(Vec::new());
+    };
+    verbose_eprintln!("[enumerate-partial] Exploring branches {}-{} at depth {}", branch_start, branch_end, depth);
 
-    // Compute widths and sort (same as full enumerate)
-    let mut widths: Vec<BigFraction> = Vec::with_capacity(root_size);
-    let mut order: Vec<usize> = Vec::with_capacity(root_size);
+    let mut results = Vec::new();
+    let (cancel, stats) = (CancelToken::new(), StatCounters::default());
+    let wanted = branch_start.max(0)..branch_end;
+    visit_nodes(&root_node(&space, origin)?, depth + 1, &wanted, &mut 0, &mut |node| {
+        collect_solutions(node, &mut results, &cancel, &stats)
+    })?;
+    Ok(results
+        .iter()
+        .map(|fixed| to_lattice_point(&space.basis, origin, fixed))
+        .collect())
+}
 
-    verbose_eprintln!("[enumerate-partial] Computing dimension widths for {} dimensions...", root_size);
+/// Box constraints `lower <= x <= upper`, or `None` if the box is empty.
+fn bounds_constraints(lower: &BigVector, upper: &BigVector) -> Result<Option<Optimize>, SolveError> {
+    bounds_builder(lower, upper).try_build()
+}
 
-    for i in 0..root_size {
-        let gradient = root_inverse.get_row(i);
-        let (_, min_val) = root_constraints.clone().minimize(&gradient);
-        let (_, max_val) = root_constraints.clone().maximize(&gradient);
-        let w = max_val.sub_frac(&min_val);
-        widths.push(w);
-        order.push(i);
+/// [`SearchSpace::new`] over the box `lower <= x <= upper`.
+fn bounds_search_space(
+    basis: &BigMatrix,
+    lower: &BigVector,
+    upper: &BigVector,
+    origin: &BigVector,
+) -> Result<Option<SearchSpace>, SearchError> {
+    match bounds_constraints(lower, upper)? {
+        Some(constraints) => SearchSpace::new(basis, origin, &constraints),
+        None => Ok(None),
     }
+}
 
-    order.sort_by(|&a, &b| widths[a].cmp(&widths[b]));
+fn bounds_builder(lower: &BigVector, upper: &BigVector) -> OptimizeBuilder {
+    let size = lower.dimension();
+    let mut builder = OptimizeBuilder::of_size(size);
+    for i in 0..size {
+        builder = builder
+            .with_lower_bound_idx(i, lower.get(i))
+            .with_upper_bound_idx(i, upper.get(i));
+    }
+    builder
+}
 
-    let root = SearchNode {
-        size: root_size,
+/// The lattice a search runs over: a basis with linearly independent
+/// columns, a left inverse mapping points back to their coefficients, and
+/// the constraints to search.
+///
+/// A square, nonsingular basis is used as given. The columns of any other
+/// basis generate a lattice of lower rank than the dimension (LLL leaves
+/// such bases when it drops dependent vectors): it is replaced with an
+/// independent basis of the same lattice, and the constraints gain the
+/// equalities that keep points on `origin + span(basis)`, so the search
+/// runs in that subspace and finds the same points.
+struct SearchSpace {
+    basis: BigMatrix,
+    inverse: BigMatrix,
+    constraints: Optimize,
+}
+
+impl SearchSpace {
+    /// `None` if the subspace misses the region entirely.
+    fn new(basis: &BigMatrix, origin: &BigVector, constraints: &Optimize) -> Result<Option<SearchSpace>, SearchError> {
+        if basis.is_square() {
+            if let Ok(inverse) = lu_decomposition::inverse(basis) {
+                return Ok(Some(SearchSpace { basis: basis.clone(), inverse, constraints: constraints.clone() }));
+            }
+        }
+
+        let dimension = basis.row_count();
+        let independent = independent_basis(basis);
+        let rank = independent.col_count();
+        verbose_eprintln!("[enumerate] Basis has rank {} in {} dimensions, searching its span", rank, dimension);
+
+        // Coordinates whose rows of the basis are independent fix a point of
+        // the span; the left inverse reads the coefficients off them.
+        let mut echelon = independent.transpose();
+        let reduction = gauss_jordan::reduce_all(&mut echelon);
+        let coordinates: Vec<usize> = (0..dimension).filter(|&i| reduction.is_pivot(i)).collect();
+        let mut square = BigMatrix::new(rank, rank);
+        for (row, &i) in coordinates.iter().enumerate() {
+            square.copy_row_from(row, &independent, i);
+        }
+        let square_inverse = lu_decomposition::inverse(&square)?;
+        let mut inverse = BigMatrix::new(rank, dimension);
+        for (col, &i) in coordinates.iter().enumerate() {
+            inverse.set_col(i, &square_inverse.get_col(col));
+        }
+
+        // Each other coordinate follows from those: `h . (x - origin) == 0`
+        // for every `h` in the nullspace of the transposed basis.
+        let mut constraints = constraints.clone();
+        for h in &reduction.nullspace(&echelon) {
+            let Some(bounded) = constraints.try_with_strict_bound(h, &h.dot(origin))? else {
+                return Ok(None);
+            };
+            constraints = bounded;
+        }
+
+        Ok(Some(SearchSpace { basis: independent, inverse, constraints }))
+    }
+}
+
+/// A basis with linearly independent columns of the lattice generated by
+/// the columns of `basis`.
+fn independent_basis(basis: &BigMatrix) -> BigMatrix {
+    let dimension = basis.row_count();
+    if (0..basis.col_count()).all(|col| basis.get_col(col).is_zero()) {
+        return BigMatrix::new(dimension, 0);
+    }
+    lll::reduce_default(&basis.transpose()).reduced_basis.transpose()
+}
+
+/// The root of the search tree, with dimensions ordered narrowest first.
+fn root_node(space: &SearchSpace, origin: &BigVector) -> Result<SearchNode, SearchError> {
+    let size = space.inverse.row_count();
+    let root_origin = space.inverse.multiply_vector(origin);
+
+    let order = narrowest_first(&space.inverse, &space.constraints, &StatCounters::default())?;
+
+    Ok(SearchNode {
+        size,
         depth: 0,
-        inverse: root_inverse.clone(),
-        origin: root_origin.clone(),
-        fixed: root_fixed,
-        constraints: root_constraints,
+        inverse: space.inverse.clone(),
+        origin: root_origin,
+        fixed: BigVector::new(size),
+        constraints: space.constraints.clone(),
         order,
-    };
+    })
+}
 
-    // Only explore depth-0 branches in [branch_start, branch_end)
-    let mut results = Vec::new();
-    collect_solutions_depth0_partial(&root, &mut results, branch_start, branch_end);
+/// Walk the nodes at `depth` below `node` (or the leaves above it) in
+/// enumeration order, center-outward at every level, numbering them from
+/// `*next` on. Only the nodes numbered in `wanted` are built and handed to
+/// `visit`; the others in the last level are counted from their parent's
+/// range, and the walk stops once it is past `wanted`, so an empty
+/// `wanted` at `i64::MAX` only counts. `*next` ends past the last node
+/// numbered.
+fn visit_nodes(
+    node: &SearchNode,
+    depth: usize,
+    wanted: &Range<i64>,
+    next: &mut i64,
+    visit: &mut dyn FnMut(&SearchNode) -> Result<(), SearchError>,
+) -> Result<(), SearchError> {
+    if node.depth == depth || node.depth == node.size {
+        if wanted.contains(next) {
+            visit(node)?;
+        }
+        *next += 1;
+        return Ok(());
+    }
+
+    let index = node.order[node.depth];
+    let (min_int, max_int) = integer_range(&node.constraints, &node.inverse.get_row(index), node.origin.get(index))?;
+    if min_int > max_int {
+        return Ok(());
+    }
+    let count = max_int.int_sub(&min_int).int_to_i64_checked().map_or(i64::MAX, |c| c.saturating_add(1));
+    let center = min_int.int_add(&max_int).int_shr(1);
+    // The center holds at least as many values below as above, so the
+    // values alternate until the last one.
+    let value = |k: i64| center.int_add_i64(if k % 2 == 0 { -(k / 2) } else { k / 2 + 1 });
 
-    results
-        .into_iter()
-        .map(|fixed| {
-            let transformed = basis.multiply_vector(&fixed);
-            origin.add(&transformed)
-        })
-        .collect()
+    if node.depth + 1 == depth || node.depth + 1 == node.size {
+        let base = *next;
+        *next = base.saturating_add(count);
+        let first = wanted.start.saturating_sub(base).clamp(0, count);
+        let last = wanted.end.saturating_sub(base).clamp(first, count);
+        for k in first..last {
+            visit(&create_child(node, index, &value(k))?)?;
+        }
+        return Ok(());
+    }
+    for k in 0..count {
+        if *next >= wanted.end {
+            break;
+        }
+        visit_nodes(&create_child(node, index, &value(k))?, depth, wanted, next, visit)?;
+    }
+    Ok(())
+}
+
+/// The integers `v` with `gradient . x == v + offset` at some point of
+/// `constraints`, as the range `min..=max`, empty if `min > max`.
+fn integer_range(constraints: &Optimize, gradient: &BigVector, offset: &BigFraction) -> Result<(Int, Int), SolveError> {
+    let min = constraints.clone().try_minimize(gradient)?.value;
+    let max = constraints.clone().try_maximize(gradient)?.value;
+    Ok((FracOps::ceil(&min.sub_frac(offset)), FracOps::floor(&max.sub_frac(offset))))
 }
 
 /// Enumerate lattice points within the feasible region defined by constraints.
@@ -176,10 +631,148 @@ pub fn enumerate(
     basis: &BigMatrix,
     origin: &BigVector,
     constraints: &Optimize,
-) -> Vec<BigVector> {
-    let root_inverse = lu_decomposition::inverse(basis);
-    let root_origin = root_inverse.multiply_vector(origin);
-    enumerate_rt(basis, origin, constraints, &root_inverse, &root_origin)
+) -> Result<Vec<BigVector>, SearchError> {
+    enumerate_with_progress(basis, origin, constraints, &CancelToken::new(), &mut |_, _| {})
+}
+
+/// Same as [`enumerate`], but calls `on_branch` once before the depth-0 sweep
+/// starts (with no points) and again after every depth-0 branch has been fully
+/// explored, together with the lattice points found in that branch.
+/// Stops early once `cancel` is triggered, returning the points found so far.
+pub fn enumerate_with_progress(
+    basis: &BigMatrix,
+    origin: &BigVector,
+    constraints: &Optimize,
+    cancel: &CancelToken,
+    on_branch: &mut dyn FnMut(BranchProgress, &[BigVector]),
+) -> Result<Vec<BigVector>, SearchError> {
+    enumerate_rt(basis, origin, constraints, cancel, &StatCounters::default(), on_branch)
+}
+
+/// Same as [`enumerate`], but stops once one of `limits` is reached or
+/// `stop_when` returns true for a found point (which is still returned).
+pub fn enumerate_limited(
+    basis: &BigMatrix,
+    origin: &BigVector,
+    constraints: &Optimize,
+    limits: &SearchLimits,
+    stop_when: &mut dyn FnMut(&BigVector) -> bool,
+) -> Result<LimitedResults<BigVector>, SearchError> {
+    collect_limited(limits, stop_when, |cancel, sink| {
+        enumerate_with_progress(basis, origin, constraints, cancel, &mut |_, points| sink(points)).map(drop)
+    })
+}
+
+/// Same as [`enumerate_bounds`], but stops once one of `limits` is reached
+/// or `stop_when` returns true for a found point (which is still returned).
+///
+/// Unlike [`enumerate_limited`], which checks between depth-0 branches, the
+/// walk stops at the very point that hits a limit, so a box that turns out
+/// to hold millions of points costs no more than `max_results` of them. The
+/// tree is walked on the calling thread.
+pub fn enumerate_bounds_limited(
+    basis: &BigMatrix,
+    lower: &BigVector,
+    upper: &BigVector,
+    origin: &BigVector,
+    limits: &SearchLimits,
+    stop_when: &mut dyn FnMut(&BigVector) -> bool,
+) -> Result<LimitedResults<BigVector>, SearchError> {
+    collect_limited(limits, stop_when, |cancel, sink| {
+        let Some(space) = bounds_search_space(basis, lower, upper, origin)? else {
+            return Ok(());
+        };
+        let mut walk = Walk::new(&root_node(&space, origin)?);
+        let stats = StatCounters::default();
+        while let Some(fixed) = walk.next_leaf(cancel, &stats)? {
+            sink(&[to_lattice_point(&space.basis, origin, &fixed)]);
+        }
+        Ok(())
+    })
+}
+
+/// Same as [`enumerate`], handing each point to `on_point` as soon as it is
+/// found instead of collecting them. Stops as soon as `on_point` breaks, and
+/// returns whether it did.
+pub fn enumerate_streaming(
+    basis: &BigMatrix,
+    origin: &BigVector,
+    constraints: &Optimize,
+    on_point: &mut dyn FnMut(BigVector) -> ControlFlow<()>,
+) -> Result<ControlFlow<()>, SearchError> {
+    for point in enumerate_iter(basis, origin, constraints) {
+        if on_point(point?).is_break() {
+            return Ok(ControlFlow::Break(()));
+        }
+    }
+    Ok(ControlFlow::Continue(()))
+}
+
+/// The points of [`enumerate`] as an iterator, in the same order. The search
+/// advances only as points are pulled, so dropping it stops the search.
+/// A failed search yields its error and ends.
+pub fn enumerate_iter(basis: &BigMatrix, origin: &BigVector, constraints: &Optimize) -> LatticePoints {
+    let mut points = LatticePoints {
+        basis: basis.clone(),
+        origin: origin.clone(),
+        walk: None,
+        error: None,
+        cancel: CancelToken::new(),
+        stats: StatCounters::default(),
+    };
+    let root = || -> Result<Option<(SearchSpace, SearchNode)>, SearchError> {
+        let Some(space) = SearchSpace::new(basis, origin, constraints)? else {
+            return Ok(None);
+        };
+        let root = root_node(&space, origin)?;
+        Ok(Some((space, root)))
+    };
+    match root() {
+        Ok(Some((space, root))) => {
+            points.walk = Some(Walk::new(&root));
+            points.basis = space.basis;
+        }
+        Ok(None) => {}
+        Err(e) => points.error = Some(e),
+    }
+    points
+}
+
+/// Iterator returned by [`enumerate_iter`].
+pub struct LatticePoints {
+    basis: BigMatrix,
+    origin: BigVector,
+    /// `None` if the region holds no point of the lattice's span, or once
+    /// the search failed.
+    walk: Option<Walk>,
+    /// Why the search failed before its first point, until it is yielded.
+    error: Option<SearchError>,
+    cancel: CancelToken,
+    stats: StatCounters,
+}
+
+impl LatticePoints {
+    /// Work done by the search so far.
+    pub fn stats(&self) -> EnumerationStats {
+        self.stats.snapshot()
+    }
+}
+
+impl Iterator for LatticePoints {
+    type Item = Result<BigVector, SearchError>;
+
+    fn next(&mut self) -> Option<Result<BigVector, SearchError>> {
+        if let Some(e) = self.error.take() {
+            return Some(Err(e));
+        }
+        match self.walk.as_mut()?.next_leaf(&self.cancel, &self.stats) {
+            Ok(fixed) => Some(Ok(to_lattice_point(&self.basis, &self.origin, &fixed?))),
+            Err(e) => {
+                self.walk = None;
+                Some(Err(e))
+            }
+        }
+    }
 }
 
 /// Low-level enumerate matching EnumerateRt.enumerate().
@@ -187,134 +780,302 @@ fn enumerate_rt(
     basis: &BigMatrix,
     origin: &BigVector,
     constraints: &Optimize,
-    root_inverse: &BigMatrix,
-    root_origin: &BigVector,
-) -> Vec<BigVector> {
-    let root_size = basis.row_count();
+    cancel: &CancelToken,
+    stats: &StatCounters,
+    on_branch: &mut dyn FnMut(BranchProgress, &[BigVector]),
+) -> Result<Vec<BigVector>, SearchError> {
+    let Some(space) = SearchSpace::new(basis, origin, constraints)? else {
+        return Ok(Vec::new());
+    };
+    let root_inverse = &space.inverse;
+    let root_origin = root_inverse.multiply_vector(origin);
+    let root_size = root_inverse.row_count();
     let root_fixed = BigVector::new(root_size);
-    let root_constraints = constraints.clone();
+    let root_constraints = space.constraints.clone();
+    let basis = &space.basis;
 
     // Compute widths for each dimension and sort by width (narrow first)
-    let mut widths: Vec<BigFraction> = Vec::with_capacity(root_size);
-    let mut order: Vec<usize> = Vec::with_capacity(root_size);
-
     verbose_eprintln!("[enumerate] Computing dimension widths for {} dimensions (LP table: {}x{})...",
              root_size, root_constraints.table_size().0, root_constraints.table_size().1);
-
-    for i in 0..root_size {
-        let gradient = root_inverse.get_row(i);
-        let (_, min_val) = root_constraints.clone().minimize(&gradient);
-        let (_, max_val) = root_constraints.clone().maximize(&gradient);
-        let w = max_val.sub_frac(&min_val);
-        verbose_eprintln!("[enumerate]   dim {} width = {} (min={}, max={})", i, w, min_val, max_val);
-        widths.push(w);
-        order.push(i);
-    }
-
-    order.sort_by(|&a, &b| widths[a].cmp(&widths[b]));
+    let order = narrowest_first(root_inverse, &root_constraints, stats)?;
 
     // Recursive search
-    let mut results = Vec::new();
     let root = SearchNode {
         size: root_size,
         depth: 0,
         inverse: root_inverse.clone(),
-        origin: root_origin.clone(),
+        origin: root_origin,
         fixed: root_fixed,
         constraints: root_constraints,
         order,
     };
 
-    collect_solutions(&root, &mut results);
+    let results = collect_solutions_depth0_partial(&root, 0, i64::MAX, cancel, stats, &mut |progress, branch| {
+        let points: Vec<BigVector> = branch
+            .iter()
+            .map(|fixed| to_lattice_point(basis, origin, fixed))
+            .collect();
+        on_branch(progress, &points);
+    })?;
 
     // Map back: result = basis * fixed + origin
-    results
-        .into_iter()
-        .map(|fixed| {
-            let transformed = basis.multiply_vector(&fixed);
-            origin.add(&transformed)
-        })
-        .collect()
-}
-
-/// Recursively collect all lattice point solutions.
-fn collect_solutions(node: &SearchNode, results: &mut Vec<BigVector>) {
-    if node.depth == node.size {
-        results.push(node.fixed.clone());
+    Ok(results
+        .iter()
+        .map(|fixed| to_lattice_point(basis, origin, fixed))
+        .collect())
+}
+
+/// Dimension indices ordered by the width of the region along each of them,
+/// narrowest first: the order every search fixes them in.
+fn narrowest_first(inverse: &BigMatrix, constraints: &Optimize, stats: &StatCounters) -> Result<Vec<usize>, SolveError> {
+    let size = inverse.row_count();
+    let widths = dimension_widths(inverse, constraints)?;
+    stats.solve(2 * size as u64);
+    let mut order: Vec<usize> = (0..size).collect();
+    order.sort_by(|&a, &b| widths[a].cmp(&widths[b]));
+    Ok(order)
+}
+
+/// Width of the region along each dimension, two LPs apiece. Like the
+/// search's, every solve starts from its own copy of `constraints`.
+#[cfg(not(feature = "rayon"))]
+fn dimension_widths(inverse: &BigMatrix, constraints: &Optimize) -> Result<Vec<BigFraction>, SolveError> {
+    (0..inverse.row_count()).map(|i| dimension_width(constraints, inverse, i)).collect()
+}
+
+/// Width of the region along each dimension, two LPs apiece, solved on the
+/// thread pool. Like the search's, every solve starts from its own copy of
+/// `constraints`.
+#[cfg(feature = "rayon")]
+fn dimension_widths(inverse: &BigMatrix, constraints: &Optimize) -> Result<Vec<BigFraction>, SolveError> {
+    use rayon::prelude::*;
+
+    let run = || {
+        (0..inverse.row_count())
+            .into_par_iter()
+            .map(|i| dimension_width(constraints, inverse, i))
+            .collect()
+    };
+    match THREAD_POOL.with(|p| p.borrow().clone()) {
+        Some(pool) => pool.install(run),
+        None => run(),
+    }
+}
+
+fn dimension_width(constraints: &Optimize, inverse: &BigMatrix, index: usize) -> Result<BigFraction, SolveError> {
+    let gradient = inverse.get_row(index);
+    let min_val = constraints.clone().try_minimize(&gradient)?.value;
+    let max_val = constraints.clone().try_maximize(&gradient)?.value;
+    let width = max_val.sub_frac(&min_val);
+    let style = FractionStyle::Decimal(3);
+    verbose_eprintln!(
+        "[enumerate]   dim {} width = {} (min={}, max={})",
+        index,
+        FractionDisplay::new(&width, style),
+        FractionDisplay::new(&min_val, style),
+        FractionDisplay::new(&max_val, style)
+    );
+    Ok(width)
+}
+
+/// Map a vector of fixed basis coefficients back to a lattice point: `basis * fixed + origin`.
+pub(super) fn to_lattice_point(basis: &BigMatrix, origin: &BigVector, fixed: &BigVector) -> BigVector {
+    let transformed = basis.multiply_vector(fixed);
+    origin.add(&transformed)
+}
+
+/// Collect all lattice point solutions below `node`, depth first.
+/// Returns without exploring further once `cancel` is triggered.
+fn collect_solutions(
+    node: &SearchNode,
+    results: &mut Vec<BigVector>,
+    cancel: &CancelToken,
+    stats: &StatCounters,
+) -> Result<(), SearchError> {
+    let mut walk = Walk::new(node);
+    while let Some(fixed) = walk.next_leaf(cancel, stats)? {
+        results.push(fixed);
         if results.len() % 100 == 0 {
             verbose_eprintln!("[enumerate] Found {} solutions so far...", results.len());
         }
-        return;
     }
+    Ok(())
+}
 
-    if node.depth <= 1 {
-        verbose_eprintln!("[enumerate] Exploring depth={}/{} (dimension index={})", node.depth, node.size, node.order[node.depth]);
+/// Resumable depth-first walk over the solutions below a search node.
+///
+/// The path is kept on an explicit stack, one frame per fixed dimension,
+/// instead of recursing with a cloned [`SearchNode`] per child: only the
+/// constraints are per frame, and the fixed coefficients are set and cleared
+/// in place. The walk pauses at each solution, so it can drive an iterator.
+struct Walk {
+    size: usize,
+    start_depth: usize,
+    origin: BigVector,
+    order: Vec<usize>,
+    gradients: Vec<BigVector>,
+    fixed: BigVector,
+    stack: Vec<Frame>,
+    /// Constraints of the start node, until the walk opens it.
+    start: Option<Optimize>,
+}
+
+impl Walk {
+    fn new(node: &SearchNode) -> Self {
+        Walk {
+            size: node.size,
+            start_depth: node.depth,
+            origin: node.origin.clone(),
+            order: node.order.clone(),
+            gradients: (0..node.size).map(|i| node.inverse.get_row(i)).collect(),
+            fixed: node.fixed.clone(),
+            stack: Vec::with_capacity(node.size - node.depth),
+            start: Some(node.constraints.clone()),
+        }
     }
 
-    let index = node.order[node.depth];
-    let gradient = node.inverse.get_row(index);
-    let offset = node.origin.get(index).clone();
+    /// Fixed coefficients of the next solution, or `None` once the subtree
+    /// is exhausted or `cancel` is triggered.
+    fn next_leaf(&mut self, cancel: &CancelToken, stats: &StatCounters) -> Result<Option<BigVector>, SearchError> {
+        if let Some(constraints) = self.start.take() {
+            if cancel.is_cancelled() {
+                return Ok(None);
+            }
+            if let Some(fixed) = self.enter(self.start_depth, constraints, stats)? {
+                return Ok(Some(fixed));
+            }
+        }
 
-    // Minimize and maximize to find integer range
-    let (_, min_val) = node.constraints.clone().minimize(&gradient);
-    let (_, max_val) = node.constraints.clone().maximize(&gradient);
+        while let Some(frame) = self.stack.last_mut() {
+            let Some(value) = frame.next_value() else {
+                self.fixed.set(frame.index, BigFraction::frac_zero());
+                self.stack.pop();
+                continue;
+            };
+            if cancel.is_cancelled() {
+                return Ok(None);
+            }
 
-    let min_int = FracOps::ceil(&min_val.sub_frac(&offset));
-    let max_int = FracOps::floor(&max_val.sub_frac(&offset));
+            let index = frame.index;
+            let value = BigFraction::frac_from_int(value);
+            let bound = value.add_frac(self.origin.get(index));
+            let constraints = frame.constraints.try_with_strict_bound(&self.gradients[index], &bound)?;
+            let constraints = constraints.expect("Values in the frame's range are feasible");
+            self.fixed.set(index, value);
 
-    if min_int > max_int {
-        return;
+            let depth = self.start_depth + self.stack.len();
+            if let Some(fixed) = self.enter(depth, constraints, stats)? {
+                return Ok(Some(fixed));
+            }
+        }
+        Ok(None)
     }
 
-    // Enumerate from center outward (like the Java version)
-    let lower_start = min_int.int_add(&max_int).int_shr(1);
-    let upper_start = lower_start.int_add(&Int::int_one());
+    /// Visit the node at `depth`, pushing its frame if it has children.
+    /// Returns its coefficients if it is a solution.
+    fn enter(&mut self, depth: usize, constraints: Optimize, stats: &StatCounters) -> Result<Option<BigVector>, SearchError> {
+        stats.visit(depth);
 
-    let mut lower = lower_start.clone();
-    let mut upper = upper_start;
-    let mut either = true;
+        if depth == self.size {
+            return Ok(Some(self.fixed.clone()));
+        }
 
-    while either {
-        either = false;
+        if depth <= 1 {
+            verbose_eprintln!("[enumerate] Exploring depth={}/{} (dimension index={})", depth, self.size, self.order[depth]);
+        }
 
-        if lower >= min_int {
-            let child = create_child(node, index, &lower);
-            collect_solutions(&child, results);
-            lower = lower.int_sub(&Int::int_one());
-            either = true;
+        let index = self.order[depth];
+
+        // Minimize and maximize to find integer range. Each solve starts
+        // from its own copy of the table as the bound left it: that basis
+        // is nearer either optimum than the other optimum is, so sharing
+        // one table takes more pivots (see `bench_min_max_table_reuse`).
+        let (min, max) = integer_range(&constraints, &self.gradients[index], self.origin.get(index))?;
+        stats.solve(2);
+
+        if min > max {
+            stats.prune();
+            return Ok(None);
         }
 
-        if upper <= max_int {
-            let child = create_child(node, index, &upper);
-            collect_solutions(&child, results);
-            upper = upper.int_add(&Int::int_one());
-            either = true;
+        // Enumerate from center outward (like the Java version)
+        let lower = min.int_add(&max).int_shr(1);
+        let upper = lower.int_add(&Int::int_one());
+        self.stack.push(Frame { constraints, index, min, max, lower, upper, lower_next: true });
+        Ok(None)
+    }
+}
+
+/// One fixed dimension on the path of a [`Walk`].
+struct Frame {
+    constraints: Optimize,
+    index: usize,
+    min: Int,
+    max: Int,
+    /// Next candidates below and above the center.
+    lower: Int,
+    upper: Int,
+    lower_next: bool,
+}
+
+impl Frame {
+    /// The next value center-outward, alternating below and above the
+    /// center until both sides are exhausted.
+    fn next_value(&mut self) -> Option<Int> {
+        for _ in 0..2 {
+            let lower_turn = self.lower_next;
+            self.lower_next = !self.lower_next;
+            if lower_turn {
+                if self.lower >= self.min {
+                    let value = self.lower.clone();
+                    self.lower = self.lower.int_sub(&Int::int_one());
+                    return Some(value);
+                }
+            } else if self.upper <= self.max {
+                let value = self.upper.clone();
+                self.upper = self.upper.int_add(&Int::int_one());
+                return Some(value);
+            }
         }
+        None
     }
 }
 
 /// Collect solutions for only depth-0 branches indexed [branch_start, branch_end).
 /// Branch index 0 = center, then alternating outward (matching the center-outward pattern).
+/// `on_branch` receives the fixed coefficients found in each finished branch;
+/// the returned list holds all of them in branch order.
 fn collect_solutions_depth0_partial(
     node: &SearchNode,
-    results: &mut Vec<BigVector>,
     branch_start: i64,
     branch_end: i64,
-) {
+    cancel: &CancelToken,
+    stats: &StatCounters,
+    on_branch: &mut dyn FnMut(BranchProgress, &[BigVector]),
+) -> Result<Vec<BigVector>, SearchError> {
     assert_eq!(node.depth, 0, "collect_solutions_depth0_partial must start at depth 0");
 
-    let index = node.order[0];
-    let gradient = node.inverse.get_row(index);
-    let offset = node.origin.get(index).clone();
-
-    let (_, min_val) = node.constraints.clone().minimize(&gradient);
-    let (_, max_val) = node.constraints.clone().maximize(&gradient);
+    if node.size == 0 {
+        // A lattice of rank 0: the origin is its only point, and the
+        // constraints already pin the search to it.
+        let solutions = if branch_start <= 0 && branch_end > 0 { vec![node.fixed.clone()] } else { Vec::new() };
+        stats.visit(0);
+        let progress = BranchProgress { branches_done: 0, total_branches: solutions.len() as i64, solutions_found: 0 };
+        on_branch(progress, &[]);
+        if !solutions.is_empty() {
+            on_branch(BranchProgress { branches_done: 1, total_branches: 1, solutions_found: 1 }, &solutions);
+        }
+        return Ok(solutions);
+    }
 
-    let min_int = FracOps::ceil(&min_val.sub_frac(&offset));
-    let max_int = FracOps::floor(&max_val.sub_frac(&offset));
+    let index = node.order[0];
+    let (min_int, max_int) = integer_range(&node.constraints, &node.inverse.get_row(index), node.origin.get(index))?;
+    stats.visit(0);
+    stats.solve(2);
 
     if min_int > max_int {
-        return;
+        stats.prune();
+        return Ok(Vec::new());
     }
 
     // Build the full list of depth-0 integer values in center-outward order
@@ -341,29 +1102,155 @@ fn collect_solutions_depth0_partial(
     }
 
     let total = all_values.len() as i64;
-    let start = branch_start.max(0) as usize;
-    let end = (branch_end.min(total) as usize).min(all_values.len());
+    let end = (branch_end.min(total).max(0) as usize).min(all_values.len());
+    let start = (branch_start.max(0) as usize).min(end);
 
     verbose_eprintln!("[enumerate-partial] Exploring branches {}-{} of {} at depth 0 (dim index={})",
              start, end, total, index);
 
-    for idx in start..end {
-        let val = &all_values[idx];
-        let child = create_child(node, index, val);
-        collect_solutions(&child, results);
+    let mut progress = BranchProgress {
+        branches_done: 0,
+        total_branches: (end - start) as i64,
+        solutions_found: 0,
+    };
+    on_branch(progress, &[]);
+
+    let results = explore_branches(node, index, &all_values[start..end], cancel, stats, &mut |branch| {
+        progress.branches_done += 1;
+        progress.solutions_found += branch.len();
+        on_branch(progress, branch);
+    })?;
+
+    if cancel.is_cancelled() {
+        verbose_eprintln!("[enumerate-partial] Cancelled after {} branch(es)", progress.branches_done);
+    }
+
+    Ok(results)
+}
+
+/// Explore the given depth-0 values one after another.
+/// `on_done` receives the solutions of each finished branch.
+#[cfg(not(feature = "rayon"))]
+fn explore_branches(
+    node: &SearchNode,
+    index: usize,
+    values: &[Int],
+    cancel: &CancelToken,
+    stats: &StatCounters,
+    on_done: &mut dyn FnMut(&[BigVector]),
+) -> Result<Vec<BigVector>, SearchError> {
+    let mut results = Vec::new();
+    for val in values {
+        if cancel.is_cancelled() {
+            break;
+        }
+        let before = results.len();
+        let child = create_child(node, index, val)?;
+        collect_solutions(&child, &mut results, cancel, stats)?;
+        on_done(&results[before..]);
     }
+    Ok(results)
 }
 
-fn create_child(parent: &SearchNode, index: usize, i: &Int) -> SearchNode {
+#[cfg(feature = "rayon")]
+thread_local! {
+    /// Pool installed by [`with_thread_pool`] for enumerations on this thread.
+    static THREAD_POOL: std::cell::RefCell<Option<std::sync::Arc<rayon::ThreadPool>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Run `f` with every enumeration it starts on this thread exploring its
+/// depth-0 branches on `pool` instead of the global rayon pool.
+#[cfg(feature = "rayon")]
+pub fn with_thread_pool<R>(pool: std::sync::Arc<rayon::ThreadPool>, f: impl FnOnce() -> R) -> R {
+    /// Puts the previous pool back, also when `f` panics.
+    struct Restore(Option<std::sync::Arc<rayon::ThreadPool>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            THREAD_POOL.with(|p| *p.borrow_mut() = self.0.take());
+        }
+    }
+
+    let _restore = Restore(THREAD_POOL.with(|p| p.replace(Some(pool))));
+    f()
+}
+
+/// Explore the given depth-0 values on the rayon thread pool.
+/// `on_done` is always called on the calling thread; branch results are merged
+/// back in branch order, so the output matches the sequential sweep. Once a
+/// branch fails, the branches not yet started are skipped.
+#[cfg(feature = "rayon")]
+fn explore_branches(
+    node: &SearchNode,
+    index: usize,
+    values: &[Int],
+    cancel: &CancelToken,
+    stats: &StatCounters,
+    on_done: &mut dyn FnMut(&[BigVector]),
+) -> Result<Vec<BigVector>, SearchError> {
+    use rayon::prelude::*;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::mpsc;
+
+    let mut per_branch: Vec<Vec<BigVector>> = vec![Vec::new(); values.len()];
+    let mut error = None;
+    let failed = AtomicBool::new(false);
+    let pool = THREAD_POOL.with(|p| p.borrow().clone());
+
+    std::thread::scope(|scope| {
+        let (tx, rx) = mpsc::channel();
+        let failed = &failed;
+        // The pool is driven from a helper thread so this thread stays free to
+        // forward progress, even when the pool has a single worker.
+        scope.spawn(move || {
+            let run = move || {
+                values.par_iter().enumerate().for_each_with(tx, |tx, (i, val)| {
+                    if cancel.is_cancelled() || failed.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    let mut local = Vec::new();
+                    let branch = create_child(node, index, val)
+                        .and_then(|child| collect_solutions(&child, &mut local, cancel, stats));
+                    if branch.is_err() {
+                        failed.store(true, Ordering::Relaxed);
+                    }
+                    let _ = tx.send((i, branch.map(|()| local)));
+                });
+            };
+            match pool {
+                Some(pool) => pool.install(run),
+                None => run(),
+            }
+        });
+
+        for (i, branch) in rx {
+            match branch {
+                Ok(local) => {
+                    on_done(&local);
+                    per_branch[i] = local;
+                }
+                Err(e) => error = error.or(Some(e)),
+            }
+        }
+    });
+
+    match error {
+        Some(e) => Err(e),
+        None => Ok(per_branch.into_iter().flatten().collect()),
+    }
+}
+
+fn create_child(parent: &SearchNode, index: usize, i: &Int) -> Result<SearchNode, SearchError> {
     let gradient = parent.inverse.get_row(index);
     let offset = parent.origin.get(index).clone();
     let value = BigFraction::frac_from_bigint(i.clone());
 
-    let next_constraints = parent.constraints.with_strict_bound(&gradient, &value.add_frac(&offset));
+    let next_constraints = parent.constraints.try_with_strict_bound(&gradient, &value.add_frac(&offset))?;
+    let next_constraints = next_constraints.expect("Values in the parent's range are feasible");
     let basis_vec = BigVector::basis(parent.size, index, value);
     let next_fixed = parent.fixed.add(&basis_vec);
 
-    SearchNode {
+    Ok(SearchNode {
         size: parent.size,
         depth: parent.depth + 1,
         inverse: parent.inverse.clone(),
@@ -371,7 +1258,7 @@ fn create_child(parent: &SearchNode, index: usize, i: &Int) -> SearchNode {
         fixed: next_fixed,
         constraints: next_constraints,
         order: parent.order.clone(),
-    }
+    })
 }
 
 struct SearchNode {
@@ -383,3 +1270,176 @@ struct SearchNode {
     constraints: Optimize,
     order: Vec<usize>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lcg::lcg::LCG;
+    use crate::lcg::rand::{JavaRng, Rand};
+    use crate::reverser::random_reverser::JavaRandomReverser;
+    use std::collections::BTreeSet;
+    use std::time::Instant;
+
+    /// The lattice, bounds and origin of a reverser that saw `calls`
+    /// `nextInt(64)` calls, each known to within `slack`.
+    fn reverser_search(calls: usize, slack: i32) -> (BigMatrix, BigVector, BigVector, BigVector) {
+        let mut rand = Rand::of_internal_seed(&LCG::JAVA, 123456789);
+        let mut reverser = JavaRandomReverser::new(vec![]);
+        for _ in 0..calls {
+            let value = rand.next_int(64);
+            reverser.add_next_int_call(64, (value - slack).max(0), (value + slack).min(63));
+        }
+        reverser.prepared().unwrap();
+        reverser.prepare_enumerate_params()
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_thread_pool_restored_after_panic() {
+        let pool = |threads| std::sync::Arc::new(rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap());
+        let installed = || THREAD_POOL.with(|p| p.borrow().as_ref().map(|pool| pool.current_num_threads()));
+
+        with_thread_pool(pool(2), || {
+            let panicked = std::panic::catch_unwind(|| with_thread_pool(pool(3), || panic!("enumeration failed")));
+            assert!(panicked.is_err());
+            assert_eq!(installed(), Some(2));
+        });
+        assert_eq!(installed(), None);
+    }
+
+    #[test]
+    fn test_rank_deficient_basis_matches_independent_basis() {
+        let column = |v: [i64; 4]| BigVector::from_data(v.iter().map(|&x| BigFraction::frac_from_i64(x)).collect());
+        let (a, b, c) = (column([3, 1, 0, 2]), column([-1, 4, 1, 0]), column([0, 2, 5, -1]));
+        let basis = |columns: &[BigVector]| BigMatrix::from_rows(columns).transpose();
+        let lower = column([-6; 4]);
+        let upper = column([6; 4]);
+        let origin = column([1, 0, -1, 2]);
+        let points = |basis: &BigMatrix| {
+            let points = enumerate_bounds(basis, &lower, &upper, &origin).unwrap();
+            let distinct: BTreeSet<String> = points.iter().map(|p| p.to_string()).collect();
+            assert_eq!(distinct.len(), points.len(), "points are found once");
+            distinct
+        };
+
+        let expected = points(&basis(&[a.clone(), b.clone(), c.clone()]));
+        assert!(expected.len() > 1);
+        // Square but singular, and more generators than dimensions.
+        let square = basis(&[a.clone(), b.clone(), c.clone(), a.add(&b)]);
+        let wide = basis(&[a.add(&c), b.clone(), c.add(&c), c.clone(), a.add(&b).add(&c)]);
+        assert_eq!(points(&square), expected);
+        assert_eq!(points(&wide), expected);
+    }
+
+    #[test]
+    fn test_balanced_branch_ranges() {
+        let total = |weights: &[u64], ranges: &[(i64, i64)]| -> Vec<u64> {
+            ranges.iter().map(|&(start, end)| weights[start as usize..end as usize].iter().map(|w| w + 1).sum()).collect()
+        };
+
+        // The light branches after a heavy one are split between the
+        // parts that are left.
+        let weights = [1, 2, 100, 1, 1, 1, 2, 3];
+        let ranges = balanced_branch_ranges(&weights, 3);
+        assert_eq!(ranges, vec![(0, 3), (3, 7), (7, 8)]);
+        assert_eq!(total(&weights, &ranges), vec![106, 9, 4]);
+        let weights = [9, 1, 1, 1, 1, 1, 1, 1, 1, 1];
+        assert_eq!(total(&weights, &balanced_branch_ranges(&weights, 4)), vec![10, 6, 6, 6]);
+
+        // No more parts than branches, and every branch in exactly one.
+        let weights = [5, 0, 7];
+        assert_eq!(balanced_branch_ranges(&weights, 10), vec![(0, 1), (1, 3)]);
+        assert_eq!(balanced_branch_ranges(&[1, 1, 1], 10), vec![(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(balanced_branch_ranges(&weights, 0), vec![(0, 3)]);
+        assert!(balanced_branch_ranges(&[], 4).is_empty());
+
+        // Empty branches still cost their LPs, so they are spread out.
+        let weights = [0; 9];
+        assert_eq!(balanced_branch_ranges(&weights, 3), vec![(0, 3), (3, 6), (6, 9)]);
+    }
+
+    #[test]
+    fn test_partitions_at_depth_cover_enumeration() {
+        let (basis, lower, upper, origin) = reverser_search(10, 1);
+        let all = enumerate_bounds(&basis, &lower, &upper, &origin).unwrap();
+        assert!(!all.is_empty());
+        let weights = get_branch_weights(&basis, &lower, &upper, &origin).unwrap();
+        assert_eq!(weights.len() as i64, get_branch_count(&basis, &lower, &upper, &origin).unwrap());
+        for depth in 0..3 {
+            let count = get_branch_count_at_depth(&basis, &lower, &upper, &origin, depth).unwrap();
+            // Uneven parts, one of them empty and the last running past the end.
+            let cuts = [0, 1, 1, count / 3, count + 5];
+            let mut union = Vec::new();
+            for part in cuts.windows(2) {
+                union.extend(
+                    enumerate_bounds_partial_at_depth(&basis, &lower, &upper, &origin, depth, part[0], part[1]).unwrap(),
+                );
+            }
+            assert_eq!(union, all, "split at depth {} into {} branches", depth, count);
+        }
+    }
+
+    /// Pivots taken to bound every node below `node`, with the min and max
+    /// solves sharing one copy of the node's table, or each on its own.
+    fn tree_pivots(node: &SearchNode, shared: bool) -> u64 {
+        if node.depth == node.size {
+            return 0;
+        }
+        let index = node.order[node.depth];
+        let gradient = node.inverse.get_row(index);
+        let mut lp = node.constraints.clone();
+        let min = lp.try_minimize(&gradient).unwrap();
+        if !shared {
+            lp = node.constraints.clone();
+        }
+        let max = lp.try_maximize(&gradient).unwrap();
+
+        let offset = node.origin.get(index);
+        let mut value = FracOps::ceil(&min.value.sub_frac(offset));
+        let last = FracOps::floor(&max.value.sub_frac(offset));
+        let mut pivots = min.stats.pivots + max.stats.pivots;
+        while value <= last {
+            pivots += tree_pivots(&create_child(node, index, &value).unwrap(), shared);
+            value = value.int_add(&Int::int_one());
+        }
+        pivots
+    }
+
+    /// Pivots taken by [`dimension_widths`] on `constraints`, with every
+    /// solve on one table, or each on its own copy.
+    fn width_pivots(inverse: &BigMatrix, constraints: &Optimize, shared: bool) -> u64 {
+        let mut lp = constraints.clone();
+        let mut pivots = 0;
+        for i in 0..inverse.row_count() {
+            let gradient = inverse.get_row(i);
+            for maximize in [false, true] {
+                if !shared {
+                    lp = constraints.clone();
+                }
+                let solution = if maximize { lp.try_maximize(&gradient) } else { lp.try_minimize(&gradient) };
+                pivots += solution.unwrap().stats.pivots;
+            }
+        }
+        pivots
+    }
+
+    /// Whether the min and max solves should share a table, in the search
+    /// and for the root widths. Both copy the table per solve.
+    #[test]
+    #[ignore = "benchmark, run with `cargo test --release --lib -- --ignored --nocapture`"]
+    fn bench_min_max_table_reuse() {
+        let (basis, lower, upper, origin) = reverser_search(16, 4);
+        let space = bounds_search_space(&basis, &lower, &upper, &origin).unwrap().unwrap();
+        let root = root_node(&space, &origin).unwrap();
+        for shared in [false, true] {
+            let start = Instant::now();
+            let pivots = tree_pivots(&root, shared);
+            println!("search, shared table {:5}: {:6} pivots in {:?}", shared, pivots, start.elapsed());
+        }
+        for shared in [false, true] {
+            let start = Instant::now();
+            let pivots = width_pivots(&space.inverse, &space.constraints, shared);
+            println!("widths, shared table {:5}: {:6} pivots in {:?}", shared, pivots, start.elapsed());
+        }
+    }
+}
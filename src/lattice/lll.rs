@@ -33,6 +33,67 @@ pub struct LLLResult {
     pub num_dependant_vectors: usize,
     pub reduced_basis: BigMatrix,
     pub transformations: BigMatrix,
+    /// Squared lengths of the Gram–Schmidt vectors of `reduced_basis`.
+    /// Past `max_stage`, when it is set, they are left at zero.
+    pub gso_norms: BigVector,
+    /// Gram–Schmidt coefficients of `reduced_basis`: row `i` holds the
+    /// `mu[i][j]` for `j < i`, the rest is zero.
+    pub mu: BigMatrix,
+}
+
+impl LLLResult {
+    /// Signed determinant of the reduced basis, if it is square (as many
+    /// independent vectors as coordinates).
+    pub fn determinant(&self) -> Option<BigFraction> {
+        self.reduced_basis.is_square().then(|| self.reduced_basis.determinant())
+    }
+
+    /// Determinant of the Gram matrix `B B^T` of the reduced basis: the
+    /// squared covolume, exactly. Also defined for lattices of lower rank.
+    pub fn gram_determinant(&self) -> BigFraction {
+        let basis = &self.reduced_basis;
+        basis.multiply_matrix(&basis.transpose()).determinant()
+    }
+
+    /// Base-2 logarithm of the covolume (the volume of a fundamental
+    /// domain), which overflows `f64` for seed-sized lattices. `None` if
+    /// `max_stage` stopped the reduction early: the Gram–Schmidt norms past
+    /// it were never computed, and [`gram_determinant`](Self::gram_determinant)
+    /// gives the covolume exactly instead.
+    pub fn log2_covolume(&self) -> Option<f64> {
+        let norms = (0..self.gso_norms.dimension()).map(|i| self.gso_norms.get(i));
+        norms.map(|norm| (!norm.is_zero()).then(|| norm.frac_log2_approx() / 2.0)).sum()
+    }
+
+    /// Hermite factor: the length of the first basis vector over the
+    /// covolume to the power `1/n`. LLL typically reaches about `1.02^n`;
+    /// much worse suggests escalating to a stronger reduction. `None` when
+    /// [`log2_covolume`](Self::log2_covolume) is.
+    pub fn hermite_factor(&self) -> Option<f64> {
+        let n = self.gso_norms.dimension();
+        if n == 0 {
+            return Some(1.0);
+        }
+        let log2_first = self.reduced_basis.row_magnitude_sq(0).frac_log2_approx() / 2.0;
+        Some((log2_first - self.log2_covolume()? / n as f64).exp2())
+    }
+
+    /// The `n`-th root of [`hermite_factor`](Self::hermite_factor), which
+    /// compares across dimensions.
+    pub fn root_hermite_factor(&self) -> Option<f64> {
+        let n = self.gso_norms.dimension().max(1);
+        Some(self.hermite_factor()?.powf(1.0 / n as f64))
+    }
+
+    /// Orthogonality defect: the product of the basis vector lengths over the
+    /// covolume. One for an orthogonal basis; the closer a reduced basis gets
+    /// to one, the better the reduction.
+    pub fn orthogonality_defect(&self) -> Option<f64> {
+        let log2_lengths: f64 = (0..self.reduced_basis.row_count())
+            .map(|row| self.reduced_basis.row_magnitude_sq(row).frac_log2_approx() / 2.0)
+            .sum();
+        Some((log2_lengths - self.log2_covolume()?).exp2())
+    }
 }
 
 /// LLL lattice basis reduction.
@@ -49,8 +110,8 @@ pub fn reduce(lattice: &BigMatrix, params: &LLLParams) -> LLLResult {
     let mut coordinates = BigMatrix::identity(nb_rows);
 
     // Initialize first GSO vector
-    base_gso.set_row(0, &basis.get_row(0));
-    norms.set(0, basis.get_row(0).magnitude_sq());
+    base_gso.copy_row_from(0, &basis, 0);
+    norms.set(0, base_gso.row_magnitude_sq(0));
 
     let mut k: usize = 1;
     let mut kmax: usize = 0;
@@ -94,12 +155,16 @@ pub fn reduce(lattice: &BigMatrix, params: &LLLParams) -> LLLResult {
         let new_rows = nb_rows - p;
         basis = basis.submatrix(p, 0, new_rows, nb_cols);
         coordinates = coordinates.submatrix(p, 0, new_rows, coordinates.col_count());
+        mu = mu.submatrix(p, p, new_rows, new_rows);
+        norms = BigVector::from_data((p..nb_rows).map(|i| norms.get(i).clone()).collect());
     }
 
     LLLResult {
         num_dependant_vectors: p,
         reduced_basis: basis,
         transformations: coordinates,
+        gso_norms: norms,
+        mu,
     }
 }
 
@@ -118,6 +183,11 @@ fn count_zero_rows(basis: &BigMatrix) -> usize {
     p
 }
 
+/// Computes GSO row `k` and `mu[k][0..k]` from the rows below it.
+///
+/// Only called the first time the main loop reaches `k`; after that `red` and
+/// `swapg` keep `base_gso`, `mu` and `norms` up to date incrementally. Row `k`
+/// of `base_gso` doubles as the working buffer, so no vectors are allocated.
 fn update_gso_at(
     basis: &BigMatrix,
     base_gso: &mut BigMatrix,
@@ -125,25 +195,23 @@ fn update_gso_at(
     norms: &mut BigVector,
     k: usize,
 ) {
-    let mut new_row = basis.get_row(k);
+    base_gso.copy_row_from(k, basis, k);
     for j in 0..k {
         if !norms.get(j).is_zero() {
-            let mu_kj = basis.get_row(k).dot(&base_gso.get_row(j)).div_frac(norms.get(j));
-            mu.set(k, j, mu_kj.clone());
-            let scaled = base_gso.get_row(j).multiply_scalar(&mu_kj);
-            new_row.subtract_assign(&scaled);
+            let mu_kj = basis.row_dot(k, base_gso, j).div_frac(norms.get(j));
+            base_gso.row_subtract_scaled(k, j, &mu_kj);
+            mu.set(k, j, mu_kj);
         } else {
             mu.set(k, j, BigFraction::frac_zero());
         }
     }
-    base_gso.set_row(k, &new_row);
-    norms.set(k, new_row.magnitude_sq());
+    norms.set(k, base_gso.row_magnitude_sq(k));
 }
 
 fn test_condition(mu: &BigMatrix, norms: &BigVector, k: usize, delta: &BigFraction) -> bool {
     let mu_temp = mu.get(k, k - 1);
-    let factor = delta.sub_frac(&mu_temp.mul_frac(mu_temp));
-    *norms.get(k) < norms.get(k - 1).mul_frac(&factor)
+    let factor = delta - mu_temp.mul_frac(mu_temp);
+    *norms.get(k) < norms.get(k - 1) * factor
 }
 
 fn red(
@@ -159,24 +227,19 @@ fn red(
     }
 
     // basis[i] -= r * basis[j]
-    let row_j = basis.get_row(j).multiply_bigint(&r);
-    let mut row_i = basis.get_row(i);
-    row_i.subtract_assign(&row_j);
-    basis.set_row(i, &row_i);
+    basis.row_subtract_scaled_int(i, j, &r);
 
     // coordinates[i] -= r * coordinates[j]
-    let coord_j = coordinates.get_row(j).multiply_bigint(&r);
-    let mut coord_i = coordinates.get_row(i);
-    coord_i.subtract_assign(&coord_j);
-    coordinates.set_row(i, &coord_i);
+    coordinates.row_subtract_scaled_int(i, j, &r);
 
     // mu[i][j] -= r
-    let new_mu = mu.get(i, j).sub_frac(&BigFraction::frac_from_bigint(r.clone()));
+    let new_mu = mu.get(i, j).sub_int(&r);
     mu.set(i, j, new_mu);
 
-    for col in 0..j {
-        let new_val = mu.get(i, col).sub_frac(&mu.get(j, col).mul_frac(&BigFraction::frac_from_bigint(r.clone())));
-        mu.set(i, col, new_val);
+    // mu[i][col] -= r * mu[j][col] for col < j
+    let (target, source) = mu.row_pair_mut(i, j);
+    for (t, s) in target[..j].iter_mut().zip(&source[..j]) {
+        t.sub_mul_assign_int(s, &r);
     }
 }
 
@@ -200,7 +263,7 @@ fn swapg(
     }
 
     let tmu = mu.get(k, k - 1).clone();
-    let tb = norms.get(k).add_frac(&tmu.mul_frac(&tmu).mul_frac(norms.get(k - 1)));
+    let tb = norms.get(k) + tmu.mul_frac(&tmu) * norms.get(k - 1);
 
     if tb.is_zero() {
         // Case 1: tB == 0
@@ -214,9 +277,8 @@ fn swapg(
     } else if norms.get(k).is_zero() && !tmu.is_zero() {
         // Case 2: B[k] == 0 and tmu != 0
         norms.set(k - 1, tb);
-        let row = base_gso.get_row(k - 1).multiply_scalar(&tmu);
-        base_gso.set_row(k - 1, &row);
-        mu.set(k, k - 1, tmu.reciprocal());
+        base_gso.row_multiply(k - 1, &tmu);
+        mu.set(k, k - 1, tmu.reciprocal().expect("tmu is nonzero"));
         for i in (k + 1)..=kmax {
             let val = mu.get(i, k - 1).div_frac(&tmu);
             mu.set(i, k - 1, val);
@@ -226,19 +288,19 @@ fn swapg(
         let t = norms.get(k - 1).div_frac(&tb);
         mu.set(k, k - 1, tmu.mul_frac(&t));
 
-        // b = gso[k-1].copy()  (save before overwrite)
-        let b = base_gso.get_row(k - 1);
-        let gso_k = base_gso.get_row(k);
-
+        // With b = gso[k-1] (before overwrite), column by column:
         // gso[k-1] = gso[k] + b * tmu
-        let new_gso_km1 = gso_k.add(&b.multiply_scalar(&tmu));
         // gso[k] = b * (B[k] / tB) - gso[k] * mu(k,k-1)
-        let bk_over_tb = norms.get(k).div_frac(&tb);
+        //        = b - gso[k-1] * mu(k,k-1)   (as B[k] + tmu^2 B[k-1] = tB)
+        // so both are updated in place, the new gso[k-1] landing in row k
+        // until the rows are swapped back.
         let new_mu_kk1 = mu.get(k, k - 1).clone(); // = tmu * t (already set above)
-        let new_gso_k = b.multiply_scalar(&bk_over_tb).subtract(&gso_k.multiply_scalar(&new_mu_kk1));
-
-        base_gso.set_row(k - 1, &new_gso_km1);
-        base_gso.set_row(k, &new_gso_k);
+        let (b_row, gso_k_row) = base_gso.row_pair_mut(k - 1, k);
+        for (b, gso_k) in b_row.iter_mut().zip(gso_k_row.iter_mut()) {
+            gso_k.add_mul_assign_frac(b, &tmu);
+            b.sub_mul_assign_frac(gso_k, &new_mu_kk1);
+        }
+        base_gso.swap_rows(k, k - 1);
 
         // B[k] = B[k] * t
         let new_bk = norms.get(k).mul_frac(&t);
@@ -246,12 +308,77 @@ fn swapg(
         // B[k-1] = tB
         norms.set(k - 1, tb);
 
+        // new mu(i,k) = mu(i,k-1) - tmu * mu(i,k)
+        // new mu(i,k-1) = mu(i,k) + mu(k,k-1) * new mu(i,k)
+        // each computed in the other's slot, then swapped.
         for i in (k + 1)..=kmax {
-            let t_val = mu.get(i, k).clone();
-            let new_ik = mu.get(i, k - 1).sub_frac(&tmu.mul_frac(&t_val));
-            let new_ikm1 = t_val.add_frac(&mu.get(k, k - 1).mul_frac(&new_ik));
-            mu.set(i, k, new_ik);
-            mu.set(i, k - 1, new_ikm1);
+            let row = mu.row_mut(i);
+            let (left, right) = row.split_at_mut(k);
+            let (ikm1, ik) = (&mut left[k - 1], &mut right[0]);
+            ikm1.sub_mul_assign_frac(&tmu, ik);
+            ik.add_mul_assign_frac(&new_mu_kk1, ikm1);
+            std::mem::swap(ikm1, ik);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(rows: &[&[i64]]) -> BigMatrix {
+        let rows: Vec<BigVector> = rows
+            .iter()
+            .map(|row| BigVector::from_data(row.iter().map(|&x| BigFraction::frac_from_i64(x)).collect()))
+            .collect();
+        BigMatrix::from_rows(&rows)
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn test_full_rank_getters() {
+        let result = reduce_default(&matrix(&[&[1, 0, 0], &[4, 2, 15], &[0, 0, 3]]));
+        assert_eq!(result.determinant().map(|det| det.frac_abs()), Some(BigFraction::frac_from_i64(6)));
+        assert_eq!(result.gram_determinant(), BigFraction::frac_from_i64(36));
+        assert_close(result.log2_covolume().unwrap(), 6f64.log2());
+        // The first vector is a unit vector.
+        assert_close(result.hermite_factor().unwrap(), 6f64.powf(-1.0 / 3.0));
+        assert!(result.orthogonality_defect().unwrap() >= 1.0);
+    }
+
+    #[test]
+    fn test_orthogonal_basis_getters() {
+        let result = reduce_default(&matrix(&[&[0, 3], &[2, 0]]));
+        assert_eq!(result.determinant().map(|det| det.frac_abs()), Some(BigFraction::frac_from_i64(6)));
+        assert_close(result.log2_covolume().unwrap(), 6f64.log2());
+        // The shorter vector comes first: 2 / sqrt(6).
+        assert_close(result.hermite_factor().unwrap(), 2.0 / 6f64.sqrt());
+        assert_close(result.root_hermite_factor().unwrap(), (2.0 / 6f64.sqrt()).sqrt());
+        assert_close(result.orthogonality_defect().unwrap(), 1.0);
+    }
+
+    #[test]
+    fn test_lower_rank_getters() {
+        // The middle row is twice the first, so one vector drops out.
+        let result = reduce_default(&matrix(&[&[1, 1, 0], &[2, 2, 0], &[0, 1, 1]]));
+        assert_eq!(result.num_dependant_vectors, 1);
+        assert_eq!(result.determinant(), None);
+        // det [[2, 1], [1, 2]]
+        assert_eq!(result.gram_determinant(), BigFraction::frac_from_i64(3));
+        assert_close(result.log2_covolume().unwrap(), 3f64.log2() / 2.0);
+    }
+
+    #[test]
+    fn test_max_stage_has_no_covolume() {
+        let lattice = matrix(&[&[1, 0, 0], &[4, 2, 15], &[0, 0, 3]]);
+        let params = LLLParams { max_stage: 2, ..LLLParams::recommended() };
+        let result = reduce(&lattice, &params);
+        assert_eq!(result.log2_covolume(), None);
+        assert_eq!(result.hermite_factor(), None);
+        assert_eq!(result.orthogonality_defect(), None);
+        assert_eq!(result.gram_determinant(), BigFraction::frac_from_i64(36));
+    }
+}
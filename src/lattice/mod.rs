@@ -1,2 +1,4 @@
 pub mod lll;
 pub mod enumerate;
+pub mod babai;
+mod sphere;
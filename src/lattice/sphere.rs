@@ -0,0 +1,285 @@
+use super::enumerate::{to_lattice_point, BranchProgress, StatCounters};
+use crate::cancel::CancelToken;
+use crate::math::big_fraction::{BigFraction, FracOps};
+use crate::math::big_matrix::BigMatrix;
+use crate::math::big_vector::BigVector;
+use crate::math::int_type::{Int, IntOps};
+use crate::math::lu_decomposition::LuDecomposition;
+
+/// Relative slack on the squared radius, so that floating-point error can
+/// only let extra points through (they fail the exact box check), never lose
+/// a point on the boundary.
+const RADIUS_SLACK: f64 = 1e-6;
+
+/// Schnorr–Euchner / Fincke–Pohst enumeration of the lattice points
+/// `basis * c + origin` (integer `c`) with `lower <= point <= upper`.
+///
+/// The box is scaled to the cube `[-1, 1]^n`, whose circumscribed ball has
+/// squared radius `n`, and the ball is enumerated level by level on the
+/// Gram–Schmidt (QR) data of the scaled basis: each level's integer interval
+/// comes from the remaining radius, without any linear program. Every leaf is
+/// then checked against the box in exact arithmetic, so the results are the
+/// same points as the LP search, in a different order.
+///
+/// The ball holds more points than the box, and the ratio grows quickly with
+/// the dimension, so this pays off on well-reduced bases of moderate size.
+///
+/// Returns `None` if the basis is not square and nonsingular.
+pub(crate) fn enumerate_box(
+    basis: &BigMatrix,
+    lower: &BigVector,
+    upper: &BigVector,
+    origin: &BigVector,
+    cancel: &CancelToken,
+    stats: &StatCounters,
+    on_branch: &mut dyn FnMut(BranchProgress, &[BigVector]),
+) -> Option<Vec<BigVector>> {
+    let size = basis.row_count();
+    let problem = ScaledBox::new(basis, lower, upper, origin)?;
+    let mut search = Search {
+        problem: &problem,
+        radius_sq: size as f64 * (1.0 + RADIUS_SLACK),
+        offsets: vec![0; size],
+        cancel,
+        stats,
+    };
+
+    // The last level plays the role of the LP search's depth 0.
+    let top = size - 1;
+    stats.visit(0);
+    let values = match search.interval(top, 0.0) {
+        Some((center, min, max)) => center_outward(center, min, max),
+        None => {
+            stats.prune();
+            Vec::new()
+        }
+    };
+
+    let mut progress = BranchProgress {
+        branches_done: 0,
+        total_branches: values.len() as i64,
+        solutions_found: 0,
+    };
+    on_branch(progress, &[]);
+
+    let mut results = Vec::new();
+    for value in values {
+        if cancel.is_cancelled() {
+            break;
+        }
+        let before = results.len();
+        search.offsets[top] = value;
+        let dist = search.level_distance(top, value);
+        search.descend(top, dist, &mut results);
+        progress.branches_done += 1;
+        progress.solutions_found += results.len() - before;
+        on_branch(progress, &results[before..]);
+    }
+    Some(results)
+}
+
+/// The box search problem scaled to the cube `[-1, 1]^n` around its center,
+/// in floats, plus what is needed to map an answer back exactly.
+pub(super) struct ScaledBox<'a> {
+    /// The basis with each row divided by the box's half-width.
+    scaled: Vec<Vec<f64>>,
+    /// `R` of the QR decomposition of `scaled`.
+    r: Vec<Vec<f64>>,
+    /// Coefficients of the box center, rounded; offsets are relative to them.
+    base: Vec<Int>,
+    /// Fractional part of the box center's coefficients.
+    frac: Vec<f64>,
+    basis: &'a BigMatrix,
+    lower: &'a BigVector,
+    upper: &'a BigVector,
+    origin: &'a BigVector,
+}
+
+impl<'a> ScaledBox<'a> {
+    /// `None` if the basis is not square and nonsingular.
+    pub(super) fn new(
+        basis: &'a BigMatrix,
+        lower: &'a BigVector,
+        upper: &'a BigVector,
+        origin: &'a BigVector,
+    ) -> Option<Self> {
+        let size = basis.row_count();
+        let half = BigFraction::frac_half();
+
+        // Coefficients of the box center, split into an exact integer part and
+        // a small fractional part the float search is relative to.
+        if !basis.is_square() {
+            return None;
+        }
+        let lu = LuDecomposition::new(basis).ok()?;
+        let mut target = BigVector::new(size);
+        let mut half_widths = Vec::with_capacity(size);
+        for i in 0..size {
+            let mid = lower.get(i).add_frac(upper.get(i)).mul_frac(&half);
+            target.set(i, mid.sub_frac(origin.get(i)));
+            let width = upper.get(i).sub_frac(lower.get(i)).mul_frac(&half);
+            half_widths.push(if width < BigFraction::frac_one() { BigFraction::frac_one() } else { width });
+        }
+        let center = lu.solve(&target);
+        let base: Vec<Int> = (0..size).map(|i| FracOps::round(center.get(i))).collect();
+        let frac: Vec<f64> = (0..size)
+            .map(|i| center.get(i).sub_frac(&BigFraction::frac_from_int(base[i].clone())).frac_to_f64_approx())
+            .collect();
+
+        let scaled: Vec<Vec<f64>> = (0..size)
+            .map(|i| (0..size).map(|j| basis.get(i, j).div_frac(&half_widths[i]).frac_to_f64_approx()).collect())
+            .collect();
+        let r = upper_triangular(&scaled);
+
+        Some(ScaledBox { scaled, r, base, frac, basis, lower, upper, origin })
+    }
+
+    pub(super) fn size(&self) -> usize {
+        self.r.len()
+    }
+
+    /// Real value of level `k` closest to the box center, given the offsets
+    /// of the levels above it.
+    pub(super) fn center(&self, k: usize, offsets: &[i64]) -> f64 {
+        let shift: f64 = ((k + 1)..self.size())
+            .map(|j| self.r[k][j] * (offsets[j] as f64 - self.frac[j]))
+            .sum();
+        self.frac[k] - shift / self.r[k][k]
+    }
+
+    /// Float test of the box, with the same slack as the radius; the ball
+    /// mostly holds points outside the box, which are cheaper to drop here
+    /// than through the exact check.
+    pub(super) fn roughly_contains(&self, offsets: &[i64]) -> bool {
+        let limit = 1.0 + RADIUS_SLACK;
+        self.scaled.iter().all(|row| {
+            let y: f64 = row
+                .iter()
+                .zip(offsets.iter().zip(&self.frac))
+                .map(|(m, (&d, f))| m * (d as f64 - f))
+                .sum();
+            y.abs() <= limit
+        })
+    }
+
+    /// The lattice point at `offsets`, exactly.
+    pub(super) fn point(&self, offsets: &[i64]) -> BigVector {
+        let mut fixed = BigVector::new(self.size());
+        for (i, base) in self.base.iter().enumerate() {
+            let value = base.int_add(&Int::int_from_i64(offsets[i]));
+            fixed.set(i, BigFraction::frac_from_int(value));
+        }
+        to_lattice_point(self.basis, self.origin, &fixed)
+    }
+
+    /// Exact test of the box.
+    pub(super) fn contains(&self, point: &BigVector) -> bool {
+        (0..point.dimension()).all(|i| point.get(i) >= self.lower.get(i) && point.get(i) <= self.upper.get(i))
+    }
+}
+
+struct Search<'a> {
+    problem: &'a ScaledBox<'a>,
+    radius_sq: f64,
+    /// Integer offsets from the center's coefficients fixed so far, from the
+    /// last level down.
+    offsets: Vec<i64>,
+    cancel: &'a CancelToken,
+    stats: &'a StatCounters,
+}
+
+impl Search<'_> {
+    /// Center of level `k` given the levels above it, and the integer range
+    /// that keeps the partial distance `dist` within the radius.
+    fn interval(&self, k: usize, dist: f64) -> Option<(f64, i64, i64)> {
+        let remaining = self.radius_sq - dist;
+        if remaining < 0.0 {
+            return None;
+        }
+        let center = self.problem.center(k, &self.offsets);
+        let reach = remaining.sqrt() / self.problem.r[k][k].abs();
+        let min = (center - reach).ceil() as i64;
+        let max = (center + reach).floor() as i64;
+        (min <= max).then_some((center, min, max))
+    }
+
+    /// Squared distance contributed by level `k` set to `value`.
+    fn level_distance(&self, k: usize, value: i64) -> f64 {
+        let delta = self.problem.r[k][k] * (value as f64 - self.problem.center(k, &self.offsets));
+        delta * delta
+    }
+
+    /// Enumerate the levels below `k`, whose value is already in `offsets`.
+    fn descend(&mut self, k: usize, dist: f64, results: &mut Vec<BigVector>) {
+        if self.cancel.is_cancelled() {
+            return;
+        }
+        let depth = self.problem.size() - k;
+        self.stats.visit(depth);
+
+        if k == 0 {
+            if self.problem.roughly_contains(&self.offsets) {
+                let point = self.problem.point(&self.offsets);
+                if self.problem.contains(&point) {
+                    results.push(point);
+                }
+            }
+            return;
+        }
+
+        let level = k - 1;
+        let Some((center, min, max)) = self.interval(level, dist) else {
+            self.stats.prune();
+            return;
+        };
+        for value in center_outward(center, min, max) {
+            self.offsets[level] = value;
+            let next = dist + self.level_distance(level, value);
+            if next <= self.radius_sq {
+                self.descend(level, next, results);
+            }
+        }
+    }
+}
+
+/// Integers in `[min, max]`, nearest to `center` first (Schnorr–Euchner order).
+fn center_outward(center: f64, min: i64, max: i64) -> Vec<i64> {
+    let mut below = (center.round() as i64).clamp(min, max);
+    let mut above = below + 1;
+    let mut values = Vec::with_capacity((max - min + 1) as usize);
+    while below >= min || above <= max {
+        let take_below = above > max || (below >= min && center - below as f64 <= above as f64 - center);
+        if take_below {
+            values.push(below);
+            below -= 1;
+        } else {
+            values.push(above);
+            above += 1;
+        }
+    }
+    values
+}
+
+/// `R` of the QR decomposition of `m` (columns are the basis vectors), by
+/// modified Gram–Schmidt.
+fn upper_triangular(m: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let n = m.len();
+    let mut columns: Vec<Vec<f64>> = (0..n).map(|j| (0..n).map(|i| m[i][j]).collect()).collect();
+    let mut r = vec![vec![0.0; n]; n];
+    for j in 0..n {
+        for i in 0..j {
+            let dot: f64 = columns[i].iter().zip(&columns[j]).map(|(a, b)| a * b).sum();
+            r[i][j] = dot;
+            let (done, rest) = columns.split_at_mut(j);
+            for (x, q) in rest[0].iter_mut().zip(&done[i]) {
+                *x -= dot * q;
+            }
+        }
+        let norm = columns[j].iter().map(|x| x * x).sum::<f64>().sqrt();
+        r[j][j] = norm;
+        for x in columns[j].iter_mut() {
+            *x /= norm;
+        }
+    }
+    r
+}
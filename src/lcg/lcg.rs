@@ -1,6 +1,10 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// Linear Congruential Generator parameters.
 /// Models: seed_{n+1} = (seed_n * multiplier + addend) mod modulus
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LCG {
     pub multiplier: i64,
     pub addend: i64,
@@ -15,7 +19,13 @@ impl LCG {
         modulus: 1 << 48,
     };
 
-    pub fn new(multiplier: i64, addend: i64, modulus: i64) -> Self {
+    /// Two, four and minus five Java steps, for hot paths that skip calls.
+    pub const JAVA_SKIP_2: LCG = LCG::JAVA.combine(2);
+    pub const JAVA_SKIP_4: LCG = LCG::JAVA.combine(4);
+    pub const JAVA_SKIP_NEG_1: LCG = LCG::JAVA.combine(-1);
+    pub const JAVA_SKIP_NEG_5: LCG = LCG::JAVA.combine(-5);
+
+    pub const fn new(multiplier: i64, addend: i64, modulus: i64) -> Self {
         LCG {
             multiplier,
             addend,
@@ -23,11 +33,11 @@ impl LCG {
         }
     }
 
-    pub fn next_seed(&self, seed: i64) -> i64 {
+    pub const fn next_seed(&self, seed: i64) -> i64 {
         self.modop(seed.wrapping_mul(self.multiplier).wrapping_add(self.addend))
     }
 
-    pub fn modop(&self, n: i64) -> i64 {
+    pub const fn modop(&self, n: i64) -> i64 {
         // Modulus is always a power of 2 for Java LCG
         if self.modulus > 0 && (self.modulus & (self.modulus.wrapping_neg())) == self.modulus {
             n & (self.modulus - 1)
@@ -39,7 +49,8 @@ impl LCG {
 
     /// Combine this LCG with itself `steps` times.
     /// Equivalent to advancing the LCG by `steps` calls in one operation.
-    pub fn combine(&self, steps: i64) -> LCG {
+    /// Usable in constants, see [`LCG::JAVA_SKIP_2`].
+    pub const fn combine(&self, steps: i64) -> LCG {
         let mut multiplier: i64 = 1;
         let mut addend: i64 = 0;
 
@@ -64,11 +75,104 @@ impl LCG {
     }
 
     /// Invert: combine(-1)
-    pub fn invert(&self) -> LCG {
+    pub const fn invert(&self) -> LCG {
         self.combine(-1)
     }
 }
 
+/// [`LCG`] with 128-bit arithmetic, for generators whose modulus does not fit
+/// in an `i64`, e.g. the 2^64-modulus ones used outside Java.
+///
+/// The modulus must be a power of two, so that products can wrap mod 2^128
+/// before they are reduced.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LCG128 {
+    pub multiplier: u128,
+    pub addend: u128,
+    pub modulus: u128,
+}
+
+impl LCG128 {
+    /// [`LCG::JAVA`] in 128 bits.
+    pub const JAVA: LCG128 = LCG128 {
+        multiplier: 0x5DEECE66D,
+        addend: 0xB,
+        modulus: 1 << 48,
+    };
+
+    /// Knuth's MMIX generator, modulus 2^64.
+    pub const MMIX: LCG128 = LCG128 {
+        multiplier: 6364136223846793005,
+        addend: 1442695040888963407,
+        modulus: 1 << 64,
+    };
+
+    pub fn new(multiplier: u128, addend: u128, modulus: u128) -> Self {
+        assert!(modulus.is_power_of_two(), "LCG128 modulus {} is not a power of two", modulus);
+        LCG128 {
+            multiplier,
+            addend,
+            modulus,
+        }
+    }
+
+    pub fn next_seed(&self, seed: u128) -> u128 {
+        self.modop(seed.wrapping_mul(self.multiplier).wrapping_add(self.addend))
+    }
+
+    pub fn modop(&self, n: u128) -> u128 {
+        n & (self.modulus - 1)
+    }
+
+    /// Combine this LCG with itself `steps` times, see [`LCG::combine`].
+    pub fn combine(&self, steps: i64) -> LCG128 {
+        let mut multiplier: u128 = 1;
+        let mut addend: u128 = 0;
+
+        let mut im = self.multiplier;
+        let mut ia = self.addend;
+
+        // A negative step count wraps around the period, which divides 2^128.
+        let mut k = steps as i128 as u128;
+        while k != 0 {
+            if (k & 1) != 0 {
+                multiplier = multiplier.wrapping_mul(im);
+                addend = im.wrapping_mul(addend).wrapping_add(ia);
+            }
+            ia = (im.wrapping_add(1)).wrapping_mul(ia);
+            im = im.wrapping_mul(im);
+            k >>= 1;
+        }
+
+        LCG128::new(self.modop(multiplier), self.modop(addend), self.modulus)
+    }
+
+    /// Invert: combine(-1)
+    pub fn invert(&self) -> LCG128 {
+        self.combine(-1)
+    }
+}
+
+/// Panics unless `lcg` has a power-of-two modulus.
+impl From<&LCG> for LCG128 {
+    fn from(lcg: &LCG) -> Self {
+        LCG128::new(lcg.multiplier as u64 as u128, lcg.addend as u64 as u128, lcg.modulus as u64 as u128)
+    }
+}
+
+impl From<LCG> for LCG128 {
+    fn from(lcg: LCG) -> Self {
+        LCG128::from(&lcg)
+    }
+}
+
+impl From<&LCG128> for LCG128 {
+    fn from(lcg: &LCG128) -> Self {
+        lcg.clone()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,4 +204,25 @@ mod tests {
         let back = inv.next_seed(next);
         assert_eq!(back, seed);
     }
+
+    #[test]
+    fn test_lcg128_combine_invert() {
+        let lcg = LCG128::MMIX;
+        let seed: u128 = 0xDEAD_BEEF_CAFE;
+        let ahead = lcg.combine(5).next_seed(seed);
+        let mut stepped = seed;
+        for _ in 0..5 {
+            stepped = lcg.next_seed(stepped);
+        }
+        assert_eq!(ahead, stepped);
+        assert_eq!(lcg.invert().next_seed(lcg.next_seed(seed)), seed);
+        assert_eq!(LCG128::from(&LCG::JAVA).combine(-1).multiplier, LCG::JAVA.invert().multiplier as u128);
+        assert_eq!(LCG128::from(&LCG::JAVA), LCG128::JAVA);
+    }
+
+    #[test]
+    #[should_panic(expected = "not a power of two")]
+    fn test_lcg128_rejects_other_moduli() {
+        LCG128::new(3, 1, (1 << 64) + 13);
+    }
 }
@@ -1,43 +1,45 @@
-use super::lcg::LCG;
+use super::lcg::LCG128;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// A random number generator state that mirrors Java's java.util.Random.
 /// This is the LattiCG Rand equivalent.
+///
+/// Runs any power-of-two LCG up to a 2^64 modulus; seeds are passed in and
+/// out as the bit pattern of an `i64`.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Rand {
-    lcg: LCG,
-    seed: i64,
+    lcg: LCG128,
+    seed: u128,
 }
 
 impl Rand {
-    pub fn of_internal_seed(lcg: &LCG, seed: i64) -> Self {
+    pub fn of_internal_seed(lcg: impl Into<LCG128>, seed: i64) -> Self {
+        let lcg = lcg.into();
+        assert!(lcg.modulus <= 1 << 64, "Rand seeds are at most 64 bits, this LCG has {}", lcg.modulus.trailing_zeros());
         Rand {
-            lcg: lcg.clone(),
-            seed: lcg.modop(seed),
+            seed: lcg.modop(seed as u64 as u128),
+            lcg,
         }
     }
 
-    pub fn of_seed_scrambled(lcg: &LCG, seed: i64) -> Self {
-        Rand {
-            lcg: lcg.clone(),
-            seed: lcg.modop(seed ^ lcg.multiplier),
-        }
+    pub fn of_seed_scrambled(lcg: impl Into<LCG128>, seed: i64) -> Self {
+        let lcg = lcg.into();
+        let scrambled = seed as u64 as u128 ^ lcg.multiplier;
+        Self::of_internal_seed(lcg, scrambled as u64 as i64)
     }
 
     pub fn get_seed(&self) -> i64 {
-        self.seed
+        self.seed as u64 as i64
     }
 
     pub fn set_seed(&mut self, seed: i64) {
-        self.seed = self.lcg.modop(seed);
+        self.seed = self.lcg.modop(seed as u64 as u128);
     }
 
     pub fn set_seed_scrambled(&mut self, seed: i64) {
-        self.seed = self.lcg.modop(seed ^ self.lcg.multiplier);
-    }
-
-    pub fn next(&mut self, bits: i32) -> i32 {
-        self.seed = self.lcg.next_seed(self.seed);
-        (self.seed >> (48 - bits)) as i32
+        self.seed = self.lcg.modop(seed as u64 as u128 ^ self.lcg.multiplier);
     }
 
     pub fn advance(&mut self, calls: i64) {
@@ -45,41 +47,86 @@ impl Rand {
         self.seed = skip.next_seed(self.seed);
     }
 
-    pub fn advance_lcg(&mut self, skip: &LCG) {
+    pub fn advance_lcg(&mut self, skip: &LCG128) {
         self.seed = skip.next_seed(self.seed);
     }
 
-    pub fn next_int(&mut self, bound: i32) -> i32 {
+    pub fn lcg(&self) -> &LCG128 {
+        &self.lcg
+    }
+}
+
+impl JavaRng for Rand {
+    fn next(&mut self, bits: i32) -> i32 {
+        self.seed = self.lcg.next_seed(self.seed);
+        (self.seed >> (self.lcg.modulus.trailing_zeros() as i32 - bits)) as i32
+    }
+}
+
+/// The `java.util.Random` methods built on `next(bits)`, shared by [`Rand`]
+/// and [`JRand`](crate::mc::jrand::JRand) so the two cannot drift apart.
+pub trait JavaRng {
+    /// Advance the seed and return its top `bits` bits.
+    fn next(&mut self, bits: i32) -> i32;
+
+    fn next_int(&mut self, bound: i32) -> i32 {
         if bound <= 0 {
             panic!("bound must be positive");
         }
 
         if (bound & (-bound)) == bound {
             // power of 2
-            return ((bound as i64 * self.next(31) as i64) >> 31) as i32;
+            return ((bound as i64).wrapping_mul(self.next(31) as i64) >> 31) as i32;
         }
 
-        let mut bits;
-        let mut value;
         loop {
-            bits = self.next(31);
-            value = bits % bound;
-            if bits - value + (bound - 1) >= 0 {
-                break;
+            let bits = self.next(31);
+            let value = bits % bound;
+            // Java's int overflow is what signals the rejection.
+            if bits.wrapping_sub(value).wrapping_add(bound - 1) >= 0 {
+                return value;
             }
         }
-        value
     }
 
-    pub fn next_long(&mut self) -> i64 {
+    fn next_long(&mut self) -> i64 {
         ((self.next(32) as i64) << 32).wrapping_add(self.next(32) as i64)
     }
 
-    pub fn next_float(&mut self) -> f32 {
+    fn next_boolean(&mut self) -> bool {
+        self.next(1) != 0
+    }
+
+    fn next_float(&mut self) -> f32 {
         self.next(24) as f32 / (1 << 24) as f32
     }
 
-    pub fn next_double(&mut self) -> f64 {
+    fn next_double(&mut self) -> f64 {
         (((self.next(26) as i64) << 27) + self.next(27) as i64) as f64 * (1.0f64 / (1i64 << 53) as f64)
     }
+
+    /// Fill `bytes` like `nextBytes`: one `nextInt()` per four bytes, low byte first.
+    fn next_bytes(&mut self, bytes: &mut [u8]) {
+        for chunk in bytes.chunks_mut(4) {
+            let int = self.next(32).to_le_bytes();
+            chunk.copy_from_slice(&int[..chunk.len()]);
+        }
+    }
+}
+
+/// Same as the [`JRand`](crate::mc::jrand::JRand) impl: `nextInt()`,
+/// `nextLong()` and `nextBytes`.
+#[cfg(feature = "rand-compat")]
+impl rand_core::RngCore for Rand {
+    fn next_u32(&mut self) -> u32 {
+        self.next(32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next_long() as u64
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.next_bytes(dest);
+    }
 }
@@ -30,7 +30,12 @@ pub fn is_verbose() -> bool {
     VERBOSE.load(Ordering::Relaxed)
 }
 
+#[cfg(feature = "log")]
+#[doc(hidden)]
+pub use log as __log;
+
 /// Like `eprintln!`, but only prints when the global `VERBOSE` flag is set.
+#[cfg(not(feature = "log"))]
 #[macro_export]
 macro_rules! verbose_eprintln {
     ($($arg:tt)*) => {
@@ -40,6 +45,16 @@ macro_rules! verbose_eprintln {
     };
 }
 
+/// Emits a `debug` record through the `log` facade, targeted at the calling
+/// module. The installed logger decides what is shown; `VERBOSE` is ignored.
+#[cfg(feature = "log")]
+#[macro_export]
+macro_rules! verbose_eprintln {
+    ($($arg:tt)*) => {
+        $crate::__log::debug!($($arg)*)
+    };
+}
+
 /// Exact rational arithmetic, matrix operations, LU decomposition, and linear programming.
 pub mod math;
 /// Linear congruential generator (LCG) types and Java `Random` state model.
@@ -52,6 +67,8 @@ pub mod reverser;
 pub mod mc;
 /// Dungeon floor parsing and the top-level cracking entry points.
 pub mod dungeon;
+/// Cooperative cancellation for long-running cracks.
+pub mod cancel;
 
 #[cfg(feature = "wasm")]
 pub mod wasm;
@@ -1,7 +1,11 @@
-use dungeon_cracker::dungeon::reverse_dungeon::{
-    self, BiomeType, FloorSize,
-};
+use dungeon_cracker::dungeon::crack_request::CrackRequest;
+use dungeon_cracker::dungeon::Floor;
+use dungeon_cracker::dungeon::dungeon_data_parser::DEFAULT_MAX_POSSIBILITIES;
+use dungeon_cracker::dungeon::reverser_instruction::MAX_TILE_INDEX;
+use dungeon_cracker::dungeon::reverse_dungeon::{generating_chunk, BiomeType, FloorSize, SpawnerMob};
+use dungeon_cracker::dungeon::seed_filter::WorldSeedFilter;
 use dungeon_cracker::mc::chunk_rand::MCVersion;
+use dungeon_cracker::mc::seed_text::Wordlist;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::env;
@@ -23,8 +27,8 @@ struct DungeonInput {
     version: String,
     /// Biome type: "desert", "notdesert", or "unknown".
     biome: String,
-    /// Floor size key: "9x9", "7x9", "9x7", "7x7".
-    #[serde(default = "default_floor_size")]
+    /// Floor size key: "9x9", "7x9", "9x7", "7x7". Detected from the rows if empty.
+    #[serde(default)]
     floor_size: String,
     /// Optional label for the dungeon.
     #[serde(default)]
@@ -37,10 +41,9 @@ struct DungeonInput {
     /// `floor_rows` and `floor_size` are ignored.
     #[serde(default)]
     floor_sequence: String,
-}
-
-fn default_floor_size() -> String {
-    "9x9".to_string()
+    /// Optional spawner mob: "skeleton", "zombie" or "spider".
+    #[serde(default)]
+    mob: String,
 }
 
 /// Top-level JSON input: an array of dungeons.
@@ -102,8 +105,9 @@ const HASH_FLOOR_SIZES: &[FloorSizeDef] = &[
 // ─── Argument parsing ───────────────────────────────────────────────────
 
 enum InputMode {
-    /// Legacy positional args: <x> <y> <z> <version> <biome> [floor_size] [floor_rows...]
-    Legacy(Vec<String>),
+    /// Legacy positional args: <x> <y> <z> <version> <biome> [floor_size] [floor_rows...],
+    /// with the rows optionally read from `--floor-file <path>` instead
+    Legacy(Vec<String>, Option<String>),
     /// --json <path>
     JsonFile(String),
     /// --hash <fragment>
@@ -114,6 +118,8 @@ struct CliArgs {
     input: InputMode,
     output_file: Option<String>,
     verbose: bool,
+    max_possibilities: usize,
+    wordlist: Option<Wordlist>,
 }
 
 fn parse_cli_args() -> CliArgs {
@@ -121,6 +127,9 @@ fn parse_cli_args() -> CliArgs {
     let mut output_file: Option<String> = None;
     let mut input_mode: Option<InputMode> = None;
     let mut verbose = false;
+    let mut max_possibilities = DEFAULT_MAX_POSSIBILITIES;
+    let mut wordlist: Option<Wordlist> = None;
+    let mut floor_file: Option<String> = None;
 
     let mut i = 1;
     while i < args.len() {
@@ -151,6 +160,36 @@ fn parse_cli_args() -> CliArgs {
                 let fragment = args[i].trim_start_matches('#').to_string();
                 input_mode = Some(InputMode::UrlHash(fragment));
             }
+            "--max-possibilities" => {
+                i += 1;
+                max_possibilities = match args.get(i).and_then(|a| a.parse().ok()) {
+                    Some(n) => n,
+                    None => {
+                        eprintln!("Error: --max-possibilities requires a number argument");
+                        std::process::exit(1);
+                    }
+                };
+            }
+            "--wordlist" => {
+                i += 1;
+                if i >= args.len() {
+                    eprintln!("Error: --wordlist requires a filename argument");
+                    std::process::exit(1);
+                }
+                let text = fs::read_to_string(&args[i]).unwrap_or_else(|e| {
+                    eprintln!("Error: failed to read '{}': {}", args[i], e);
+                    std::process::exit(1);
+                });
+                wordlist = Some(Wordlist::from_lines(&text));
+            }
+            "--floor-file" => {
+                i += 1;
+                if i >= args.len() {
+                    eprintln!("Error: --floor-file requires a filename argument (or - for stdin)");
+                    std::process::exit(1);
+                }
+                floor_file = Some(args[i].clone());
+            }
             "--verbose" | "--log" => {
                 verbose = true;
             }
@@ -162,7 +201,7 @@ fn parse_cli_args() -> CliArgs {
                 // Must be start of legacy positional args; collect the rest
                 if input_mode.is_none() {
                     let rest: Vec<String> = args[i..].to_vec();
-                    input_mode = Some(InputMode::Legacy(rest));
+                    input_mode = Some(InputMode::Legacy(rest, floor_file.take()));
                     // Skip the rest since we consumed them
                     i = args.len();
                     continue;
@@ -179,11 +218,17 @@ fn parse_cli_args() -> CliArgs {
         print_help();
         std::process::exit(1);
     }
+    if floor_file.is_some() {
+        eprintln!("Error: --floor-file only applies to positional arguments, and must come before them");
+        std::process::exit(1);
+    }
 
     CliArgs {
         input: input_mode.unwrap(),
         output_file,
         verbose,
+        max_possibilities,
+        wordlist,
     }
 }
 
@@ -201,14 +246,24 @@ fn print_help() {
     eprintln!();
     eprintln!("OPTIONS:");
     eprintln!("  --output <file> | -o <file>    Write results to a JSON file");
+    eprintln!("  --max-possibilities <n>        Floor interpretations to try before giving up (default: {DEFAULT_MAX_POSSIBILITIES})");
+    eprintln!("  --wordlist <file>              Only keep text seeds typed as a line of <file>");
+    eprintln!("  --floor-file <file>            Read the floor rows for positional args from <file> (- for stdin)");
     eprintln!("  --verbose       | --log        Show detailed internal logs");
     eprintln!("  --help          | -h           Show this help message");
     eprintln!();
     eprintln!("LEGACY POSITIONAL ARGS:");
-    eprintln!("  version: 1.8, 1.9, ..., 1.17");
+    eprintln!("  version: 1.6 (and earlier), 1.7, 1.8, ..., 1.17; patch releases (1.16.5)");
+    eprintln!("           and snapshots (20w45a) count as their release; 1.18 and later are not");
+    eprintln!("           supported, as their dungeon generation is not modelled");
     eprintln!("  biome:   desert, notdesert, unknown");
-    eprintln!("  size:    9x9, 7x9, 9x7, 7x7  (default: 9x9)");
-    eprintln!("  rows:    digit strings (0=mossy, 1=cobble, 2=air, 3=unknown, 4=unknown_solid)");
+    eprintln!("  size:    9x9, 7x9, 9x7, 7x7  (default: detected from the rows)");
+    eprintln!("  rows:    digit strings (0=mossy, 1=cobble, 2=air, 3=unknown, 4=unknown_solid,");
+    eprintln!("           5=chest, 6=water, 7=obstructed)");
+    eprintln!();
+    eprintln!("FLOOR FILE FORMAT:");
+    eprintln!("  One row per line, as in the positional args; spaces inside rows, blank lines");
+    eprintln!("  and everything after a '#' are ignored.");
     eprintln!();
     eprintln!("JSON FILE FORMAT:");
     eprintln!(r#"  {{
@@ -222,6 +277,7 @@ fn print_help() {
       }}
     ]
   }}"#);
+    eprintln!("  Optional \"mob\" field: skeleton, zombie, or spider (1.9+).");
     eprintln!();
     eprintln!("URL HASH:");
     eprintln!("  Copy the #fragment from the web UI address bar and pass it to --hash.");
@@ -231,6 +287,10 @@ fn print_help() {
     eprintln!("  # Legacy single dungeon:");
     eprintln!("  {prog} 320 29 -418 1.13 notdesert 000001000 000000000 000000010 001101000 000000110 000000011 100010000");
     eprintln!();
+    eprintln!("  # Floor rows from a file, or piped through stdin:");
+    eprintln!("  {prog} --floor-file floor.txt 320 29 -418 1.13 notdesert");
+    eprintln!("  cat floor.txt | {prog} --floor-file - 320 29 -418 1.13 notdesert");
+    eprintln!();
     eprintln!("  # From JSON file, output to JSON:");
     eprintln!("  {prog} --json dungeons.json --output results.json");
     eprintln!();
@@ -242,14 +302,15 @@ fn print_help() {
 
 fn resolve_input(mode: InputMode) -> Vec<DungeonInput> {
     match mode {
-        InputMode::Legacy(args) => vec![parse_legacy_args(&args)],
+        InputMode::Legacy(args, floor_file) => vec![parse_legacy_args(&args, floor_file.as_deref())],
         InputMode::JsonFile(path) => parse_json_file(&path),
         InputMode::UrlHash(fragment) => parse_url_hash(&fragment),
     }
 }
 
-/// Parse legacy positional CLI arguments into a single DungeonInput.
-fn parse_legacy_args(args: &[String]) -> DungeonInput {
+/// Parse legacy positional CLI arguments into a single DungeonInput, taking
+/// the floor rows from `floor_file` when given.
+fn parse_legacy_args(args: &[String], floor_file: Option<&str>) -> DungeonInput {
     if args.len() < 5 {
         eprintln!("Error: legacy mode requires at least: <x> <y> <z> <version> <biome>");
         std::process::exit(1);
@@ -274,10 +335,17 @@ fn parse_legacy_args(args: &[String]) -> DungeonInput {
     let (floor_size, floor_start) = if args.len() > 5 && is_floor_size(&args[5]) {
         (args[5].clone(), 6)
     } else {
-        ("9x9".to_string(), 5)
+        (String::new(), 5)
     };
 
-    let floor_rows: Vec<String> = args[floor_start..].to_vec();
+    let mut floor_rows: Vec<String> = args[floor_start..].to_vec();
+    if let Some(path) = floor_file {
+        if !floor_rows.is_empty() {
+            eprintln!("Error: floor rows given both as arguments and through --floor-file");
+            std::process::exit(1);
+        }
+        floor_rows = read_floor_file(path);
+    }
 
     DungeonInput {
         spawner_x,
@@ -289,9 +357,30 @@ fn parse_legacy_args(args: &[String]) -> DungeonInput {
         label: String::new(),
         floor_rows,
         floor_sequence: String::new(),
+        mob: String::new(),
     }
 }
 
+/// Read floor rows from a file, or from stdin for `-`: one row per line, with
+/// `#` comments and blank lines dropped. The rows themselves are checked
+/// later by [`Floor::parse`].
+fn read_floor_file(path: &str) -> Vec<String> {
+    let content = if path == "-" {
+        std::io::read_to_string(std::io::stdin())
+    } else {
+        fs::read_to_string(path)
+    };
+    let content = content.unwrap_or_else(|e| {
+        eprintln!("Error: could not read floor from '{}': {}", path, e);
+        std::process::exit(1);
+    });
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim().to_string())
+        .filter(|row| !row.is_empty())
+        .collect()
+}
+
 fn is_floor_size(s: &str) -> bool {
     matches!(s.to_lowercase().as_str(), "9x9" | "7x9" | "9x7" | "7x7")
 }
@@ -420,6 +509,7 @@ fn parse_hash_text_segment(s: &str, fallback_num: usize) -> Option<DungeonInput>
         label,
         floor_rows: Vec::new(),
         floor_sequence,
+        mob: String::new(),
     })
 }
 
@@ -448,7 +538,7 @@ fn decode_hash_floor_pattern(raw: &str, fs: &FloorSizeDef) -> Option<String> {
             return None;
         }
         tiles = raw.bytes().map(|b| {
-            if b >= b'0' && b <= b'4' { b - b'0' } else { 4 }
+            if b >= b'0' && b <= b'0' + MAX_TILE_INDEX { b - b'0' } else { 4 }
         }).collect();
     }
 
@@ -619,6 +709,7 @@ fn parse_hash_binary(hex: &str) -> Vec<DungeonInput> {
             label: if label.is_empty() { format!("Dungeon {}", di + 1) } else { label },
             floor_rows: Vec::new(),
             floor_sequence,
+            mob: String::new(),
         });
     }
 
@@ -668,9 +759,11 @@ fn urlencoding_decode(s: &str) -> String {
 // ─── Dungeon input resolution ───────────────────────────────────────────
 
 /// Resolve a DungeonInput into its floor sequence, validating fields.
-fn resolve_dungeon(d: &DungeonInput) -> Result<(i32, i32, i32, MCVersion, BiomeType, String), String> {
-    let version = parse_version(&d.version)?;
+#[allow(clippy::type_complexity)]
+fn resolve_dungeon(d: &DungeonInput) -> Result<(i32, i32, i32, MCVersion, BiomeType, String, Option<SpawnerMob>), String> {
+    let version: MCVersion = d.version.parse()?;
     let biome = parse_biome(&d.biome)?;
+    let mob = parse_mob(&d.mob)?;
 
     let sequence = if !d.floor_sequence.is_empty() {
         d.floor_sequence.clone()
@@ -680,64 +773,21 @@ fn resolve_dungeon(d: &DungeonInput) -> Result<(i32, i32, i32, MCVersion, BiomeT
         return Err("No floor data provided (need either floor_rows or floor_sequence)".to_string());
     };
 
-    Ok((d.spawner_x, d.spawner_y, d.spawner_z, version, biome, sequence))
+    Ok((d.spawner_x, d.spawner_y, d.spawner_z, version, biome, sequence, mob))
 }
 
-/// Build a column-major sequence string from row strings + floor size key.
+/// Build a column-major sequence string from row strings, checked against the
+/// floor size key when one is given.
 fn build_sequence_from_rows(rows: &[String], floor_size_key: &str) -> Result<String, String> {
-    let floor_size = parse_floor_size(floor_size_key)?;
-
-    let expected_rows = floor_size.z_max() - floor_size.z_min();
-    let expected_cols = floor_size.x_max() - floor_size.x_min();
-
-    if rows.len() != expected_rows {
-        return Err(format!(
-            "Expected {} floor rows for size {}, got {}",
-            expected_rows, floor_size_key, rows.len()
-        ));
+    let floor = Floor::parse(&rows.join("\n"))?;
+    if !floor_size_key.is_empty() && floor.size() != parse_floor_size(floor_size_key)? {
+        return Err(format!("Floor rows do not match floor size {}", floor_size_key));
     }
-
-    // Build 9x9 grid, defaulting to unknown_solid (4)
-    let mut floor = [[4u8; 9]; 9];
-
-    for (row_idx, z) in (floor_size.z_min()..floor_size.z_max()).enumerate() {
-        let row_str = &rows[row_idx];
-        if row_str.len() != expected_cols {
-            return Err(format!(
-                "Row {} has {} characters, expected {}",
-                row_idx, row_str.len(), expected_cols
-            ));
-        }
-        for (col_idx, x) in (floor_size.x_min()..floor_size.x_max()).enumerate() {
-            let ch = row_str.as_bytes()[col_idx];
-            if ch < b'0' || ch > b'4' {
-                return Err(format!("Invalid tile '{}' at row {} col {}", ch as char, row_idx, col_idx));
-            }
-            floor[z][x] = ch - b'0';
-        }
-    }
-
-    Ok(reverse_dungeon::get_sequence(&floor, floor_size))
+    Ok(floor.to_sequence())
 }
 
 // ─── Parsing helpers ────────────────────────────────────────────────────
 
-fn parse_version(s: &str) -> Result<MCVersion, String> {
-    match s {
-        "1.8" => Ok(MCVersion::V1_8),
-        "1.9" => Ok(MCVersion::V1_9),
-        "1.10" => Ok(MCVersion::V1_10),
-        "1.11" => Ok(MCVersion::V1_11),
-        "1.12" => Ok(MCVersion::V1_12),
-        "1.13" => Ok(MCVersion::V1_13),
-        "1.14" => Ok(MCVersion::V1_14),
-        "1.15" => Ok(MCVersion::V1_15),
-        "1.16" => Ok(MCVersion::V1_16),
-        "1.17" => Ok(MCVersion::V1_17),
-        _ => Err(format!("Unknown version: {}", s)),
-    }
-}
-
 fn parse_biome(s: &str) -> Result<BiomeType, String> {
     match s.to_lowercase().as_str() {
         "desert" => Ok(BiomeType::Desert),
@@ -747,6 +797,16 @@ fn parse_biome(s: &str) -> Result<BiomeType, String> {
     }
 }
 
+fn parse_mob(s: &str) -> Result<Option<SpawnerMob>, String> {
+    match s.to_lowercase().as_str() {
+        "" => Ok(None),
+        "skeleton" => Ok(Some(SpawnerMob::Skeleton)),
+        "zombie" => Ok(Some(SpawnerMob::Zombie)),
+        "spider" => Ok(Some(SpawnerMob::Spider)),
+        _ => Err(format!("Unknown mob: {} (use skeleton, zombie, or spider)", s)),
+    }
+}
+
 fn parse_floor_size(s: &str) -> Result<FloorSize, String> {
     match s.to_lowercase().as_str() {
         "9x9" => Ok(FloorSize::_9x9),
@@ -757,22 +817,6 @@ fn parse_floor_size(s: &str) -> Result<FloorSize, String> {
     }
 }
 
-/// Format version for output JSON (user-friendly "1.13" style, not "V1_13").
-fn format_version(v: MCVersion) -> String {
-    match v {
-        MCVersion::V1_8 => "1.8",
-        MCVersion::V1_9 => "1.9",
-        MCVersion::V1_10 => "1.10",
-        MCVersion::V1_11 => "1.11",
-        MCVersion::V1_12 => "1.12",
-        MCVersion::V1_13 => "1.13",
-        MCVersion::V1_14 => "1.14",
-        MCVersion::V1_15 => "1.15",
-        MCVersion::V1_16 => "1.16",
-        MCVersion::V1_17 => "1.17",
-    }.to_string()
-}
-
 fn format_biome(b: BiomeType) -> String {
     match b {
         BiomeType::Desert => "desert",
@@ -783,9 +827,43 @@ fn format_biome(b: BiomeType) -> String {
 
 // ─── Main ───────────────────────────────────────────────────────────────
 
+/// Print `seeds` to stdout, with the words they were typed as to stderr.
+fn print_seeds(seeds: &[i64], wordlist: Option<&Wordlist>) {
+    for seed in seeds {
+        println!("{}", seed);
+        for word in wordlist.map_or(&[][..], |list| list.words_for(*seed)) {
+            eprintln!("  typed as \"{}\"", word);
+        }
+    }
+}
+
+/// Prints the library's `log` records to stderr for `--verbose`.
+#[cfg(feature = "log")]
+struct StderrLogger;
+
+#[cfg(feature = "log")]
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.target().starts_with("dungeon_cracker")
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("{}", record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
 fn main() {
     let cli = parse_cli_args();
     dungeon_cracker::set_verbose(cli.verbose);
+    #[cfg(feature = "log")]
+    if cli.verbose {
+        log::set_logger(&StderrLogger).expect("no other logger is installed");
+        log::set_max_level(log::LevelFilter::Debug);
+    }
     let dungeons = resolve_input(cli.input);
 
     if dungeons.is_empty() {
@@ -828,13 +906,26 @@ fn main() {
                     elapsed_ms: 0,
                 });
             }
-            Ok((sx, sy, sz, version, biome, sequence)) => {
+            Ok((sx, sy, sz, version, biome, sequence, mob)) => {
                 eprintln!("  Spawner: ({}, {}, {})", sx, sy, sz);
-                eprintln!("  Version: {}, Biome: {}", format_version(version), format_biome(biome));
+                let (chunk_x, chunk_z) = generating_chunk(sx, sz, version);
+                eprintln!("  Generating chunk: ({}, {})", chunk_x, chunk_z);
+                eprintln!("  Version: {}, Biome: {}", version, format_biome(biome));
                 eprintln!("  Sequence: {} ({} tiles)", sequence, sequence.len());
 
                 let start = Instant::now();
-                match reverse_dungeon::crack_dungeon(sx, sy, sz, version, biome, &sequence) {
+                let mut request = CrackRequest::new(sx, sy, sz)
+                    .version(version)
+                    .biome(biome)
+                    .floor(sequence.as_str())
+                    .max_possibilities(cli.max_possibilities);
+                if let Some(mob) = mob {
+                    request = request.mob(mob);
+                }
+                if let Some(wordlist) = &cli.wordlist {
+                    request = request.world_seed_filter(WorldSeedFilter::new().wordlist(wordlist));
+                }
+                match request.crack() {
                     Ok(result) => {
                         let elapsed = start.elapsed();
                         let elapsed_ms = elapsed.as_millis() as u64;
@@ -853,7 +944,7 @@ fn main() {
                             spawner_x: sx,
                             spawner_y: sy,
                             spawner_z: sz,
-                            version: format_version(version),
+                            version: version.to_string(),
                             biome: format_biome(biome),
                             dungeon_seeds: result.dungeon_seeds,
                             structure_seeds: result.structure_seeds,
@@ -873,7 +964,7 @@ fn main() {
                             spawner_x: sx,
                             spawner_y: sy,
                             spawner_z: sz,
-                            version: format_version(version),
+                            version: version.to_string(),
                             biome: format_biome(biome),
                             dungeon_seeds: vec![],
                             structure_seeds: vec![],
@@ -910,14 +1001,10 @@ fn main() {
     eprintln!("═══════════════════════════════════════");
     if dungeons.len() > 1 {
         eprintln!("Common world seeds: {}", common_world_seeds.len());
-        for seed in &common_world_seeds {
-            println!("{}", seed);
-        }
+        print_seeds(&common_world_seeds, cli.wordlist.as_ref());
     } else if !outputs.is_empty() && outputs[0].error.is_none() {
         eprintln!("World seeds found: {}", outputs[0].world_seeds.len());
-        for seed in &outputs[0].world_seeds {
-            println!("{}", seed);
-        }
+        print_seeds(&outputs[0].world_seeds, cli.wordlist.as_ref());
     }
     eprintln!("Total time: {:?}", total_elapsed);
 
@@ -5,14 +5,22 @@
 // done in highly-optimized C/assembly.
 //
 // When `gmp` is not enabled (WASM builds), this uses a hand-rolled
-// `BigFractionInner` backed by `num_bigint::BigInt`.
+// `BigFractionInner` backed by `num_bigint::BigInt`, which keeps values that
+// fit in machine integers out of `BigInt` entirely.
+//
+// Both support the arithmetic operators (`+ - * /`, their `*Assign` forms and
+// unary `-`). `rug::Rational` has them natively, and there an operation on
+// two references yields an incomplete value rather than a `Rational`, so code
+// shared by the backends keeps at least one operand of each operator owned.
 
-use super::int_type::Int;
-#[cfg(not(feature = "gmp"))]
-use super::int_type::IntOps;
+use super::error::{MathError, ParseFractionError};
+use super::int_type::{Int, IntOps};
 #[cfg(not(feature = "gmp"))]
 use std::cmp::Ordering;
 #[cfg(not(feature = "gmp"))]
+use num_traits::ToPrimitive;
+#[cfg(not(feature = "gmp"))]
+use std::borrow::Cow;
 use std::fmt;
 
 // ─── Type alias ──────────────────────────────────────────────────────────────
@@ -50,6 +58,18 @@ pub trait FracOps: Sized {
     fn mul_int(&self, other: &Int) -> Self;
     fn div_int(&self, other: &Int) -> Self;
 
+    /// In-place forms of the above, which reuse `self`'s storage instead of
+    /// allocating a new value.
+    fn add_assign_frac(&mut self, other: &Self);
+    fn sub_assign_frac(&mut self, other: &Self);
+    fn mul_assign_frac(&mut self, other: &Self);
+    /// `self += a * b`
+    fn add_mul_assign_frac(&mut self, a: &Self, b: &Self);
+    /// `self -= a * b`
+    fn sub_mul_assign_frac(&mut self, a: &Self, b: &Self);
+    /// `self -= a * b` for an integer `b`
+    fn sub_mul_assign_int(&mut self, a: &Self, b: &Int);
+
     /// Backwards-compatible aliases.
     fn add_bigint(&self, other: &Int) -> Self { self.add_int(other) }
     fn sub_bigint(&self, other: &Int) -> Self { self.sub_int(other) }
@@ -57,19 +77,217 @@ pub trait FracOps: Sized {
     fn div_bigint(&self, other: &Int) -> Self { self.div_int(other) }
 
     fn negate(&self) -> Self;
-    fn reciprocal(&self) -> Self;
+    /// `1 / self`, or [`MathError::DivisionByZero`] for zero.
+    fn reciprocal(&self) -> Result<Self, MathError>;
     fn frac_abs(&self) -> Self;
     fn signum(&self) -> i32;
     fn is_zero(&self) -> bool;
 
     fn numerator_int(&self) -> Int;
+    fn denominator_int(&self) -> Int;
+
+    /// Base-2 logarithm of the absolute value, also for values beyond the
+    /// range of `f64`. Negative infinity for zero.
+    fn frac_log2_approx(&self) -> f64 {
+        self.numerator_int().int_log2_approx() - self.denominator_int().int_log2_approx()
+    }
+
+    /// The nearest `f64`, give or take a few ulps, for heuristics that do not
+    /// need exact comparisons. Infinite or zero beyond the range of `f64`.
+    fn frac_to_f64_approx(&self) -> f64;
 
     /// Floor: largest integer k such that k <= self.
     fn floor(&self) -> Int;
     /// Ceil: smallest integer k such that k >= self.
     fn ceil(&self) -> Int;
-    /// Round: closest integer, rounding 0.5 up (towards +inf).
+    /// Round: closest integer, rounding 0.5 up (towards +inf), like Java's
+    /// `Math.round`; LLL's size reduction depends on this to match the Java
+    /// cracker's bases.
     fn round(&self) -> Int;
+
+    /// Closest integer, rounding 0.5 to the even neighbour.
+    fn round_half_even(&self) -> Int {
+        let floor = self.floor();
+        let two = Int::int_from_i64(2);
+        // Twice the fractional part, compared to 1.
+        let excess = self.sub_int(&floor).mul_int(&two).sub_frac(&Self::frac_one());
+        match excess.signum() {
+            -1 => floor,
+            1 => floor.int_add(&Int::int_one()),
+            _ if floor.int_rem(&two).int_is_zero() => floor,
+            _ => floor.int_add(&Int::int_one()),
+        }
+    }
+
+    /// Closest integer, rounding 0.5 away from zero.
+    fn round_half_away_from_zero(&self) -> Int {
+        if self.signum() < 0 {
+            self.negate().round().int_neg()
+        } else {
+            self.round()
+        }
+    }
+
+    /// The integer part, like Java's cast to `long`.
+    fn round_toward_zero(&self) -> Int {
+        if self.signum() < 0 {
+            self.ceil()
+        } else {
+            self.floor()
+        }
+    }
+
+    /// `floor(self / other)`, like Java's `Math.floorDiv`.
+    fn floor_div(&self, other: &Self) -> Int {
+        self.div_frac(other).floor()
+    }
+
+    /// `self - other * floor(self / other)`, which takes the sign of `other`,
+    /// like Java's `Math.floorMod`.
+    fn floor_mod(&self, other: &Self) -> Self {
+        self.sub_frac(&other.mul_int(&self.floor_div(other)))
+    }
+
+    /// The closest fraction to `self` with a denominator of at most
+    /// `max_denominator`, from its continued fraction: the last convergent
+    /// within the bound or the best semiconvergent after it, whichever is
+    /// closer. Recovers an exact value from a good enough approximation.
+    fn approximate(&self, max_denominator: &Int) -> Self {
+        assert!(max_denominator.int_is_positive(), "Maximum denominator must be positive");
+        let ntor = self.numerator_int();
+        let dtor = self.denominator_int();
+        if dtor <= *max_denominator {
+            return Self::frac_from_int(ntor).div_int(&dtor);
+        }
+
+        // Convergents p0/q0 and p1/q1 of |self| = n/d, one step behind.
+        let (mut p0, mut q0, mut p1, mut q1) = (Int::int_zero(), Int::int_one(), Int::int_one(), Int::int_zero());
+        let (mut n, mut d) = (ntor.int_abs(), dtor);
+        loop {
+            let a = n.int_div(&d);
+            let q2 = q0.int_add(&a.int_mul(&q1));
+            if q2 > *max_denominator {
+                break;
+            }
+            let p2 = p0.int_add(&a.int_mul(&p1));
+            (p0, q0, p1, q1) = (p1, q1, p2, q2);
+            let r = n.int_sub(&a.int_mul(&d));
+            (n, d) = (d, r);
+        }
+
+        let k = max_denominator.int_sub(&q0).int_div(&q1);
+        let semi = Self::frac_from_int(p0.int_add(&k.int_mul(&p1))).div_int(&q0.int_add(&k.int_mul(&q1)));
+        let convergent = Self::frac_from_int(p1).div_int(&q1);
+        let abs = self.frac_abs();
+        let semi_error = semi.sub_frac(&abs).frac_abs();
+        let convergent_error = convergent.sub_frac(&abs).frac_abs();
+        let best = if semi_error.sub_frac(&convergent_error).signum() < 0 { semi } else { convergent };
+        if ntor.int_is_negative() {
+            best.negate()
+        } else {
+            best
+        }
+    }
+
+    /// Parse an integer (`-12`), a fraction (`3/-4`) or a decimal (`-1.25`,
+    /// `.5`), with surrounding whitespace ignored.
+    fn frac_parse(s: &str) -> Result<Self, ParseFractionError> {
+        let error = || ParseFractionError { input: s.to_string() };
+        let text = s.trim();
+        let parse_int = |t: &str| t.parse::<Int>().map_err(|_| error());
+
+        if let Some((ntor, dtor)) = text.split_once('/') {
+            let dtor = parse_int(dtor.trim())?;
+            if dtor.int_is_zero() {
+                return Err(error());
+            }
+            return Ok(Self::frac_from_int(parse_int(ntor.trim())?).div_int(&dtor));
+        }
+
+        let Some((whole, digits)) = text.split_once('.') else {
+            return Ok(Self::frac_from_int(parse_int(text)?));
+        };
+        let (sign, whole) = match whole.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", whole.strip_prefix('+').unwrap_or(whole)),
+        };
+        let is_digits = |t: &str| t.bytes().all(|b| b.is_ascii_digit());
+        if whole.len() + digits.len() == 0 || !is_digits(whole) || !is_digits(digits) {
+            return Err(error());
+        }
+        let ntor = parse_int(&format!("{}{}{}", sign, whole, digits))?;
+        Ok(Self::frac_from_int(ntor).div_int(&pow10(digits.len())))
+    }
+}
+
+/// Parse a [`BigFraction`] as [`FracOps::frac_parse`] does. Prefer this to
+/// `str::parse`, which under `gmp` is rug's own parser and rejects decimals.
+pub fn parse_fraction(s: &str) -> Result<BigFraction, ParseFractionError> {
+    BigFraction::frac_parse(s)
+}
+
+/// `10^n`
+fn pow10(n: usize) -> Int {
+    let ten = Int::int_from_i64(10);
+    (0..n).fold(Int::int_one(), |acc, _| acc.int_mul(&ten))
+}
+
+/// How a [`FractionDisplay`] writes its value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FractionStyle {
+    /// `-7/2`, or the integer alone, as `Display` writes it.
+    Fraction,
+    /// A whole part and a proper fraction: `-3 1/2`.
+    Mixed,
+    /// A decimal with this many digits after the point, rounded to
+    /// nearest, halves away from zero: `-3.50`.
+    Decimal(usize),
+}
+
+/// A [`BigFraction`] written in a chosen [`FractionStyle`].
+pub struct FractionDisplay<'a> {
+    value: &'a BigFraction,
+    style: FractionStyle,
+}
+
+impl<'a> FractionDisplay<'a> {
+    pub fn new(value: &'a BigFraction, style: FractionStyle) -> Self {
+        FractionDisplay { value, style }
+    }
+}
+
+impl fmt::Display for FractionDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ntor = self.value.numerator_int();
+        let dtor = self.value.denominator_int();
+        let sign = if ntor.int_is_negative() { "-" } else { "" };
+        let abs = ntor.int_abs();
+        match self.style {
+            FractionStyle::Fraction => write!(f, "{}", self.value),
+            FractionStyle::Mixed => {
+                let whole = abs.int_div(&dtor);
+                let rem = abs.int_rem(&dtor);
+                match (whole.int_is_zero(), rem.int_is_zero()) {
+                    (_, true) => write!(f, "{}", ntor),
+                    (true, false) => write!(f, "{}{}/{}", sign, rem, dtor),
+                    (false, false) => write!(f, "{}{} {}/{}", sign, whole, rem, dtor),
+                }
+            }
+            FractionStyle::Decimal(places) => {
+                // round(|value| * 10^places), as (2 |n| 10^p + d) / 2d
+                let two = Int::int_from_i64(2);
+                let scaled = abs.int_mul(&pow10(places)).int_mul(&two).int_add(&dtor).int_div(&dtor.int_mul(&two));
+                let digits = format!("{:0>width$}", scaled.to_string(), width = places + 1);
+                let sign = if scaled.int_is_zero() { "" } else { sign };
+                let (whole, frac) = digits.split_at(digits.len() - places);
+                if places == 0 {
+                    write!(f, "{}{}", sign, whole)
+                } else {
+                    write!(f, "{}{}.{}", sign, whole, frac)
+                }
+            }
+        }
+    }
 }
 
 // ─── rug / GMP backend (rug::Rational) ──────────────────────────────────────
@@ -104,9 +322,21 @@ mod rug_frac_impl {
         fn mul_int(&self, other: &Int) -> Self { Rational::from(self * other) }
         fn div_int(&self, other: &Int) -> Self { Rational::from(self / other) }
 
+        fn add_assign_frac(&mut self, other: &Self) { *self += other; }
+        fn sub_assign_frac(&mut self, other: &Self) { *self -= other; }
+        fn mul_assign_frac(&mut self, other: &Self) { *self *= other; }
+        fn add_mul_assign_frac(&mut self, a: &Self, b: &Self) { *self += Rational::from(a * b); }
+        fn sub_mul_assign_frac(&mut self, a: &Self, b: &Self) { *self -= Rational::from(a * b); }
+        fn sub_mul_assign_int(&mut self, a: &Self, b: &Int) { *self -= Rational::from(a * b); }
+
         fn negate(&self) -> Self { Rational::from(-self) }
 
-        fn reciprocal(&self) -> Self { Rational::from(self.recip_ref()) }
+        fn reciprocal(&self) -> Result<Self, MathError> {
+            if self.is_zero() {
+                return Err(MathError::DivisionByZero);
+            }
+            Ok(Rational::from(self.recip_ref()))
+        }
 
         fn frac_abs(&self) -> Self { Rational::from(self.abs_ref()) }
 
@@ -121,10 +351,16 @@ mod rug_frac_impl {
 
         fn is_zero(&self) -> bool { *self == 0 }
 
+        fn frac_to_f64_approx(&self) -> f64 { self.to_f64() }
+
         fn numerator_int(&self) -> Int {
             Integer::from(self.numer())
         }
 
+        fn denominator_int(&self) -> Int {
+            Integer::from(self.denom())
+        }
+
         fn floor(&self) -> Int {
             // rug's trunc_ref truncates toward zero.
             // floor = trunc if non-negative or exact, else trunc - 1.
@@ -157,169 +393,422 @@ mod rug_frac_impl {
 // ─── num-bigint backend (BigFractionInner) ───────────────────────────────────
 
 /// Pure-Rust exact rational backed by `num_bigint::BigInt`.
-/// Invariants after `simplify()`:
+///
+/// Values whose parts fit in an `i64` are kept in machine integers: their
+/// sums, products and cross-products fit in an `i128`, so arithmetic on them
+/// never overflows and only moves to `BigInt` when a result is too large.
+/// Invariants:
 /// - denominator > 0
 /// - gcd(|numerator|, denominator) == 1
 /// - if numerator == 0 then denominator == 1
+/// - the value is `Small` exactly when both parts fit in an `i64`, so each
+///   value has a single representation
 #[cfg(not(feature = "gmp"))]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct BigFractionInner {
-    ntor: Int,
-    dtor: Int,
+    repr: Repr,
+}
+
+#[cfg(not(feature = "gmp"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Repr {
+    Small { ntor: i64, dtor: i64 },
+    Big { ntor: Int, dtor: Int },
 }
 
+#[cfg(not(feature = "gmp"))]
+type SmallOp = fn(i64, i64, i64, i64) -> Option<BigFractionInner>;
+
+#[cfg(not(feature = "gmp"))]
+type BigOp = fn(&Int, &Int, &Int, &Int) -> (Int, Int);
+
 #[cfg(not(feature = "gmp"))]
 impl BigFractionInner {
-    fn new_raw(ntor: Int, dtor: Int) -> Self {
-        let mut f = BigFractionInner { ntor, dtor };
-        f.simplify();
-        f
+    const fn small(ntor: i64, dtor: i64) -> Self {
+        BigFractionInner { repr: Repr::Small { ntor, dtor } }
     }
 
-    fn simplify(&mut self) {
-        if self.ntor.int_is_zero() {
-            self.dtor = Int::int_one();
+    /// A value already in lowest terms with a positive denominator.
+    fn reduced_wide(ntor: i128, dtor: i128) -> Self {
+        match (i64::try_from(ntor), i64::try_from(dtor)) {
+            (Ok(n), Ok(d)) => Self::small(n, d),
+            _ => BigFractionInner { repr: Repr::Big { ntor: Int::from(ntor), dtor: Int::from(dtor) } },
+        }
+    }
+
+    /// A value already in lowest terms with a positive denominator, moved
+    /// to machine integers if it fits.
+    fn reduced(ntor: Int, dtor: Int) -> Self {
+        match (ntor.to_i64(), dtor.to_i64()) {
+            (Some(n), Some(d)) => Self::small(n, d),
+            _ => BigFractionInner { repr: Repr::Big { ntor, dtor } },
+        }
+    }
+
+    fn new_raw(mut ntor: Int, mut dtor: Int) -> Self {
+        if ntor.int_is_zero() {
+            return Self::small(0, 1);
+        }
+        if dtor.int_is_negative() {
+            ntor = -ntor;
+            dtor = -dtor;
+        }
+        if !dtor.int_is_one() {
+            let g = ntor.int_gcd(&dtor);
+            if !g.int_is_one() {
+                ntor /= &g;
+                dtor /= &g;
+            }
+        }
+        Self::reduced(ntor, dtor)
+    }
+
+    fn parts(&self) -> (Cow<'_, Int>, Cow<'_, Int>) {
+        match &self.repr {
+            Repr::Small { ntor, dtor } => (Cow::Owned(Int::from(*ntor)), Cow::Owned(Int::from(*dtor))),
+            Repr::Big { ntor, dtor } => (Cow::Borrowed(ntor), Cow::Borrowed(dtor)),
+        }
+    }
+
+    fn is_big(&self) -> bool {
+        matches!(self.repr, Repr::Big { .. })
+    }
+
+    /// Apply a binary operation, in machine integers when both operands are
+    /// small.
+    fn binary(&self, other: &Self, small: SmallOp, big: BigOp) -> Self {
+        if let (Repr::Small { ntor: a, dtor: b }, Repr::Small { ntor: c, dtor: d }) = (&self.repr, &other.repr) {
+            if let Some(f) = small(*a, *b, *c, *d) {
+                return f;
+            }
+        }
+        let (a, b) = self.parts();
+        let (c, d) = other.parts();
+        let (ntor, dtor) = big(&a, &b, &c, &d);
+        Self::new_raw(ntor, dtor)
+    }
+
+    /// Switch to the big representation ahead of an in-place update, which
+    /// normalizes back afterwards.
+    fn make_big(&mut self) -> (&mut Int, &mut Int) {
+        if let Repr::Small { ntor, dtor } = self.repr {
+            self.repr = Repr::Big { ntor: Int::from(ntor), dtor: Int::from(dtor) };
+        }
+        match &mut self.repr {
+            Repr::Big { ntor, dtor } => (ntor, dtor),
+            Repr::Small { .. } => unreachable!(),
+        }
+    }
+
+    /// `self += ntor / dtor` in big integers, with a single normalization at
+    /// the end.
+    fn add_assign_raw(&mut self, ntor: Int, dtor: Int) {
+        if self.is_zero() {
+            *self = Self::new_raw(ntor, dtor);
             return;
         }
-        if self.dtor.int_is_negative() {
-            self.ntor = self.ntor.int_neg();
-            self.dtor = self.dtor.int_neg();
+        let (n, d) = self.make_big();
+        if *d == dtor {
+            *n += ntor;
+        } else {
+            *n *= &dtor;
+            *n += ntor * &*d;
+            *d *= dtor;
+        }
+        let (n, d) = (std::mem::take(n), std::mem::take(d));
+        *self = Self::new_raw(n, d);
+    }
+
+    /// Numerator and denominator of `a * b` (not reduced) in big integers,
+    /// multiplying by a small side's parts directly.
+    fn big_product(a: &Self, b: &Self) -> (Int, Int) {
+        match (&a.repr, &b.repr) {
+            (Repr::Big { ntor: an, dtor: ad }, Repr::Big { ntor: bn, dtor: bd }) => (an * bn, ad * bd),
+            (Repr::Big { ntor, dtor }, Repr::Small { ntor: sn, dtor: sd })
+            | (Repr::Small { ntor: sn, dtor: sd }, Repr::Big { ntor, dtor }) => {
+                (ntor * *sn, if *sd == 1 { dtor.clone() } else { dtor * *sd })
+            }
+            (Repr::Small { ntor: an, dtor: ad }, Repr::Small { ntor: bn, dtor: bd }) => (
+                Int::from(*an as i128 * *bn as i128),
+                Int::from(*ad as i128 * *bd as i128),
+            ),
+        }
+    }
+}
+
+#[cfg(not(feature = "gmp"))]
+fn gcd_u64(mut a: u64, mut b: u64) -> u64 {
+    if a == 0 {
+        return b;
+    }
+    if b == 0 {
+        return a;
+    }
+    let shift = (a | b).trailing_zeros();
+    a >>= a.trailing_zeros();
+    loop {
+        b >>= b.trailing_zeros();
+        if a > b {
+            std::mem::swap(&mut a, &mut b);
+        }
+        b -= a;
+        if b == 0 {
+            return a << shift;
         }
-        let g = self.ntor.int_gcd(&self.dtor);
-        self.ntor = self.ntor.int_div(&g);
-        self.dtor = self.dtor.int_div(&g);
+    }
+}
+
+/// `a/b + c/d` for small fractions in lowest terms, reducing with gcds of
+/// the denominators only (Knuth, TAOCP 4.5.1).
+#[cfg(not(feature = "gmp"))]
+fn small_add(a: i64, b: i64, c: i64, d: i64) -> Option<BigFractionInner> {
+    let g = gcd_u64(b as u64, d as u64) as i64;
+    if g == 1 {
+        let ntor = a as i128 * d as i128 + c as i128 * b as i128;
+        return Some(BigFractionInner::reduced_wide(ntor, b as i128 * d as i128));
+    }
+    let t = a as i128 * (d / g) as i128 + c as i128 * (b / g) as i128;
+    if t == 0 {
+        return Some(BigFractionInner::small(0, 1));
+    }
+    let g2 = gcd_u64(t.rem_euclid(g as i128) as u64, g as u64) as i128;
+    Some(BigFractionInner::reduced_wide(t / g2, (b / g) as i128 * (d as i128 / g2)))
+}
+
+#[cfg(not(feature = "gmp"))]
+fn small_sub(a: i64, b: i64, c: i64, d: i64) -> Option<BigFractionInner> {
+    small_add(a, b, c.checked_neg()?, d)
+}
+
+/// `(a / b) * (c / d)` for small fractions in lowest terms. Cancelling
+/// across first leaves the result in lowest terms.
+#[cfg(not(feature = "gmp"))]
+fn small_mul(a: i64, b: i64, c: i64, d: i64) -> Option<BigFractionInner> {
+    if a == 0 || c == 0 {
+        return Some(BigFractionInner::small(0, 1));
+    }
+    let g1 = gcd_u64(a.unsigned_abs(), d as u64) as i64;
+    let g2 = gcd_u64(c.unsigned_abs(), b as u64) as i64;
+    let ntor = (a / g1) as i128 * (c / g2) as i128;
+    let dtor = (b / g2) as i128 * (d / g1) as i128;
+    Some(BigFractionInner::reduced_wide(ntor, dtor))
+}
+
+#[cfg(not(feature = "gmp"))]
+fn small_div(a: i64, b: i64, c: i64, d: i64) -> Option<BigFractionInner> {
+    match c.signum() {
+        1 => small_mul(a, b, d, c),
+        -1 => small_mul(a.checked_neg()?, b, d, c.checked_neg()?),
+        _ => None,
     }
 }
 
 #[cfg(not(feature = "gmp"))]
 impl FracOps for BigFractionInner {
     fn frac_zero() -> Self {
-        BigFractionInner { ntor: Int::int_zero(), dtor: Int::int_one() }
+        Self::small(0, 1)
     }
 
     fn frac_one() -> Self {
-        BigFractionInner { ntor: Int::int_one(), dtor: Int::int_one() }
+        Self::small(1, 1)
     }
 
     fn frac_minus_one() -> Self {
-        BigFractionInner { ntor: Int::int_one().int_neg(), dtor: Int::int_one() }
+        Self::small(-1, 1)
     }
 
     fn frac_half() -> Self {
-        BigFractionInner::new_raw(Int::int_one(), Int::int_from_i64(2))
+        Self::small(1, 2)
     }
 
     fn frac_new(numerator: i64, denominator: i64) -> Self {
-        let mut f = BigFractionInner {
-            ntor: Int::int_from_i64(numerator),
-            dtor: Int::int_from_i64(denominator),
-        };
-        assert!(!f.dtor.int_is_zero(), "Division by zero");
-        f.simplify();
-        f
+        assert!(denominator != 0, "Division by zero");
+        Self::new_raw(Int::int_from_i64(numerator), Int::int_from_i64(denominator))
     }
 
     fn frac_from_i64(n: i64) -> Self {
-        BigFractionInner { ntor: Int::int_from_i64(n), dtor: Int::int_one() }
+        Self::small(n, 1)
     }
 
     fn frac_from_int(n: Int) -> Self {
-        BigFractionInner { ntor: n, dtor: Int::int_one() }
+        Self::reduced(n, Int::int_one())
     }
 
     fn add_frac(&self, other: &Self) -> Self {
-        Self::new_raw(
-            self.ntor.int_mul(&other.dtor).int_add(&other.ntor.int_mul(&self.dtor)),
-            self.dtor.int_mul(&other.dtor),
-        )
+        self.binary(other, small_add, |a, b, c, d| (a * d + c * b, b * d))
     }
 
     fn sub_frac(&self, other: &Self) -> Self {
-        Self::new_raw(
-            self.ntor.int_mul(&other.dtor).int_sub(&other.ntor.int_mul(&self.dtor)),
-            self.dtor.int_mul(&other.dtor),
-        )
+        self.binary(other, small_sub, |a, b, c, d| (a * d - c * b, b * d))
     }
 
     fn mul_frac(&self, other: &Self) -> Self {
-        Self::new_raw(
-            self.ntor.int_mul(&other.ntor),
-            self.dtor.int_mul(&other.dtor),
-        )
+        self.binary(other, small_mul, |a, b, c, d| (a * c, b * d))
     }
 
     fn div_frac(&self, other: &Self) -> Self {
-        Self::new_raw(
-            self.ntor.int_mul(&other.dtor),
-            self.dtor.int_mul(&other.ntor),
-        )
+        self.binary(other, small_div, |a, b, c, d| (a * d, b * c))
     }
 
     fn add_int(&self, other: &Int) -> Self {
-        Self::new_raw(
-            self.ntor.int_add(&other.int_mul(&self.dtor)),
-            self.dtor.clone(),
-        )
+        self.add_frac(&Self::frac_from_int(other.clone()))
     }
 
     fn sub_int(&self, other: &Int) -> Self {
-        Self::new_raw(
-            self.ntor.int_sub(&other.int_mul(&self.dtor)),
-            self.dtor.clone(),
-        )
+        self.sub_frac(&Self::frac_from_int(other.clone()))
     }
 
     fn mul_int(&self, other: &Int) -> Self {
-        Self::new_raw(self.ntor.int_mul(other), self.dtor.clone())
+        self.mul_frac(&Self::frac_from_int(other.clone()))
     }
 
     fn div_int(&self, other: &Int) -> Self {
-        Self::new_raw(self.ntor.clone(), self.dtor.int_mul(other))
+        self.div_frac(&Self::frac_from_int(other.clone()))
+    }
+
+    fn add_assign_frac(&mut self, other: &Self) {
+        if self.is_big() || other.is_big() {
+            let (c, d) = other.parts();
+            self.add_assign_raw(c.into_owned(), d.into_owned());
+        } else {
+            *self = self.add_frac(other);
+        }
+    }
+
+    fn sub_assign_frac(&mut self, other: &Self) {
+        if self.is_big() || other.is_big() {
+            let (c, d) = other.parts();
+            self.add_assign_raw(-c.into_owned(), d.into_owned());
+        } else {
+            *self = self.sub_frac(other);
+        }
+    }
+
+    fn mul_assign_frac(&mut self, other: &Self) {
+        if self.is_big() || other.is_big() {
+            let (ntor, dtor) = Self::big_product(self, other);
+            *self = Self::new_raw(ntor, dtor);
+        } else {
+            *self = self.mul_frac(other);
+        }
+    }
+
+    fn add_mul_assign_frac(&mut self, a: &Self, b: &Self) {
+        if self.is_big() || a.is_big() || b.is_big() {
+            let (ntor, dtor) = Self::big_product(a, b);
+            self.add_assign_raw(ntor, dtor);
+        } else {
+            *self = self.add_frac(&a.mul_frac(b));
+        }
+    }
+
+    fn sub_mul_assign_frac(&mut self, a: &Self, b: &Self) {
+        if self.is_big() || a.is_big() || b.is_big() {
+            let (ntor, dtor) = Self::big_product(a, b);
+            self.add_assign_raw(-ntor, dtor);
+        } else {
+            *self = self.sub_frac(&a.mul_frac(b));
+        }
+    }
+
+    fn sub_mul_assign_int(&mut self, a: &Self, b: &Int) {
+        match (b.to_i64(), &a.repr) {
+            (Some(r), Repr::Small { .. }) if !self.is_big() => {
+                *self = self.sub_frac(&a.mul_frac(&Self::small(r, 1)));
+            }
+            (_, Repr::Big { ntor, dtor }) => self.add_assign_raw(-(ntor * b), dtor.clone()),
+            (_, Repr::Small { ntor, dtor }) => self.add_assign_raw(-(b * *ntor), Int::from(*dtor)),
+        }
     }
 
     fn negate(&self) -> Self {
-        BigFractionInner { ntor: self.ntor.int_neg(), dtor: self.dtor.clone() }
+        match &self.repr {
+            Repr::Small { ntor, dtor } => Self::reduced_wide(-(*ntor as i128), *dtor as i128),
+            Repr::Big { ntor, dtor } => Self::reduced(ntor.int_neg(), dtor.clone()),
+        }
     }
 
-    fn reciprocal(&self) -> Self {
-        Self::new_raw(self.dtor.clone(), self.ntor.clone())
+    fn reciprocal(&self) -> Result<Self, MathError> {
+        if self.is_zero() {
+            return Err(MathError::DivisionByZero);
+        }
+        Ok(Self::frac_one().div_frac(self))
     }
 
     fn frac_abs(&self) -> Self {
-        if self.ntor.int_is_negative() { self.negate() } else { self.clone() }
+        if self.signum() < 0 { self.negate() } else { self.clone() }
     }
 
     fn signum(&self) -> i32 {
-        if self.ntor.int_is_positive() { 1 }
-        else if self.ntor.int_is_negative() { -1 }
-        else { 0 }
+        match &self.repr {
+            Repr::Small { ntor, .. } => ntor.signum() as i32,
+            Repr::Big { ntor, .. } => {
+                if ntor.int_is_positive() { 1 }
+                else if ntor.int_is_negative() { -1 }
+                else { 0 }
+            }
+        }
     }
 
     fn is_zero(&self) -> bool {
-        self.ntor.int_is_zero()
+        matches!(self.repr, Repr::Small { ntor: 0, .. })
+    }
+
+    fn frac_to_f64_approx(&self) -> f64 {
+        match &self.repr {
+            Repr::Small { ntor, dtor } => *ntor as f64 / *dtor as f64,
+            Repr::Big { ntor, dtor } => {
+                // Shift off low bits first, each side by its own amount, so
+                // neither overflows to infinity, then scale the quotient back
+                // in two steps so the scale does not overflow on its own.
+                let shift = |x: &Int| (x.int_abs().int_log2_approx() - 1000.0).max(0.0) as u32;
+                let (ntor_shift, dtor_shift) = (shift(ntor), shift(dtor));
+                let quotient =
+                    ntor.int_shr(ntor_shift).int_to_f64_approx() / dtor.int_shr(dtor_shift).int_to_f64_approx();
+                let scale = (ntor_shift as i64 - dtor_shift as i64).clamp(-4096, 4096) as i32;
+                quotient * 2f64.powi(scale / 2) * 2f64.powi(scale - scale / 2)
+            }
+        }
     }
 
     fn numerator_int(&self) -> Int {
-        self.ntor.clone()
+        self.parts().0.into_owned()
+    }
+
+    fn denominator_int(&self) -> Int {
+        self.parts().1.into_owned()
     }
 
     fn floor(&self) -> Int {
-        if self.dtor.int_is_one() {
-            self.ntor.clone()
-        } else if self.ntor.int_is_negative() {
-            self.ntor.int_div(&self.dtor).int_sub(&Int::int_one())
-        } else {
-            self.ntor.int_div(&self.dtor)
+        match &self.repr {
+            Repr::Small { ntor, dtor } => Int::from(ntor.div_euclid(*dtor)),
+            Repr::Big { ntor, dtor } => {
+                if dtor.int_is_one() {
+                    ntor.clone()
+                } else if ntor.int_is_negative() {
+                    ntor.int_div(dtor).int_sub(&Int::int_one())
+                } else {
+                    ntor.int_div(dtor)
+                }
+            }
         }
     }
 
     fn ceil(&self) -> Int {
-        if self.dtor.int_is_one() {
-            self.ntor.clone()
-        } else if self.ntor.int_is_positive() {
-            self.ntor.int_div(&self.dtor).int_add(&Int::int_one())
-        } else {
-            self.ntor.int_div(&self.dtor)
+        match &self.repr {
+            Repr::Small { ntor, dtor } => {
+                let q = ntor.div_euclid(*dtor) as i128;
+                Int::from(if q * *dtor as i128 == *ntor as i128 { q } else { q + 1 })
+            }
+            Repr::Big { ntor, dtor } => {
+                if dtor.int_is_one() {
+                    ntor.clone()
+                } else if ntor.int_is_positive() {
+                    ntor.int_div(dtor).int_add(&Int::int_one())
+                } else {
+                    ntor.int_div(dtor)
+                }
+            }
         }
     }
 
@@ -328,16 +817,6 @@ impl FracOps for BigFractionInner {
     }
 }
 
-#[cfg(not(feature = "gmp"))]
-impl PartialEq for BigFractionInner {
-    fn eq(&self, other: &Self) -> bool {
-        self.ntor == other.ntor && self.dtor == other.dtor
-    }
-}
-
-#[cfg(not(feature = "gmp"))]
-impl Eq for BigFractionInner {}
-
 #[cfg(not(feature = "gmp"))]
 impl PartialOrd for BigFractionInner {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
@@ -348,21 +827,36 @@ impl PartialOrd for BigFractionInner {
 #[cfg(not(feature = "gmp"))]
 impl Ord for BigFractionInner {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.ntor.int_mul(&other.dtor).cmp(&other.ntor.int_mul(&self.dtor))
+        if let (Repr::Small { ntor: a, dtor: b }, Repr::Small { ntor: c, dtor: d }) = (&self.repr, &other.repr) {
+            return (*a as i128 * *d as i128).cmp(&(*c as i128 * *b as i128));
+        }
+        let (a, b) = self.parts();
+        let (c, d) = other.parts();
+        a.int_mul(&d).cmp(&c.int_mul(&b))
     }
 }
 
 #[cfg(not(feature = "gmp"))]
 impl fmt::Display for BigFractionInner {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.dtor.int_is_one() {
-            write!(f, "{}", self.ntor)
+        let (ntor, dtor) = self.parts();
+        if dtor.int_is_one() {
+            write!(f, "{}", ntor)
         } else {
-            write!(f, "{}/{}", self.ntor, self.dtor)
+            write!(f, "{}/{}", ntor, dtor)
         }
     }
 }
 
+#[cfg(not(feature = "gmp"))]
+impl std::str::FromStr for BigFractionInner {
+    type Err = ParseFractionError;
+
+    fn from_str(s: &str) -> Result<Self, ParseFractionError> {
+        Self::frac_parse(s)
+    }
+}
+
 #[cfg(not(feature = "gmp"))]
 impl From<i64> for BigFractionInner {
     fn from(n: i64) -> Self { Self::frac_from_i64(n) }
@@ -372,3 +866,292 @@ impl From<i64> for BigFractionInner {
 impl From<Int> for BigFractionInner {
     fn from(n: Int) -> Self { Self::frac_from_int(n) }
 }
+
+// ─── Operators ───────────────────────────────────────────────────────────────
+
+#[cfg(not(feature = "gmp"))]
+macro_rules! frac_binary_op {
+    ($op:ident, $method:ident, $assign_op:ident, $assign_method:ident, $frac_method:ident, $assign_body:expr) => {
+        impl std::ops::$op<&BigFractionInner> for &BigFractionInner {
+            type Output = BigFractionInner;
+            fn $method(self, other: &BigFractionInner) -> BigFractionInner {
+                self.$frac_method(other)
+            }
+        }
+
+        impl std::ops::$op<BigFractionInner> for &BigFractionInner {
+            type Output = BigFractionInner;
+            fn $method(self, other: BigFractionInner) -> BigFractionInner {
+                self.$frac_method(&other)
+            }
+        }
+
+        impl std::ops::$op<&BigFractionInner> for BigFractionInner {
+            type Output = BigFractionInner;
+            fn $method(self, other: &BigFractionInner) -> BigFractionInner {
+                self.$frac_method(other)
+            }
+        }
+
+        impl std::ops::$op<BigFractionInner> for BigFractionInner {
+            type Output = BigFractionInner;
+            fn $method(self, other: BigFractionInner) -> BigFractionInner {
+                self.$frac_method(&other)
+            }
+        }
+
+        impl std::ops::$assign_op<&BigFractionInner> for BigFractionInner {
+            fn $assign_method(&mut self, other: &BigFractionInner) {
+                $assign_body(self, other);
+            }
+        }
+
+        impl std::ops::$assign_op<BigFractionInner> for BigFractionInner {
+            fn $assign_method(&mut self, other: BigFractionInner) {
+                $assign_body(self, &other);
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "gmp"))]
+frac_binary_op!(Add, add, AddAssign, add_assign, add_frac, FracOps::add_assign_frac);
+#[cfg(not(feature = "gmp"))]
+frac_binary_op!(Sub, sub, SubAssign, sub_assign, sub_frac, FracOps::sub_assign_frac);
+#[cfg(not(feature = "gmp"))]
+frac_binary_op!(Mul, mul, MulAssign, mul_assign, mul_frac, FracOps::mul_assign_frac);
+#[cfg(not(feature = "gmp"))]
+frac_binary_op!(Div, div, DivAssign, div_assign, div_frac, |x: &mut BigFractionInner, y: &BigFractionInner| {
+    *x = x.div_frac(y)
+});
+
+#[cfg(not(feature = "gmp"))]
+impl std::ops::Neg for &BigFractionInner {
+    type Output = BigFractionInner;
+    fn neg(self) -> BigFractionInner {
+        self.negate()
+    }
+}
+
+#[cfg(not(feature = "gmp"))]
+impl std::ops::Neg for BigFractionInner {
+    type Output = BigFractionInner;
+    fn neg(self) -> BigFractionInner {
+        self.negate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(value: i64) -> Int {
+        Int::int_from_i64(value)
+    }
+
+    #[test]
+    fn test_round_matches_java() {
+        // Math.round rounds halves up, also below zero.
+        assert_eq!(FracOps::round(&BigFraction::frac_new(5, 2)), int(3));
+        assert_eq!(FracOps::round(&BigFraction::frac_new(-5, 2)), int(-2));
+        assert_eq!(FracOps::round(&BigFraction::frac_new(-13, 5)), int(-3));
+        assert_eq!(FracOps::round(&BigFraction::frac_new(-12, 5)), int(-2));
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        let halves = [(5, 2), (-5, 2), (7, 2), (-7, 2)];
+        type Rounding = fn(&BigFraction) -> Int;
+        let modes: [(Rounding, [i64; 4]); 3] = [
+            (BigFraction::round_half_even, [2, -2, 4, -4]),
+            (BigFraction::round_half_away_from_zero, [3, -3, 4, -4]),
+            (BigFraction::round_toward_zero, [2, -2, 3, -3]),
+        ];
+        for (round, expected) in modes {
+            for (&(n, d), e) in halves.iter().zip(expected) {
+                assert_eq!(round(&BigFraction::frac_new(n, d)), int(e), "{}/{}", n, d);
+            }
+        }
+        assert_eq!(BigFraction::frac_new(-13, 5).round_half_even(), int(-3));
+    }
+
+    #[test]
+    fn test_floor_div_mod() {
+        let (x, y) = (BigFraction::frac_new(-7, 2), BigFraction::frac_from_i64(2));
+        assert_eq!(x.floor_div(&y), int(-2));
+        assert_eq!(x.floor_mod(&y), BigFraction::frac_new(1, 2));
+        assert_eq!(x.floor_mod(&y.negate()), BigFraction::frac_new(-3, 2));
+    }
+
+    #[test]
+    fn test_to_f64_approx_with_huge_parts() {
+        let pow2 = |bits| (0..bits).fold(int(1), |x, _| x.int_add(&x));
+        let frac = |n: Int, d: Int| BigFraction::frac_from_bigint(n).div_int(&d);
+        let close = |x: &BigFraction, expected: f64| {
+            let value = x.frac_to_f64_approx();
+            assert!((value / expected - 1.0).abs() < 1e-12, "{} instead of {}", value, expected);
+        };
+
+        // Only one side is too large for an f64.
+        let (big, small) = (pow2(1030), pow2(20).int_add(&int(1)));
+        let quotient = 2f64.powi(1010) * (1048576.0 / 1048577.0);
+        close(&frac(big.clone(), small.clone()), quotient);
+        close(&frac(big.int_neg(), small.clone()), -quotient);
+        close(&frac(small, big), 2f64.powi(-1010) * (1048577.0 / 1048576.0));
+        // Both are, and so is the scale between them.
+        close(&frac(pow2(2000).int_mul(&int(3)), pow2(2001).int_add(&int(1))), 1.5);
+        close(&frac(pow2(2025).int_add(&int(1)), pow2(1002).int_add(&int(1))), 2f64.powi(1023));
+        assert_eq!(frac(pow2(1100), int(3)).frac_to_f64_approx(), f64::INFINITY);
+        assert_eq!(frac(int(3), pow2(1100)).frac_to_f64_approx(), 0.0);
+    }
+
+    /// Checks `x == n / d` in lowest terms, and that `x` is small exactly
+    /// when both parts fit in an `i64`.
+    #[cfg(not(feature = "gmp"))]
+    fn assert_parts(x: &BigFraction, n: Int, d: Int) {
+        assert_eq!((x.numerator_int(), x.denominator_int()), (n.clone(), d.clone()));
+        assert_eq!(x.is_big(), n.to_i64().is_none() || d.to_i64().is_none(), "{}", x);
+    }
+
+    #[cfg(not(feature = "gmp"))]
+    #[test]
+    fn test_i64_min_parts() {
+        let two_63 = int(i64::MAX).int_add(&int(1));
+        let min = BigFraction::frac_new(i64::MIN, 1);
+        assert_parts(&min, int(i64::MIN), int(1));
+        assert_parts(&min.negate(), two_63.clone(), int(1));
+        assert_eq!(min.negate().negate(), min);
+        assert_parts(&BigFraction::frac_new(i64::MIN, -1), two_63.clone(), int(1));
+        assert_parts(&BigFraction::frac_new(i64::MIN, 2), int(i64::MIN / 2), int(1));
+        assert_parts(&BigFraction::frac_new(i64::MIN, 3), int(i64::MIN), int(3));
+
+        assert_parts(&BigFraction::frac_new(1, i64::MIN), int(-1), two_63.clone());
+        assert_parts(&BigFraction::frac_new(-1, i64::MIN), int(1), two_63.clone());
+        assert_parts(&BigFraction::frac_new(2, i64::MIN), int(-1), int(1 << 62));
+        assert_parts(&BigFraction::frac_new(i64::MIN, i64::MIN), int(1), int(1));
+        assert_parts(&min.reciprocal().unwrap(), int(-1), two_63);
+        assert_eq!(min.reciprocal().unwrap().reciprocal().unwrap(), min);
+
+        let third = BigFraction::frac_new(i64::MIN, 3);
+        assert_eq!(third.floor(), int(i64::MIN / 3 - 1));
+        assert_eq!(third.ceil(), int(i64::MIN / 3));
+        assert_eq!(min.sub_frac(&BigFraction::frac_new(i64::MIN, 1)), BigFraction::frac_zero());
+        assert_parts(&min.div_frac(&BigFraction::frac_minus_one()), int(i64::MAX).int_add(&int(1)), int(1));
+    }
+
+    #[cfg(not(feature = "gmp"))]
+    #[test]
+    fn test_overflow_promotes_and_reduces_back() {
+        let max = BigFraction::frac_from_i64(i64::MAX);
+        let sum = max.add_frac(&max);
+        assert_parts(&sum, int(i64::MAX).int_mul(&int(2)), int(1));
+        assert_parts(&sum.sub_frac(&max), int(i64::MAX), int(1));
+        assert_parts(&sum.mul_frac(&BigFraction::frac_half()), int(i64::MAX), int(1));
+        assert_parts(&sum.div_int(&int(2)), int(i64::MAX), int(1));
+        let below_min = BigFraction::frac_from_i64(i64::MIN).sub_frac(&BigFraction::frac_one());
+        assert_parts(&below_min, int(i64::MIN).int_sub(&int(1)), int(1));
+
+        // Denominators overflow too, and cancel back.
+        let (a, b) = (BigFraction::frac_new(1, i64::MAX), BigFraction::frac_new(1, i64::MAX - 1));
+        let both = a.add_frac(&b);
+        assert!(both.is_big());
+        assert_parts(&both.sub_frac(&b), int(1), int(i64::MAX));
+        let product = a.mul_frac(&b);
+        assert_parts(&product, int(1), int(i64::MAX).int_mul(&int(i64::MAX - 1)));
+        assert_parts(&product.div_frac(&b), int(1), int(i64::MAX));
+
+        // The in-place forms normalize back as well.
+        let mut x = sum.clone();
+        x.sub_assign_frac(&max);
+        assert_parts(&x, int(i64::MAX), int(1));
+        x.add_assign_frac(&max);
+        assert_eq!(x, sum);
+        x.sub_mul_assign_frac(&max, &BigFraction::frac_from_i64(2));
+        assert_parts(&x, int(0), int(1));
+        x.add_mul_assign_frac(&max, &max);
+        x.mul_assign_frac(&a.mul_frac(&a));
+        assert_parts(&x, int(1), int(1));
+        x.sub_mul_assign_int(&a, &int(i64::MAX).int_mul(&int(4)));
+        assert_parts(&x, int(-3), int(1));
+    }
+
+    #[cfg(not(feature = "gmp"))]
+    #[test]
+    fn test_compare_across_representations() {
+        let max = BigFraction::frac_from_i64(i64::MAX);
+        let min = BigFraction::frac_from_i64(i64::MIN);
+        let above = max.add_frac(&BigFraction::frac_one());
+        let below = min.sub_frac(&BigFraction::frac_one());
+        let tiny = BigFraction::frac_new(-1, i64::MIN);
+        assert!(above.is_big() && below.is_big() && tiny.is_big());
+
+        let zero = BigFraction::frac_zero();
+        let mut values = vec![above.clone(), max.clone(), tiny.clone(), below.clone(), zero.clone(), min.clone()];
+        values.sort();
+        assert_eq!(values, vec![below, min, zero, tiny.clone(), max.clone(), above.clone()]);
+        assert!(tiny < BigFraction::frac_new(1, i64::MAX));
+        assert_eq!(above.sub_frac(&BigFraction::frac_one()), max);
+        assert_ne!(above, max);
+    }
+
+    #[test]
+    fn test_parse_fraction() {
+        let parsed = |text: &str| parse_fraction(text).unwrap();
+        assert_eq!(parsed("12"), BigFraction::frac_from_i64(12));
+        assert_eq!(parsed(" -12 "), BigFraction::frac_from_i64(-12));
+        assert_eq!(parsed("3/-4"), BigFraction::frac_new(-3, 4));
+        assert_eq!(parsed("-6 / 8"), BigFraction::frac_new(-3, 4));
+        assert_eq!(parsed("-1.25"), BigFraction::frac_new(-5, 4));
+        assert_eq!(parsed(".5"), BigFraction::frac_new(1, 2));
+        assert_eq!(parsed("-.5"), BigFraction::frac_new(-1, 2));
+        assert_eq!(parsed("+2."), BigFraction::frac_from_i64(2));
+        let digits = "123456789012345678901234567890";
+        assert_eq!(parsed(digits), BigFraction::frac_from_int(digits.parse().unwrap()));
+
+        for text in ["", ".", "-", "1/0", "1/2/3", "1.2.3", "1e3", "- 1", "0x10", "1,5", "abc", "1/.5"] {
+            assert_eq!(parse_fraction(text), Err(ParseFractionError { input: text.to_string() }), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_display_styles() {
+        let show = |n, d, style| FractionDisplay::new(&BigFraction::frac_new(n, d), style).to_string();
+        assert_eq!(show(-7, 2, FractionStyle::Fraction), "-7/2");
+        assert_eq!(show(-7, 2, FractionStyle::Mixed), "-3 1/2");
+        assert_eq!(show(-1, 2, FractionStyle::Mixed), "-1/2");
+        assert_eq!(show(6, 3, FractionStyle::Mixed), "2");
+
+        // Halves round away from zero, and a value rounding to zero loses its sign.
+        assert_eq!(show(1, 8, FractionStyle::Decimal(2)), "0.13");
+        assert_eq!(show(-1, 8, FractionStyle::Decimal(2)), "-0.13");
+        assert_eq!(show(2, 3, FractionStyle::Decimal(3)), "0.667");
+        assert_eq!(show(5, 2, FractionStyle::Decimal(0)), "3");
+        assert_eq!(show(-1, 1000, FractionStyle::Decimal(2)), "0.00");
+        assert_eq!(show(-1234, 100, FractionStyle::Decimal(1)), "-12.3");
+
+        // What a decimal writes parses back to the rounded value.
+        let rounded = show(-22, 7, FractionStyle::Decimal(4));
+        assert_eq!(rounded, "-3.1429");
+        assert_eq!(parse_fraction(&rounded).unwrap(), BigFraction::frac_new(-31429, 10000));
+    }
+
+    #[test]
+    fn test_approximate() {
+        let pi = BigFraction::frac_new(314_159_265_358_979, 100_000_000_000_000);
+        assert_eq!(pi.approximate(&int(1000)), BigFraction::frac_new(355, 113));
+        assert_eq!(pi.approximate(&int(100)), BigFraction::frac_new(311, 99));
+        assert_eq!(pi.negate().approximate(&int(100)), BigFraction::frac_new(-311, 99));
+
+        // An approximation close enough recovers the exact value.
+        let third = BigFraction::frac_new(333_333_333_334, 1_000_000_000_000);
+        assert_eq!(third.approximate(&int(10)), BigFraction::frac_new(1, 3));
+        assert_eq!(BigFraction::frac_new(-22, 7).approximate(&int(7)), BigFraction::frac_new(-22, 7));
+    }
+
+    #[test]
+    fn test_approximate_to_integer() {
+        for (n, d, expected) in [(7, 3, 2), (5, 3, 2), (-8, 3, -3), (-7, 3, -2), (1, 5, 0)] {
+            assert_eq!(BigFraction::frac_new(n, d).approximate(&int(1)), BigFraction::frac_from_i64(expected));
+        }
+    }
+}
@@ -1,5 +1,12 @@
 use super::big_fraction::{BigFraction, FracOps};
 use super::big_vector::BigVector;
+use super::int_type::{Int, IntOps};
+use super::mth;
+
+/// Products with at least this many multiply-adds run on the rayon pool;
+/// below it, spawning costs more than the rows take.
+#[cfg(feature = "rayon")]
+const PARALLEL_MULTIPLY_WORK: usize = 32 * 32 * 32;
 
 /// A matrix of BigFraction values stored in row-major order.
 #[derive(Clone, Debug)]
@@ -18,6 +25,17 @@ impl BigMatrix {
         }
     }
 
+    /// A matrix with the given vectors as its rows, all of the same dimension.
+    pub fn from_rows(rows: &[BigVector]) -> Self {
+        let cols = rows.first().map_or(0, BigVector::dimension);
+        let mut data = Vec::with_capacity(rows.len() * cols);
+        for row in rows {
+            assert_eq!(row.dimension(), cols);
+            data.extend((0..cols).map(|i| row.get(i).clone()));
+        }
+        BigMatrix { data, rows: rows.len(), cols }
+    }
+
     pub fn identity(size: usize) -> Self {
         let mut m = BigMatrix::new(size, size);
         for i in 0..size {
@@ -46,9 +64,49 @@ impl BigMatrix {
         self.data[row * self.cols + col] = value;
     }
 
-    pub fn get_row(&self, row: usize) -> BigVector {
+    pub fn get_mut(&mut self, row: usize, col: usize) -> &mut BigFraction {
+        &mut self.data[row * self.cols + col]
+    }
+
+    /// A row as a slice, without copying it
+    pub fn row(&self, row: usize) -> &[BigFraction] {
+        let start = row * self.cols;
+        &self.data[start..start + self.cols]
+    }
+
+    /// The rows in order, as slices
+    pub fn rows(&self) -> impl Iterator<Item = &[BigFraction]> + '_ {
+        (0..self.rows).map(move |r| self.row(r))
+    }
+
+    /// All entries in row-major order
+    pub fn iter(&self) -> std::slice::Iter<'_, BigFraction> {
+        self.data.iter()
+    }
+
+    /// A row as a mutable slice, for updating its entries in place
+    pub fn row_mut(&mut self, row: usize) -> &mut [BigFraction] {
         let start = row * self.cols;
-        BigVector::from_data(self.data[start..start + self.cols].to_vec())
+        &mut self.data[start..start + self.cols]
+    }
+
+    /// Two distinct rows as mutable slices, in the order asked for
+    pub fn row_pair_mut(&mut self, first: usize, second: usize) -> (&mut [BigFraction], &mut [BigFraction]) {
+        assert_ne!(first, second);
+        let cols = self.cols;
+        let (low, high) = (first.min(second), first.max(second));
+        let (head, tail) = self.data.split_at_mut(high * cols);
+        let low_row = &mut head[low * cols..(low + 1) * cols];
+        let high_row = &mut tail[..cols];
+        if first < second {
+            (low_row, high_row)
+        } else {
+            (high_row, low_row)
+        }
+    }
+
+    pub fn get_row(&self, row: usize) -> BigVector {
+        BigVector::from_data(self.row(row).to_vec())
     }
 
     pub fn set_row(&mut self, row: usize, v: &BigVector) {
@@ -101,27 +159,48 @@ impl BigMatrix {
         m
     }
 
+    /// `self * other`, a row at a time: row `r` of the product sums the rows
+    /// of `other` scaled by row `r` of `self`, so `other` is read row by row
+    /// and zero entries on either side (as in diagonal scaling matrices)
+    /// cost nothing. With the `rayon` feature, large products compute their
+    /// rows in parallel.
     pub fn multiply_matrix(&self, other: &BigMatrix) -> BigMatrix {
         assert_eq!(self.cols, other.rows);
         let mut result = BigMatrix::new(self.rows, other.cols);
-        for r in 0..self.rows {
-            for c in 0..other.cols {
-                let mut sum = BigFraction::frac_zero();
-                for k in 0..self.cols {
-                    sum = sum.add_frac(&self.get(r, k).mul_frac(other.get(k, c)));
+        if other.cols == 0 {
+            return result;
+        }
+        let fill = |(r, out): (usize, &mut [BigFraction])| {
+            for (a, other_row) in self.row(r).iter().zip(other.rows()) {
+                if a.is_zero() {
+                    continue;
+                }
+                for (sum, b) in out.iter_mut().zip(other_row) {
+                    if !b.is_zero() {
+                        sum.add_mul_assign_frac(a, b);
+                    }
                 }
-                result.set(r, c, sum);
             }
+        };
+
+        #[cfg(feature = "rayon")]
+        if self.rows * self.cols * other.cols >= PARALLEL_MULTIPLY_WORK {
+            use rayon::prelude::*;
+            result.data.par_chunks_mut(other.cols).enumerate().for_each(fill);
+            return result;
         }
+        result.data.chunks_mut(other.cols).enumerate().for_each(fill);
         result
     }
 
     pub fn multiply_vector(&self, v: &BigVector) -> BigVector {
         assert_eq!(self.cols, v.dimension());
         let mut result = BigVector::new(self.rows);
-        for r in 0..self.rows {
-            let row = self.get_row(r);
-            result.set(r, row.dot(v));
+        for (r, row) in self.rows().enumerate() {
+            let sum = result.get_mut(r);
+            for (i, x) in row.iter().enumerate() {
+                sum.add_mul_assign_frac(x, v.get(i));
+            }
         }
         result
     }
@@ -151,36 +230,138 @@ impl BigMatrix {
         m
     }
 
+    /// Exact determinant, by fraction-free Bareiss elimination on the matrix
+    /// with each row scaled to integers. One for an empty matrix.
+    pub fn determinant(&self) -> BigFraction {
+        assert!(self.is_square(), "Matrix is not square");
+        let mut scale = Int::int_one();
+        let mut m: Vec<Vec<Int>> = self
+            .rows()
+            .map(|row| {
+                let lcm = row.iter().fold(Int::int_one(), |l, x| mth::lcm_int(&l, &x.denominator_int()));
+                let scaled = row.iter().map(|x| x.mul_int(&lcm).numerator_int()).collect();
+                scale = scale.int_mul(&lcm);
+                scaled
+            })
+            .collect();
+        BigFraction::frac_from_int(bareiss(&mut m)).div_int(&scale)
+    }
+
     /// Row operations for Gauss-Jordan / LU:
     pub fn row_subtract_scaled(&mut self, target_row: usize, source_row: usize, scale: &BigFraction) {
-        for c in 0..self.cols {
-            let val = self.get(target_row, c).sub_frac(&self.get(source_row, c).mul_frac(scale));
-            self.set(target_row, c, val);
+        let (target, source) = self.row_pair_mut(target_row, source_row);
+        for (t, s) in target.iter_mut().zip(source.iter()) {
+            t.sub_mul_assign_frac(s, scale);
         }
     }
 
+    /// Panics if `divisor` is zero.
     pub fn row_divide(&mut self, row: usize, divisor: &BigFraction) {
-        let recip = divisor.reciprocal();
-        for c in 0..self.cols {
-            let val = self.get(row, c).mul_frac(&recip);
-            self.set(row, c, val);
-        }
+        let recip = divisor.reciprocal().expect("Division by zero");
+        self.row_multiply(row, &recip);
     }
 
     /// Multiply row by scalar in place
     pub fn row_multiply(&mut self, row: usize, scalar: &BigFraction) {
-        for c in 0..self.cols {
-            let val = self.get(row, c).mul_frac(scalar);
-            self.set(row, c, val);
+        for value in self.row_mut(row) {
+            value.mul_assign_frac(scalar);
         }
     }
 
     /// Add scaled row to target
     pub fn row_add_scaled(&mut self, target_row: usize, source_row: usize, scale: &BigFraction) {
+        let (target, source) = self.row_pair_mut(target_row, source_row);
+        for (t, s) in target.iter_mut().zip(source.iter()) {
+            t.add_mul_assign_frac(s, scale);
+        }
+    }
+
+    /// Subtract an integer multiple of one row from another in place
+    pub fn row_subtract_scaled_int(&mut self, target_row: usize, source_row: usize, scale: &Int) {
+        let (target, source) = self.row_pair_mut(target_row, source_row);
+        for (t, s) in target.iter_mut().zip(source.iter()) {
+            t.sub_mul_assign_int(s, scale);
+        }
+    }
+
+    /// Overwrite a row with a row of another matrix of the same width
+    pub fn copy_row_from(&mut self, row: usize, other: &BigMatrix, other_row: usize) {
+        assert_eq!(self.cols, other.cols);
+        for c in 0..self.cols {
+            self.data[row * self.cols + c].clone_from(other.get(other_row, c));
+        }
+    }
+
+    /// Dot product of a row with a row of another matrix, without copying either
+    pub fn row_dot(&self, row: usize, other: &BigMatrix, other_row: usize) -> BigFraction {
+        assert_eq!(self.cols, other.cols);
+        let mut result = BigFraction::frac_zero();
         for c in 0..self.cols {
-            let val = self.get(target_row, c).add_frac(&self.get(source_row, c).mul_frac(scale));
-            self.set(target_row, c, val);
+            result.add_mul_assign_frac(self.get(row, c), other.get(other_row, c));
+        }
+        result
+    }
+
+    pub fn row_magnitude_sq(&self, row: usize) -> BigFraction {
+        self.row_dot(row, self, row)
+    }
+}
+
+/// Determinant of a square integer matrix, by Bareiss elimination: after
+/// step `k` every remaining entry is a `(k + 1)`-minor of the input, so each
+/// division is exact and no entry grows past the size of such a minor.
+fn bareiss(m: &mut [Vec<Int>]) -> Int {
+    let size = m.len();
+    let mut negative = false;
+    let mut previous = Int::int_one();
+    for k in 0..size {
+        let Some(pivot) = (k..size).find(|&row| !m[row][k].int_is_zero()) else {
+            return Int::int_zero();
+        };
+        if pivot != k {
+            m.swap(k, pivot);
+            negative = !negative;
         }
+        let (done, rest) = m.split_at_mut(k + 1);
+        let pivot_row = &done[k];
+        for row in rest.iter_mut() {
+            let factor = row[k].clone();
+            for (value, p) in row.iter_mut().zip(pivot_row).skip(k + 1) {
+                *value = value.int_mul(&pivot_row[k]).int_sub(&factor.int_mul(p)).int_div(&previous);
+            }
+        }
+        previous = pivot_row[k].clone();
+    }
+    if negative { previous.int_neg() } else { previous }
+}
+
+impl std::ops::Index<(usize, usize)> for BigMatrix {
+    type Output = BigFraction;
+
+    fn index(&self, (row, col): (usize, usize)) -> &BigFraction {
+        self.get(row, col)
+    }
+}
+
+impl std::ops::IndexMut<(usize, usize)> for BigMatrix {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut BigFraction {
+        self.get_mut(row, col)
+    }
+}
+
+impl std::ops::Mul<&BigMatrix> for &BigMatrix {
+    type Output = BigMatrix;
+
+    fn mul(self, other: &BigMatrix) -> BigMatrix {
+        self.multiply_matrix(other)
+    }
+}
+
+impl std::ops::Mul<&BigVector> for &BigMatrix {
+    type Output = BigVector;
+
+    fn mul(self, v: &BigVector) -> BigVector {
+        self.multiply_vector(v)
     }
 }
 
@@ -196,3 +377,45 @@ impl std::fmt::Display for BigMatrix {
         write!(f, "}}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(rows: &[&[(i64, i64)]]) -> BigMatrix {
+        let rows: Vec<BigVector> = rows
+            .iter()
+            .map(|row| BigVector::from_data(row.iter().map(|&(n, d)| BigFraction::frac_new(n, d)).collect()))
+            .collect();
+        BigMatrix::from_rows(&rows)
+    }
+
+    fn integers(rows: &[&[i64]]) -> BigMatrix {
+        let rows: Vec<Vec<(i64, i64)>> = rows.iter().map(|row| row.iter().map(|&x| (x, 1)).collect()).collect();
+        matrix(&rows.iter().map(Vec::as_slice).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_determinant() {
+        assert_eq!(BigMatrix::new(0, 0).determinant(), BigFraction::frac_from_i64(1));
+        assert_eq!(integers(&[&[2, -1, 0], &[-1, 2, -1], &[0, -1, 2]]).determinant(), BigFraction::frac_from_i64(4));
+        // 1/2 - 1/12, with each row scaled by a different denominator.
+        assert_eq!(matrix(&[&[(1, 2), (1, 3)], &[(1, 4), (1, 1)]]).determinant(), BigFraction::frac_new(5, 12));
+    }
+
+    #[test]
+    fn test_singular_determinant() {
+        assert_eq!(integers(&[&[1, 2, 3], &[4, 5, 6], &[7, 8, 9]]).determinant(), BigFraction::frac_zero());
+        assert_eq!(integers(&[&[1, 2, 3], &[2, 4, 7], &[3, 6, 1]]).determinant(), BigFraction::frac_zero());
+    }
+
+    #[test]
+    fn test_determinant_with_row_swaps() {
+        // Zero in the first pivot position.
+        assert_eq!(integers(&[&[0, 1, 2], &[1, 0, 3], &[4, -3, 8]]).determinant(), BigFraction::frac_from_i64(-2));
+        // The second pivot only vanishes after the first elimination step.
+        assert_eq!(integers(&[&[1, 1, 1], &[1, 1, 2], &[1, 2, 3]]).determinant(), BigFraction::frac_from_i64(-1));
+        // Two swaps cancel out.
+        assert_eq!(integers(&[&[0, 0, 3], &[2, 0, 0], &[0, 5, 0]]).determinant(), BigFraction::frac_from_i64(30));
+    }
+}
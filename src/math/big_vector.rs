@@ -2,8 +2,8 @@ use super::big_fraction::{BigFraction, FracOps};
 use super::int_type::Int;
 use std::fmt;
 
-/// A vector of BigFraction values.
-#[derive(Clone, Debug)]
+/// A vector of BigFraction values. Vectors order lexicographically.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct BigVector {
     data: Vec<BigFraction>,
 }
@@ -31,14 +31,29 @@ impl BigVector {
         self.data[i] = value;
     }
 
+    pub fn get_mut(&mut self, i: usize) -> &mut BigFraction {
+        &mut self.data[i]
+    }
+
     pub fn magnitude_sq(&self) -> BigFraction {
         let mut mag = BigFraction::frac_zero();
-        for i in 0..self.dimension() {
-            mag = mag.add_frac(&self.data[i].mul_frac(&self.data[i]));
+        for x in &self.data {
+            mag.add_mul_assign_frac(x, x);
         }
         mag
     }
 
+    /// Euclidean length, approximated in floating point for heuristics.
+    pub fn norm_f64(&self) -> f64 {
+        let values: Vec<f64> = self.data.iter().map(FracOps::frac_to_f64_approx).collect();
+        // Scale by the largest entry so the squares cannot overflow.
+        let largest = values.iter().fold(0.0f64, |m, x| m.max(x.abs()));
+        if largest == 0.0 || !largest.is_finite() {
+            return largest;
+        }
+        largest * values.iter().map(|x| (x / largest).powi(2)).sum::<f64>().sqrt()
+    }
+
     pub fn is_zero(&self) -> bool {
         self.data.iter().all(|x| x.signum() == 0)
     }
@@ -69,15 +84,31 @@ impl BigVector {
 
     pub fn subtract_assign(&mut self, other: &BigVector) {
         assert_eq!(self.dimension(), other.dimension());
-        for i in 0..self.dimension() {
-            self.data[i] = self.data[i].sub_frac(&other.data[i]);
+        for (x, y) in self.data.iter_mut().zip(&other.data) {
+            x.sub_assign_frac(y);
         }
     }
 
     pub fn add_assign(&mut self, other: &BigVector) {
         assert_eq!(self.dimension(), other.dimension());
-        for i in 0..self.dimension() {
-            self.data[i] = self.data[i].add_frac(&other.data[i]);
+        for (x, y) in self.data.iter_mut().zip(&other.data) {
+            x.add_assign_frac(y);
+        }
+    }
+
+    /// `self += scale * other`
+    pub fn add_scaled_assign(&mut self, other: &BigVector, scale: &BigFraction) {
+        assert_eq!(self.dimension(), other.dimension());
+        for (x, y) in self.data.iter_mut().zip(&other.data) {
+            x.add_mul_assign_frac(y, scale);
+        }
+    }
+
+    /// `self -= scale * other`
+    pub fn subtract_scaled_assign(&mut self, other: &BigVector, scale: &BigFraction) {
+        assert_eq!(self.dimension(), other.dimension());
+        for (x, y) in self.data.iter_mut().zip(&other.data) {
+            x.sub_mul_assign_frac(y, scale);
         }
     }
 
@@ -94,25 +125,71 @@ impl BigVector {
     }
 
     pub fn multiply_scalar_assign(&mut self, scalar: &BigFraction) {
-        for i in 0..self.dimension() {
-            self.data[i] = self.data[i].mul_frac(scalar);
+        for x in self.data.iter_mut() {
+            x.mul_assign_frac(scalar);
         }
     }
 
+    /// Panics if `scalar` is zero.
     pub fn divide_scalar_assign(&mut self, scalar: &BigFraction) {
-        let recip = scalar.reciprocal();
+        let recip = scalar.reciprocal().expect("Division by zero");
         self.multiply_scalar_assign(&recip);
     }
 
     pub fn dot(&self, other: &BigVector) -> BigFraction {
         assert_eq!(self.dimension(), other.dimension());
         let mut result = BigFraction::frac_zero();
-        for i in 0..self.dimension() {
-            result = result.add_frac(&self.data[i].mul_frac(&other.data[i]));
+        for (x, y) in self.data.iter().zip(&other.data) {
+            result.add_mul_assign_frac(x, y);
         }
         result
     }
 
+    /// `self . onto / onto . onto`, the multiple of `onto` that is the
+    /// projection of `self` onto it. Panics if `onto` is zero.
+    pub fn projection_coefficient(&self, onto: &BigVector) -> BigFraction {
+        let norm = onto.magnitude_sq();
+        assert!(!norm.is_zero(), "Projection onto the zero vector");
+        self.dot(onto).div_frac(&norm)
+    }
+
+    /// The projection of `self` onto the line through `onto`.
+    pub fn project_onto(&self, onto: &BigVector) -> BigVector {
+        onto.multiply_scalar(&self.projection_coefficient(onto))
+    }
+
+    /// Remove from `self` its projection onto each of `others`, which must
+    /// be pairwise orthogonal, as one Gram–Schmidt step does. Returns the
+    /// projection coefficients; zero vectors in `others` are skipped, with a
+    /// coefficient of zero.
+    pub fn orthogonalize_against(&mut self, others: &[BigVector]) -> Vec<BigFraction> {
+        others
+            .iter()
+            .map(|other| {
+                if other.is_zero() {
+                    return BigFraction::frac_zero();
+                }
+                let mu = self.projection_coefficient(other);
+                self.subtract_scaled_assign(other, &mu);
+                mu
+            })
+            .collect()
+    }
+
+    /// Cross product of two 3-dimensional vectors.
+    pub fn cross(&self, other: &BigVector) -> BigVector {
+        assert!(self.dimension() == 3 && other.dimension() == 3, "Cross product needs 3 dimensions");
+        let term = |i: usize, j: usize| self.data[i].mul_frac(&other.data[j]) - other.data[i].mul_frac(&self.data[j]);
+        BigVector::from_data(vec![term(1, 2), term(2, 0), term(0, 1)])
+    }
+
+    /// Scalar triple product `self . (b x c)`: the signed volume of the
+    /// parallelepiped the three vectors span, zero when they are coplanar.
+    /// 3-dimensional only.
+    pub fn triple_product(&self, b: &BigVector, c: &BigVector) -> BigFraction {
+        self.dot(&b.cross(c))
+    }
+
     pub fn swap(&mut self, i: usize, j: usize) {
         self.data.swap(i, j);
     }
@@ -141,3 +218,35 @@ impl fmt::Display for BigVector {
         write!(f, "}}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vector(values: [i64; 3]) -> BigVector {
+        BigVector::from_data(values.iter().map(|&x| BigFraction::frac_from_i64(x)).collect())
+    }
+
+    #[test]
+    fn test_cross() {
+        let (a, b) = (vector([1, 2, 3]), vector([-4, 0, 5]));
+        let c = a.cross(&b);
+        assert_eq!(c, vector([10, -17, 8]));
+        assert!(c.dot(&a).is_zero() && c.dot(&b).is_zero());
+        assert_eq!(b.cross(&a), vector([-10, 17, -8]));
+        assert!(a.cross(&a).is_zero());
+    }
+
+    #[test]
+    fn test_triple_product() {
+        let (x, y, z) = (BigVector::basis_one(3, 0), BigVector::basis_one(3, 1), BigVector::basis_one(3, 2));
+        assert_eq!(x.triple_product(&y, &z), BigFraction::frac_one());
+        assert_eq!(y.triple_product(&z, &x), BigFraction::frac_one());
+        assert_eq!(y.triple_product(&x, &z), BigFraction::frac_minus_one());
+
+        // det [[2, 0, 1], [1, 3, 0], [0, 1, 4]]
+        let (a, b, c) = (vector([2, 0, 1]), vector([1, 3, 0]), vector([0, 1, 4]));
+        assert_eq!(a.triple_product(&b, &c), BigFraction::frac_from_i64(25));
+        assert!(a.triple_product(&b, &a.add(&b)).is_zero());
+    }
+}
@@ -0,0 +1,36 @@
+use std::fmt;
+
+/// Failure of an exact arithmetic operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MathError {
+    /// Division of a fraction by zero.
+    DivisionByZero,
+    /// Inversion or solve against a singular matrix.
+    SingularMatrix,
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MathError::DivisionByZero => write!(f, "division by zero"),
+            MathError::SingularMatrix => write!(f, "matrix is singular"),
+        }
+    }
+}
+
+impl std::error::Error for MathError {}
+
+/// A string that [`FracOps::frac_parse`](super::big_fraction::FracOps::frac_parse)
+/// could not read as a fraction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseFractionError {
+    pub input: String,
+}
+
+impl fmt::Display for ParseFractionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid fraction: '{}'", self.input)
+    }
+}
+
+impl std::error::Error for ParseFractionError {}
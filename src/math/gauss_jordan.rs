@@ -1,14 +1,58 @@
-#[allow(unused_imports)]
-use super::big_fraction::FracOps;
+use super::big_fraction::{BigFraction, FracOps};
 use super::big_matrix::BigMatrix;
+use super::big_vector::BigVector;
 
-/// Gauss-Jordan elimination. Returns pivot_rows array:
-/// pivot_rows[col] = row that has pivot in that column, or -1 if none.
+/// The structure Gauss-Jordan elimination found in a matrix.
+#[derive(Clone, Debug)]
+pub struct Reduction {
+    /// `pivot_rows[col]` = row that has pivot in that column, or -1 if none.
+    pub pivot_rows: Vec<i32>,
+    /// Number of pivots; the pivots are in rows `0..rank`.
+    pub rank: usize,
+}
+
+impl Reduction {
+    pub fn is_pivot(&self, col: usize) -> bool {
+        self.pivot_rows[col] != -1
+    }
+
+    /// A basis of the nullspace of the matrix `reduced` was reduced from,
+    /// one vector per column without a pivot, which is one in that column
+    /// and zero in the others. Setting it to one fixes each pivot column to
+    /// minus that column's entry in the pivot's row.
+    ///
+    /// `reduced` must have come from [`reduce_all`]: columns a predicate
+    /// skipped are not eliminated, so their vectors would be wrong.
+    pub fn nullspace(&self, reduced: &BigMatrix) -> Vec<BigVector> {
+        let cols = reduced.col_count();
+        (0..cols)
+            .filter(|&free| !self.is_pivot(free))
+            .map(|free| {
+                let mut v = BigVector::new(cols);
+                v.set(free, BigFraction::frac_one());
+                for (col, &row) in self.pivot_rows.iter().enumerate() {
+                    if row != -1 {
+                        v.set(col, reduced.get(row as usize, free).negate());
+                    }
+                }
+                v
+            })
+            .collect()
+    }
+}
+
+/// Gauss-Jordan elimination, bringing `matrix` to reduced row echelon form
+/// and applying the same row operations to `others`. Columns are only
+/// considered as pivots while `predicate(col, pivot_rows)` holds.
+///
+/// This works on fractions: it skips the rows with a zero in the pivot
+/// column, where fraction-free (Bareiss) elimination rescales every row at
+/// every step, and small fractions stay in machine integers.
 pub fn reduce(
     matrix: &mut BigMatrix,
     others: &mut [&mut BigMatrix],
     predicate: &dyn Fn(usize, &[i32]) -> bool,
-) -> Vec<i32> {
+) -> Reduction {
     let rows = matrix.row_count();
     let cols = matrix.col_count();
     let mut pivot_rows = vec![-1i32; cols];
@@ -70,9 +114,46 @@ pub fn reduce(
         }
     }
 
-    pivot_rows
+    Reduction { pivot_rows, rank: row }
 }
 
-pub fn reduce_all(matrix: &mut BigMatrix) -> Vec<i32> {
+pub fn reduce_all(matrix: &mut BigMatrix) -> Reduction {
     reduce(matrix, &mut [], &|_, _| true)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(rows: &[&[i64]]) -> BigMatrix {
+        let rows: Vec<BigVector> = rows
+            .iter()
+            .map(|row| BigVector::from_data(row.iter().map(|&x| BigFraction::frac_from_i64(x)).collect()))
+            .collect();
+        BigMatrix::from_rows(&rows)
+    }
+
+    #[test]
+    fn test_nullspace() {
+        let original = matrix(&[&[1, 2, 0, 3], &[2, 4, 1, 5], &[3, 6, 1, 8]]);
+        let mut reduced = original.clone();
+        let reduction = reduce_all(&mut reduced);
+        assert_eq!(reduction.rank, 2);
+        assert_eq!(reduction.pivot_rows, [0, -1, 1, -1]);
+        let nullspace = reduction.nullspace(&reduced);
+        assert_eq!(nullspace.len(), 2);
+        for v in &nullspace {
+            assert!((&original * v).is_zero(), "{}", v);
+        }
+    }
+
+    #[test]
+    fn test_predicate_skips_columns() {
+        let mut m = matrix(&[&[0, 1, 2], &[1, 0, 3]]);
+        let mut other = BigMatrix::identity(2);
+        let reduction = reduce(&mut m, &mut [&mut other], &|col, _| col != 1);
+        assert_eq!(reduction.pivot_rows, [0, -1, 1]);
+        // `other` records the row operations that took the rows to `m`.
+        assert!((&other * &matrix(&[&[0, 1, 2], &[1, 0, 3]])).rows().eq(m.rows()));
+    }
+}
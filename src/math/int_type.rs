@@ -28,8 +28,14 @@ pub trait IntOps: Sized {
     fn int_gcd(&self, other: &Self) -> Self;
     fn int_modpow(&self, exp: &Self, modulus: &Self) -> Self;
     fn int_shr(&self, bits: u32) -> Self;
+    /// Low 64 bits as an `i64`, wrapping values that do not fit.
     fn int_to_i64(&self) -> i64;
+    /// The value as an `i64`, or `None` if it does not fit.
+    fn int_to_i64_checked(&self) -> Option<i64>;
     fn int_to_f64_approx(&self) -> f64;
+    /// Base-2 logarithm of the absolute value, also for values beyond the
+    /// range of `f64`. Negative infinity for zero.
+    fn int_log2_approx(&self) -> f64;
 
     // Arithmetic (returns new value)
     fn int_add(&self, other: &Self) -> Self;
@@ -85,10 +91,23 @@ mod num_impl {
             result
         }
 
+        fn int_to_i64_checked(&self) -> Option<i64> {
+            ToPrimitive::to_i64(self)
+        }
+
         fn int_to_f64_approx(&self) -> f64 {
             ToPrimitive::to_f64(self).unwrap_or(0.0)
         }
 
+        fn int_log2_approx(&self) -> f64 {
+            let bits = self.bits();
+            if bits <= 64 {
+                return self.int_abs().int_to_f64_approx().log2();
+            }
+            let shift = bits - 64;
+            (self.int_abs() >> shift as usize).int_to_f64_approx().log2() + shift as f64
+        }
+
         fn int_add(&self, other: &Self) -> Self { self + other }
         fn int_sub(&self, other: &Self) -> Self { self - other }
         fn int_mul(&self, other: &Self) -> Self { self * other }
@@ -133,10 +152,23 @@ mod rug_impl {
             })
         }
 
+        fn int_to_i64_checked(&self) -> Option<i64> {
+            self.to_i64()
+        }
+
         fn int_to_f64_approx(&self) -> f64 {
             self.to_f64()
         }
 
+        fn int_log2_approx(&self) -> f64 {
+            let bits = self.significant_bits();
+            if bits <= 64 {
+                return self.int_abs().to_f64().log2();
+            }
+            let shift = bits - 64;
+            Integer::from(self.abs_ref() >> shift).to_f64().log2() + shift as f64
+        }
+
         fn int_add(&self, other: &Self) -> Self { Integer::from(self + other) }
         fn int_sub(&self, other: &Self) -> Self { Integer::from(self - other) }
         fn int_mul(&self, other: &Self) -> Self { Integer::from(self * other) }
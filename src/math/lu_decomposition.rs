@@ -1,69 +1,166 @@
 use super::big_fraction::{BigFraction, FracOps};
 use super::big_matrix::BigMatrix;
+use super::big_vector::BigVector;
+use super::error::MathError;
 
 /// LU Decomposition for BigMatrix (exact arithmetic with BigFraction).
-/// Returns the inverse matrix.
-pub fn inverse(matrix: &BigMatrix) -> BigMatrix {
-    assert!(matrix.is_square(), "Matrix is not square");
-    let size = matrix.row_count();
-
-    let mut m = matrix.clone();
-    let mut inv = BigMatrix::identity(size);
-
-    // Decomposition
-    for i in 0..size {
-        let mut pivot = None;
-        let mut biggest = BigFraction::frac_zero();
-
-        for row in i..size {
-            let d = m.get(row, i).frac_abs();
-            if d > biggest {
-                biggest = d;
-                pivot = Some(row);
+/// Returns the inverse matrix, or [`MathError::SingularMatrix`].
+pub fn inverse(matrix: &BigMatrix) -> Result<BigMatrix, MathError> {
+    LuDecomposition::new(matrix).map(|lu| lu.inverse())
+}
+
+/// `P A = L U` for a square nonsingular matrix `A`, pivoting on the largest
+/// entry of each column. Solving against it costs a forward and a back
+/// substitution, O(n^2) per right-hand side, so callers that only apply
+/// `A^-1` to a few vectors need not form the inverse.
+#[derive(Clone, Debug)]
+pub struct LuDecomposition {
+    /// `L` below the diagonal (its unit diagonal implied) and `U` on and
+    /// above it.
+    lu: BigMatrix,
+    /// `permutation[i]` is the row of `A` that ended up in row `i`.
+    permutation: Vec<usize>,
+}
+
+impl LuDecomposition {
+    /// The decomposition of `matrix`, or [`MathError::SingularMatrix`].
+    pub fn new(matrix: &BigMatrix) -> Result<Self, MathError> {
+        assert!(matrix.is_square(), "Matrix is not square");
+        let size = matrix.row_count();
+
+        let mut m = matrix.clone();
+        let mut permutation: Vec<usize> = (0..size).collect();
+
+        for i in 0..size {
+            let mut pivot = None;
+            let mut biggest = BigFraction::frac_zero();
+
+            for row in i..size {
+                let d = m.get(row, i).frac_abs();
+                if d > biggest {
+                    biggest = d;
+                    pivot = Some(row);
+                }
             }
-        }
 
-        let pivot = pivot.expect("Matrix is singular");
+            let pivot = pivot.ok_or(MathError::SingularMatrix)?;
 
-        inv.swap_rows(i, pivot);
-        if pivot != i {
-            m.swap_rows(i, pivot);
-        }
+            permutation.swap(i, pivot);
+            if pivot != i {
+                m.swap_rows(i, pivot);
+            }
 
-        for row in (i + 1)..size {
-            let val = m.get(row, i).div_frac(m.get(i, i));
-            m.set(row, i, val);
-        }
+            for row in (i + 1)..size {
+                let val = m.get(row, i).div_frac(m.get(i, i));
+                m.set(row, i, val);
+            }
 
-        for row in (i + 1)..size {
-            for col in (i + 1)..size {
-                let val = m.get(row, col).sub_frac(&m.get(row, i).mul_frac(m.get(i, col)));
-                m.set(row, col, val);
+            for row in (i + 1)..size {
+                let (values, pivot_row) = m.row_pair_mut(row, i);
+                let factor = values[i].clone();
+                for (value, p) in values.iter_mut().zip(pivot_row.iter()).skip(i + 1) {
+                    value.sub_mul_assign_frac(&factor, p);
+                }
             }
         }
+
+        Ok(LuDecomposition { lu: m, permutation })
+    }
+
+    pub fn size(&self) -> usize {
+        self.permutation.len()
     }
 
-    // Inverse (forward substitution)
-    for dcol in 0..size {
+    /// `x` with `A x = b`.
+    pub fn solve(&self, b: &BigVector) -> BigVector {
+        let size = self.size();
+        assert_eq!(b.dimension(), size);
+        let mut x: Vec<BigFraction> = self.permutation.iter().map(|&row| b.get(row).clone()).collect();
+
+        // Forward substitution with L
         for row in 0..size {
-            for col in 0..row {
-                let val = inv.get(row, dcol).sub_frac(&m.get(row, col).mul_frac(inv.get(col, dcol)));
-                inv.set(row, dcol, val);
+            let (done, rest) = x.split_at_mut(row);
+            for (col, value) in done.iter().enumerate() {
+                rest[0].sub_mul_assign_frac(self.lu.get(row, col), value);
             }
         }
-    }
 
-    // Inverse (back substitution)
-    for dcol in 0..size {
+        // Back substitution with U
         for row in (0..size).rev() {
-            for col in ((row + 1)..size).rev() {
-                let val = inv.get(row, dcol).sub_frac(&m.get(row, col).mul_frac(inv.get(col, dcol)));
-                inv.set(row, dcol, val);
+            let (head, done) = x.split_at_mut(row + 1);
+            for (col, value) in done.iter().enumerate() {
+                head[row].sub_mul_assign_frac(self.lu.get(row, row + 1 + col), value);
             }
-            let val = inv.get(row, dcol).div_frac(m.get(row, row));
-            inv.set(row, dcol, val);
+            head[row] = head[row].div_frac(self.lu.get(row, row));
+        }
+
+        BigVector::from_data(x)
+    }
+
+    /// `X` with `A X = B`, solved column by column.
+    pub fn solve_matrix(&self, b: &BigMatrix) -> BigMatrix {
+        assert_eq!(b.row_count(), self.size());
+        let mut x = BigMatrix::new(b.row_count(), b.col_count());
+        for col in 0..b.col_count() {
+            x.set_col(col, &self.solve(&b.get_col(col)));
         }
+        x
+    }
+
+    /// `A^-1`.
+    pub fn inverse(&self) -> BigMatrix {
+        self.solve_matrix(&BigMatrix::identity(self.size()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vector(values: &[(i64, i64)]) -> BigVector {
+        BigVector::from_data(values.iter().map(|&(n, d)| BigFraction::frac_new(n, d)).collect())
+    }
+
+    fn matrix(rows: &[&[(i64, i64)]]) -> BigMatrix {
+        BigMatrix::from_rows(&rows.iter().map(|row| vector(row)).collect::<Vec<_>>())
     }
 
-    inv
+    /// Needs a row swap at the first pivot, and has fractional entries.
+    fn example() -> BigMatrix {
+        matrix(&[
+            &[(0, 1), (2, 1), (1, 3)],
+            &[(1, 2), (-1, 1), (4, 1)],
+            &[(3, 1), (5, 7), (-2, 1)],
+        ])
+    }
+
+    #[test]
+    fn test_solve() {
+        let a = example();
+        let lu = LuDecomposition::new(&a).unwrap();
+        for b in [vector(&[(1, 1), (0, 1), (0, 1)]), vector(&[(7, 1), (-3, 5), (11, 2)])] {
+            assert_eq!(&a * &lu.solve(&b), b);
+        }
+    }
+
+    #[test]
+    fn test_inverse() {
+        let a = example();
+        let inv = inverse(&a).unwrap();
+        let identity = BigMatrix::identity(3);
+        for product in [&a * &inv, &inv * &a] {
+            assert!(product.rows().eq(identity.rows()), "{}", product);
+        }
+    }
+
+    #[test]
+    fn test_singular() {
+        let singular = matrix(&[
+            &[(1, 1), (2, 1), (3, 1)],
+            &[(1, 2), (1, 1), (3, 2)],
+            &[(0, 1), (4, 1), (-1, 1)],
+        ]);
+        assert!(matches!(LuDecomposition::new(&singular), Err(MathError::SingularMatrix)));
+        assert!(matches!(inverse(&BigMatrix::new(2, 2)), Err(MathError::SingularMatrix)));
+    }
 }
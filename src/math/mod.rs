@@ -1,8 +1,12 @@
+pub mod error;
 pub mod int_type;
 pub mod big_fraction;
 pub mod big_vector;
 pub mod big_matrix;
+pub mod mod_vector;
+pub mod mod_matrix;
 pub mod gauss_jordan;
+pub mod smith;
 pub mod lu_decomposition;
 pub mod optimize;
 pub mod mth;
@@ -0,0 +1,180 @@
+use super::big_fraction::{BigFraction, FracOps};
+use super::big_matrix::BigMatrix;
+use super::mod_vector::{self, ModVector, MASK};
+
+/// A matrix of integers modulo `2^48` stored in row-major order, each kept
+/// in `[0, 2^48)`. See [`ModVector`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ModMatrix {
+    data: Vec<u64>,
+    rows: usize,
+    cols: usize,
+}
+
+impl ModMatrix {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        ModMatrix { data: vec![0; rows * cols], rows, cols }
+    }
+
+    pub fn identity(size: usize) -> Self {
+        let mut m = ModMatrix::new(size, size);
+        for i in 0..size {
+            m.set(i, i, 1);
+        }
+        m
+    }
+
+    /// The integer matrix `m` reduced modulo `2^48`, or `None` if an entry
+    /// is not an integer.
+    pub fn from_big_matrix(m: &BigMatrix) -> Option<Self> {
+        let data = m.iter().map(mod_vector::reduce).collect::<Option<_>>()?;
+        Some(ModMatrix { data, rows: m.row_count(), cols: m.col_count() })
+    }
+
+    /// The entries as integers in `[0, 2^48)`.
+    pub fn to_big_matrix(&self) -> BigMatrix {
+        let mut m = BigMatrix::new(self.rows, self.cols);
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                m.set(row, col, BigFraction::frac_from_i64(self.get(row, col) as i64));
+            }
+        }
+        m
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows
+    }
+
+    pub fn col_count(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> u64 {
+        self.data[row * self.cols + col]
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: u64) {
+        self.data[row * self.cols + col] = value & MASK;
+    }
+
+    pub fn row(&self, row: usize) -> &[u64] {
+        &self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn get_row(&self, row: usize) -> ModVector {
+        ModVector::from_data(self.row(row).to_vec())
+    }
+
+    pub fn get_col(&self, col: usize) -> ModVector {
+        ModVector::from_data((0..self.rows).map(|row| self.get(row, col)).collect())
+    }
+
+    pub fn transpose(&self) -> ModMatrix {
+        let mut t = ModMatrix::new(self.cols, self.rows);
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                t.data[col * self.rows + row] = self.get(row, col);
+            }
+        }
+        t
+    }
+
+    pub fn multiply_matrix(&self, other: &ModMatrix) -> ModMatrix {
+        assert_eq!(self.cols, other.rows);
+        let other_t = other.transpose();
+        let mut result = ModMatrix::new(self.rows, other.cols);
+        for row in 0..self.rows {
+            for col in 0..other.cols {
+                result.data[row * other.cols + col] = mod_vector::dot(self.row(row), other_t.row(col));
+            }
+        }
+        result
+    }
+
+    pub fn multiply_vector(&self, v: &ModVector) -> ModVector {
+        assert_eq!(self.cols, v.dimension());
+        ModVector::from_data((0..self.rows).map(|row| mod_vector::dot(self.row(row), v.as_slice())).collect())
+    }
+}
+
+impl std::ops::Mul<&ModMatrix> for &ModMatrix {
+    type Output = ModMatrix;
+
+    fn mul(self, rhs: &ModMatrix) -> ModMatrix {
+        self.multiply_matrix(rhs)
+    }
+}
+
+impl std::ops::Mul<&ModVector> for &ModMatrix {
+    type Output = ModVector;
+
+    fn mul(self, rhs: &ModVector) -> ModVector {
+        self.multiply_vector(rhs)
+    }
+}
+
+impl std::fmt::Display for ModMatrix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{")?;
+        for r in 0..self.rows {
+            if r > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", self.get_row(r))?;
+        }
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::mth;
+
+    const MULTIPLIER: u64 = 0x5DEECE66D;
+    const ADDEND: u64 = 0xB;
+
+    /// One step of `java.util.Random` acting on `(seed, 1)`.
+    fn step() -> ModMatrix {
+        let mut m = ModMatrix::identity(2);
+        m.set(0, 0, MULTIPLIER);
+        m.set(0, 1, ADDEND);
+        m
+    }
+
+    #[test]
+    fn test_products() {
+        let seed = 0x1234_5678_9ABC;
+        let two = &step() * &step();
+        let state = ModVector::from_data(vec![seed, 1]);
+        let next = |x: u64| x.wrapping_mul(MULTIPLIER).wrapping_add(ADDEND) & MASK;
+        assert_eq!((&two * &state).as_slice(), [next(next(seed)), 1]);
+        assert_eq!(&step() * &(&step() * &state), &two * &state);
+        assert_eq!(two.transpose().transpose(), two);
+        assert_eq!(two.transpose().get(1, 0), two.get(0, 1));
+    }
+
+    #[test]
+    fn test_inverse_step() {
+        let inverse_multiplier = mth::mod_inverse(MULTIPLIER as i64, 48) as u64;
+        let mut back = ModMatrix::identity(2);
+        back.set(0, 0, inverse_multiplier);
+        back.set(0, 1, inverse_multiplier.wrapping_mul(ADDEND).wrapping_neg());
+        assert_eq!(&step() * &back, ModMatrix::identity(2));
+        assert_eq!(&back * &step(), ModMatrix::identity(2));
+    }
+
+    #[test]
+    fn test_big_matrix_conversion() {
+        let mut big = BigMatrix::new(2, 3);
+        big.set(0, 2, BigFraction::frac_from_i64(-3));
+        big.set(1, 0, BigFraction::frac_from_i64(1 << 50));
+        let m = ModMatrix::from_big_matrix(&big).unwrap();
+        assert_eq!((m.row(0), m.row(1)), (&[0, 0, MASK - 2][..], &[0, 0, 0][..]));
+        assert_eq!(ModMatrix::from_big_matrix(&m.to_big_matrix()), Some(m));
+
+        big.set(1, 1, BigFraction::frac_new(3, 4));
+        assert_eq!(ModMatrix::from_big_matrix(&big), None);
+    }
+}
@@ -0,0 +1,140 @@
+use super::big_fraction::{BigFraction, FracOps};
+use super::big_vector::BigVector;
+use super::int_type::{Int, IntOps};
+use std::fmt;
+
+/// Bits of the modulus: the state of `java.util.Random`.
+pub const MODULUS_BITS: u32 = 48;
+/// `2^48 - 1`; masking with it reduces modulo `2^48`.
+pub const MASK: u64 = (1 << MODULUS_BITS) - 1;
+
+/// A vector of integers modulo `2^48`, each kept in `[0, 2^48)`.
+///
+/// `2^48` divides `2^64`, so wrapping `u64` arithmetic followed by a mask is
+/// exact, with no fractions or big integers involved.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ModVector {
+    data: Vec<u64>,
+}
+
+impl ModVector {
+    pub fn new(dimension: usize) -> Self {
+        ModVector { data: vec![0; dimension] }
+    }
+
+    /// The vector with entries `data`, reduced.
+    pub fn from_data(data: Vec<u64>) -> Self {
+        ModVector { data: data.into_iter().map(|x| x & MASK).collect() }
+    }
+
+    /// The integer vector `v` reduced modulo `2^48`, or `None` if an entry
+    /// is not an integer.
+    pub fn from_big_vector(v: &BigVector) -> Option<Self> {
+        let data = (0..v.dimension()).map(|i| reduce(v.get(i))).collect::<Option<_>>()?;
+        Some(ModVector { data })
+    }
+
+    /// The entries as integers in `[0, 2^48)`.
+    pub fn to_big_vector(&self) -> BigVector {
+        BigVector::from_data(self.data.iter().map(|&x| BigFraction::frac_from_i64(x as i64)).collect())
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn get(&self, i: usize) -> u64 {
+        self.data[i]
+    }
+
+    pub fn set(&mut self, i: usize, value: u64) {
+        self.data[i] = value & MASK;
+    }
+
+    pub fn as_slice(&self) -> &[u64] {
+        &self.data
+    }
+
+    pub fn add(&self, other: &ModVector) -> ModVector {
+        assert_eq!(self.dimension(), other.dimension());
+        ModVector { data: self.data.iter().zip(&other.data).map(|(a, b)| a.wrapping_add(*b) & MASK).collect() }
+    }
+
+    pub fn subtract(&self, other: &ModVector) -> ModVector {
+        assert_eq!(self.dimension(), other.dimension());
+        ModVector { data: self.data.iter().zip(&other.data).map(|(a, b)| a.wrapping_sub(*b) & MASK).collect() }
+    }
+
+    pub fn multiply_scalar(&self, scalar: u64) -> ModVector {
+        ModVector { data: self.data.iter().map(|x| x.wrapping_mul(scalar) & MASK).collect() }
+    }
+
+    pub fn dot(&self, other: &ModVector) -> u64 {
+        assert_eq!(self.dimension(), other.dimension());
+        dot(&self.data, &other.data)
+    }
+}
+
+impl fmt::Display for ModVector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        for (i, x) in self.data.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", x)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// `a . b` modulo `2^48`.
+pub(super) fn dot(a: &[u64], b: &[u64]) -> u64 {
+    a.iter().zip(b).fold(0u64, |sum, (x, y)| sum.wrapping_add(x.wrapping_mul(*y))) & MASK
+}
+
+/// The integer `x` modulo `2^48`, or `None` if `x` is not an integer.
+pub(super) fn reduce(x: &BigFraction) -> Option<u64> {
+    if !x.denominator_int().int_is_one() {
+        return None;
+    }
+    let modulus = Int::int_from_i64(1 << MODULUS_BITS);
+    let mut value = x.numerator_int().int_rem(&modulus);
+    if value.int_is_negative() {
+        value = value.int_add(&modulus);
+    }
+    Some(value.int_to_i64() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic_wraps() {
+        let a = ModVector::from_data(vec![MASK, 5, 1 << 47]);
+        let b = ModVector::from_data(vec![1, 7, 1 << 47]);
+        assert_eq!(a.add(&b).as_slice(), [0, 12, 0]);
+        assert_eq!(b.subtract(&a).as_slice(), [2, 2, 0]);
+        assert_eq!(a.add(&b).subtract(&b), a);
+        assert_eq!(a.multiply_scalar(2).as_slice(), [MASK - 1, 10, 0]);
+        // MASK * 1 + 5 * 7 + 2^94
+        assert_eq!(a.dot(&b), 34);
+        assert_eq!(ModVector::from_data(vec![u64::MAX]).as_slice(), [MASK]);
+    }
+
+    #[test]
+    fn test_big_vector_conversion() {
+        let v = BigVector::from_data(vec![
+            BigFraction::frac_from_i64(-1),
+            BigFraction::frac_from_i64(3 << 48 | 9),
+            BigFraction::frac_from_i64(42),
+        ]);
+        let reduced = ModVector::from_big_vector(&v).unwrap();
+        assert_eq!(reduced.as_slice(), [MASK, 9, 42]);
+        assert_eq!(ModVector::from_big_vector(&reduced.to_big_vector()), Some(reduced));
+
+        let fraction = BigVector::from_data(vec![BigFraction::frac_from_i64(1), BigFraction::frac_new(1, 2)]);
+        assert_eq!(ModVector::from_big_vector(&fraction), None);
+    }
+}
@@ -25,7 +25,25 @@ pub fn mask_signed(value: i64, bits: u32) -> i64 {
     (value << (64 - bits)) >> (64 - bits)
 }
 
-/// Modular inverse mod 2^bits using Newton's method
+/// `floor(x / y)`, like Java's `Math.floorDiv`; `/` rounds toward zero.
+pub fn floor_div(x: i64, y: i64) -> i64 {
+    let r = x / y;
+    // If the signs are different and there's a remainder, subtract 1
+    if (x ^ y) < 0 && (r * y != x) {
+        r - 1
+    } else {
+        r
+    }
+}
+
+/// `x - y * floor_div(x, y)`, which takes the sign of `y`, like Java's
+/// `Math.floorMod`; `%` takes the sign of `x`.
+pub fn floor_mod(x: i64, y: i64) -> i64 {
+    x - y * floor_div(x, y)
+}
+
+/// Modular inverse mod 2^bits using Newton's method. `value` must be odd;
+/// see [`mod_inverse_pow2`] for the checked version.
 pub fn mod_inverse(value: i64, bits: u32) -> i64 {
     let mut x = ((((value << 1) ^ value) & 4) << 1) ^ value;
     x = x.wrapping_mul(2i64.wrapping_sub(value.wrapping_mul(x)));
@@ -35,22 +53,68 @@ pub fn mod_inverse(value: i64, bits: u32) -> i64 {
     mask(x, bits)
 }
 
-/// Modular inverse mod 2^16 (simpler version used in PopulationReverser)
-pub fn mod_inverse_16(x: i64) -> i64 {
-    if (x & 1) == 0 {
-        panic!("x is not coprime with the modulus");
+/// Modular inverse mod 2^bits, or `None` if `value` is even and has none.
+pub fn mod_inverse_pow2(value: i64, bits: u32) -> Option<i64> {
+    (value & 1 == 1).then(|| mod_inverse(value, bits))
+}
+
+/// Modular inverse mod 2^16, as used by the population reverser.
+pub fn mod_inverse_16(x: i64) -> Option<i64> {
+    mod_inverse_pow2(x, 16)
+}
+
+/// `a` modulo `m`, in `[0, |m|)` whatever the sign of `a`.
+pub fn mod_int(a: &Int, m: &Int) -> Int {
+    let r = a.int_rem(m);
+    if r.int_is_negative() {
+        r.int_add(&m.int_abs())
+    } else {
+        r
+    }
+}
+
+/// Modular inverse of `value` mod `modulus`, by the extended Euclidean
+/// algorithm, or `None` if they are not coprime.
+pub fn mod_inverse_int(value: &Int, modulus: &Int) -> Option<Int> {
+    let modulus = modulus.int_abs();
+    let (g, x, _) = extended_gcd(&mod_int(value, &modulus), &modulus);
+    g.int_is_one().then(|| mod_int(&x, &modulus))
+}
+
+/// `(g, x, y)` with `a * x + b * y == g == gcd(a, b)`, for non-negative `a`
+/// and `b`.
+pub fn extended_gcd(a: &Int, b: &Int) -> (Int, Int, Int) {
+    let (mut r0, mut r1) = (a.clone(), b.clone());
+    let (mut x0, mut x1) = (Int::int_one(), Int::int_zero());
+    let (mut y0, mut y1) = (Int::int_zero(), Int::int_one());
+    while !r1.int_is_zero() {
+        let q = r0.int_div(&r1);
+        let r2 = r0.int_sub(&q.int_mul(&r1));
+        let x2 = x0.int_sub(&q.int_mul(&x1));
+        let y2 = y0.int_sub(&q.int_mul(&y1));
+        (r0, r1) = (r1, r2);
+        (x0, x1) = (x1, x2);
+        (y0, y1) = (y1, y2);
     }
-    let mut inv: i64 = 0;
-    let mut b: i64 = 1;
-    for i in 0..16 {
-        if (b & 1) == 1 {
-            inv |= 1i64 << i;
-            b = (b - x) >> 1;
-        } else {
-            b >>= 1;
-        }
+    (r0, x0, y0)
+}
+
+/// Chinese remainder combination of `x == r1 (mod m1)` and `x == r2 (mod m2)`
+/// into `x == r (mod lcm(m1, m2))`, returned as `(r, lcm)` with `r` reduced.
+/// The moduli need not be coprime; `None` if the congruences contradict.
+pub fn crt(r1: &Int, m1: &Int, r2: &Int, m2: &Int) -> Option<(Int, Int)> {
+    let (m1, m2) = (m1.int_abs(), m2.int_abs());
+    let (g, p, _) = extended_gcd(&m1, &m2);
+    let diff = r2.int_sub(r1);
+    if !diff.int_rem(&g).int_is_zero() {
+        return None;
     }
-    inv
+    // x = r1 + m1 * k with m1 * k == diff (mod m2), so k == (diff / g) * p
+    // (mod m2 / g), where p is the inverse of m1 / g mod m2 / g.
+    let step = m2.int_div(&g);
+    let k = mod_int(&diff.int_div(&g).int_mul(&p), &step);
+    let lcm = m1.int_mul(&step);
+    Some((mod_int(&r1.int_add(&m1.int_mul(&k)), &lcm), lcm))
 }
 
 pub fn lcm_int(a: &Int, b: &Int) -> Int {
@@ -66,3 +130,54 @@ pub fn lcm_int(a: &Int, b: &Int) -> Int {
 pub fn lcm_bigint(a: &Int, b: &Int) -> Int {
     lcm_int(a, b)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(value: i64) -> Int {
+        Int::int_from_i64(value)
+    }
+
+    #[test]
+    fn test_mod_inverse_pow2() {
+        assert_eq!(mod_inverse_pow2(3, 16).map(|x| mask(3 * x, 16)), Some(1));
+        assert_eq!(mod_inverse_pow2(-5, 48).map(|x| mask(-5i64.wrapping_mul(x), 48)), Some(1));
+        assert_eq!(mod_inverse_pow2(0x5DEECE66D, 48), Some(0xDFE05BCB1365));
+        assert_eq!(mod_inverse_pow2(6, 16), None);
+        assert_eq!(mod_inverse_16(0), None);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        let (g, x, y) = extended_gcd(&int(240), &int(46));
+        assert_eq!(g, int(2));
+        assert_eq!(int(240).int_mul(&x).int_add(&int(46).int_mul(&y)), g);
+        assert_eq!(extended_gcd(&int(0), &int(7)).0, int(7));
+    }
+
+    #[test]
+    fn test_mod_inverse_int() {
+        assert_eq!(mod_inverse_int(&int(-3), &int(7)), Some(int(2)));
+        assert_eq!(mod_inverse_int(&int(3), &int(-7)), Some(int(5)));
+        assert_eq!(mod_inverse_int(&int(4), &int(6)), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&int(2), &int(3), &int(3), &int(5)), Some((int(8), int(15))));
+        assert_eq!(crt(&int(-1), &int(3), &int(-2), &int(5)), Some((int(8), int(15))));
+        // Moduli sharing a factor combine when the residues agree on it.
+        assert_eq!(crt(&int(3), &int(4), &int(5), &int(6)), Some((int(11), int(12))));
+        assert_eq!(crt(&int(1), &int(4), &int(2), &int(6)), None);
+    }
+
+    #[test]
+    fn test_floor_div_mod() {
+        assert_eq!((floor_div(7, 2), floor_mod(7, 2)), (3, 1));
+        assert_eq!((floor_div(-7, 2), floor_mod(-7, 2)), (-4, 1));
+        assert_eq!((floor_div(7, -2), floor_mod(7, -2)), (-4, -1));
+        assert_eq!((floor_div(-7, -2), floor_mod(-7, -2)), (3, -1));
+        assert_eq!((floor_div(-6, 2), floor_mod(-6, 2)), (-3, 0));
+    }
+}
@@ -2,28 +2,76 @@ use super::big_fraction::{BigFraction, FracOps};
 use super::big_matrix::BigMatrix;
 use super::big_vector::BigVector;
 use super::gauss_jordan;
+use super::int_type::{Int, IntOps};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Simplex steps a single solve may take before giving up with
+/// [`SolveError::IterationLimit`].
+const MAX_ITERATIONS: u64 = 1_000_000;
+
+/// Counts from one simplex solve.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    /// Steps taken, the last of which found the objective optimal.
+    pub iterations: u64,
+    /// Pivots made.
+    pub pivots: u64,
+    /// Pivots on a row with a zero right-hand side, which leave the
+    /// objective's value where it was.
+    pub degenerate: u64,
+}
+
+/// An optimum of [`Optimize::try_minimize`] or [`Optimize::try_maximize`].
+#[derive(Clone, Debug)]
+pub struct Solution {
+    pub point: BigVector,
+    pub value: BigFraction,
+    pub stats: SolveStats,
+}
+
+/// Why a solve stopped without an optimum.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolveError {
+    /// The objective decreases without bound.
+    Unbounded,
+    /// [`MAX_ITERATIONS`] steps went by without an optimum, so the pivots
+    /// are most likely cycling.
+    IterationLimit,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::Unbounded => write!(f, "Unbounded LP"),
+            SolveError::IterationLimit => write!(f, "Simplex hit {} iterations, likely cycling", MAX_ITERATIONS),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
 
 /// Linear programming optimizer using the simplex method over BigFractions.
 /// This is a faithful port of the Java Optimize class from LattiCG.
+///
+/// Problems whose table starts out integral, such as a box with integer
+/// bounds, are solved in integers instead; see [`Tableau::Integral`].
 #[derive(Clone)]
 pub struct Optimize {
-    table: BigMatrix,
+    tableau: Tableau,
     basics: Vec<usize>,
     nonbasics: Vec<usize>,
-    transform: BigMatrix,
     rows: usize,
     cols: usize,
 }
 
 impl Optimize {
-    fn new(table: BigMatrix, basics: Vec<usize>, nonbasics: Vec<usize>, transform: BigMatrix) -> Self {
-        let rows = table.row_count();
-        let cols = table.col_count();
+    fn new(tableau: Tableau, basics: Vec<usize>, nonbasics: Vec<usize>) -> Self {
+        let (rows, cols) = tableau.dimensions();
         Optimize {
-            table,
+            tableau,
             basics,
             nonbasics,
-            transform,
             rows,
             cols,
         }
@@ -33,178 +81,78 @@ impl Optimize {
         (self.rows, self.cols)
     }
 
-    fn transform_for_table(&self, lhs: &BigVector, rhs: &BigFraction) -> BigVector {
-        let tcols = self.transform.col_count();
-        let mut transformed = BigVector::new(tcols);
-        transformed.set(tcols - 1, rhs.clone());
-
-        for row in 0..self.transform.row_count() {
-            let x = lhs.get(row).clone();
-            let tr_row = self.transform.get_row(row);
-            let scaled = tr_row.multiply_scalar(&x);
-            transformed.subtract_assign(&scaled);
-        }
-
-        let mut eliminated = BigVector::new(self.cols);
-        for col in 0..(self.cols - 1) {
-            eliminated.set(col, transformed.get(self.nonbasics[col]).clone());
-        }
-        eliminated.set(self.cols - 1, transformed.get(tcols - 1).clone());
-
-        for row in 0..(self.rows - 1) {
-            let x = transformed.get(self.basics[row]).clone();
-            let t_row = self.table.get_row(row);
-            let scaled = t_row.multiply_scalar(&x);
-            eliminated.subtract_assign(&scaled);
-        }
+    /// Panics if the problem is unbounded or the solve cycles; see
+    /// [`try_maximize`](Self::try_maximize).
+    pub fn maximize(&mut self, gradient: &BigVector) -> (BigVector, BigFraction) {
+        let solution = self.try_maximize(gradient).unwrap_or_else(|e| panic!("{}", e));
+        (solution.point, solution.value)
+    }
 
-        eliminated
+    /// Panics if the problem is unbounded or the solve cycles; see
+    /// [`try_minimize`](Self::try_minimize).
+    pub fn minimize(&mut self, gradient: &BigVector) -> (BigVector, BigFraction) {
+        let solution = self.try_minimize(gradient).unwrap_or_else(|e| panic!("{}", e));
+        (solution.point, solution.value)
     }
 
-    pub fn maximize(&mut self, gradient: &BigVector) -> (BigVector, BigFraction) {
+    pub fn try_maximize(&mut self, gradient: &BigVector) -> Result<Solution, SolveError> {
         let neg = BigVector::from_data(
             (0..gradient.dimension())
                 .map(|i| gradient.get(i).negate())
                 .collect(),
         );
-        let (result, val) = self.minimize(&neg);
-        (result, val.negate())
+        let mut solution = self.try_minimize(&neg)?;
+        solution.value = solution.value.negate();
+        Ok(solution)
     }
 
-    pub fn minimize(&mut self, gradient: &BigVector) -> (BigVector, BigFraction) {
-        assert_eq!(gradient.dimension(), self.transform.row_count());
-
-        // Set objective row
-        let obj_row = BigVector::new(self.cols);
-        self.table.set_row(self.rows - 1, &obj_row);
-
-        let neg_transformed = self.transform_for_table(gradient, &BigFraction::frac_zero());
-        // Subtract from objective row
-        for c in 0..self.cols {
-            let val = self.table.get(self.rows - 1, c).sub_frac(neg_transformed.get(c));
-            self.table.set(self.rows - 1, c, val);
-        }
-
-        self.solve();
-
-        let tcols = self.transform.col_count();
-        let mut result = self.transform.get_col(tcols - 1);
+    pub fn try_minimize(&mut self, gradient: &BigVector) -> Result<Solution, SolveError> {
+        assert_eq!(gradient.dimension(), self.tableau.size());
 
-        for row in 0..(self.rows - 1) {
-            let v0 = self.basics[row];
-            let scale = self.table.get(row, self.cols - 1);
-            let col_vec = self.transform.get_col(v0);
-            let scaled = col_vec.multiply_scalar(scale);
-            result.subtract_assign(&scaled);
-        }
+        let scale = self.tableau.set_objective(&self.basics, &self.nonbasics, gradient);
+        let stats = self.solve()?;
 
-        let obj_val = self.table.get(self.rows - 1, self.cols - 1).clone();
-        (result, obj_val)
+        let point = self.tableau.point(&self.basics);
+        let value = self.tableau.value(self.rows - 1, self.cols - 1).div_int(&scale);
+        Ok(Solution { point, value, stats })
     }
 
-    fn solve(&mut self) {
-        let mut iters = 0u64;
-        while self.step() {
-            iters += 1;
-            if iters % 10000 == 0 {
-                verbose_eprintln!("[simplex]     solve iteration {}, table {}x{}", iters, self.rows, self.cols);
+    fn solve(&mut self) -> Result<SolveStats, SolveError> {
+        let mut stats = SolveStats::default();
+        loop {
+            if stats.iterations == MAX_ITERATIONS {
+                return Err(SolveError::IterationLimit);
             }
-            if iters > 1_000_000 {
-                verbose_eprintln!("[simplex]     WARNING: over 1M iterations, likely cycling. Aborting.");
-                break;
+            stats.iterations += 1;
+            if stats.iterations % 10000 == 0 {
+                let iterations = stats.iterations;
+                verbose_eprintln!("[simplex]     solve iteration {}, table {}x{}", iterations, self.rows, self.cols);
             }
-        }
-    }
-
-    fn step(&mut self) -> bool {
-        let mut bland = false;
-
-        for row in 0..(self.rows - 1) {
-            if self.table.get(row, self.cols - 1).signum() == 0 {
-                bland = true;
-                break;
+            if !self.step(&mut stats)? {
+                return Ok(stats);
             }
         }
+    }
 
-        let mut entering: Option<usize> = None;
-        let mut candidate = BigFraction::frac_zero();
+    fn step(&mut self, stats: &mut SolveStats) -> Result<bool, SolveError> {
+        let bland = (0..(self.rows - 1)).any(|row| self.tableau.sign(row, self.cols - 1) == 0);
 
-        for col in 0..(self.cols - 1) {
-            let x = self.table.get(self.rows - 1, col);
-            if x.signum() <= 0 {
-                continue;
-            }
-            if entering.is_some() && *x <= candidate {
-                continue;
-            }
-            entering = Some(col);
-            candidate = x.clone();
-            if bland {
-                break;
-            }
-        }
-
-        let entering = match entering {
+        let entering = match self.tableau.entering(bland) {
             Some(e) => e,
-            None => return false,
+            None => return Ok(false),
         };
 
-        let mut exiting: Option<usize> = None;
-        candidate = BigFraction::frac_zero(); // reuse for ratio test
-
-        for row in 0..(self.rows - 1) {
-            let x = self.table.get(row, entering);
-            if x.signum() <= 0 {
-                continue;
-            }
-            let y = self.table.get(row, self.cols - 1).div_frac(x);
-            if exiting.is_some() && y >= candidate {
-                continue;
-            }
-            exiting = Some(row);
-            candidate = y;
+        let exiting = self.tableau.exiting(entering).ok_or(SolveError::Unbounded)?;
+        stats.pivots += 1;
+        if self.tableau.sign(exiting, self.cols - 1) == 0 {
+            stats.degenerate += 1;
         }
-
-        let exiting = exiting.expect("Unbounded LP");
         self.pivot(entering, exiting);
-        true
+        Ok(true)
     }
 
     fn pivot(&mut self, entering: usize, exiting: usize) {
-        let rows = self.rows;
-        let cols = self.cols;
-
-        let pivot = self.table.get(exiting, entering).clone();
-
-        // Scale pivot row
-        for col in 0..cols {
-            if col == entering {
-                continue;
-            }
-            let val = self.table.get(exiting, col).div_frac(&pivot);
-            self.table.set(exiting, col, val);
-        }
-
-        // Eliminate entering column from other rows
-        for row in 0..rows {
-            if row == exiting {
-                continue;
-            }
-            let x = self.table.get(row, entering).clone();
-            for col in 0..cols {
-                if col == entering {
-                    continue;
-                }
-                let y = self.table.get(exiting, col);
-                let val = self.table.get(row, col).sub_frac(&x.mul_frac(y));
-                self.table.set(row, col, val);
-            }
-            let val = x.div_frac(&pivot).negate();
-            self.table.set(row, entering, val);
-        }
-
-        let recip = pivot.reciprocal();
-        self.table.set(exiting, entering, recip);
+        self.tableau.pivot(entering, exiting);
 
         // Swap basic/nonbasic
         let tmp = self.nonbasics[entering];
@@ -212,69 +160,126 @@ impl Optimize {
         self.basics[exiting] = tmp;
     }
 
+    /// The same problem with the equality `lhs . x == rhs` added.
+    ///
+    /// This starts from this table's basis instead of solving phase 1 again:
+    /// the new row's artificial variable is pivoted out at once, and the dual
+    /// simplex repairs the right-hand sides that pivot left negative. The
+    /// objective row is cleared, so every basis is dual feasible and the
+    /// repair only looks for a feasible one.
+    ///
+    /// Panics if no point satisfies the equality or the repair cycles; see
+    /// [`try_with_strict_bound`](Self::try_with_strict_bound).
     pub fn with_strict_bound(&self, lhs: &BigVector, rhs: &BigFraction) -> Optimize {
-        let mut new_table = BigMatrix::new(self.rows + 1, self.cols);
-
-        for row in 0..(self.rows - 1) {
-            for col in 0..self.cols {
-                new_table.set(row, col, self.table.get(row, col).clone());
-            }
-        }
-
-        let bound_row = self.transform_for_table(lhs, rhs);
-        for col in 0..self.cols {
-            new_table.set(self.rows - 1, col, bound_row.get(col).clone());
-        }
+        self.try_with_strict_bound(lhs, rhs)
+            .unwrap_or_else(|e| panic!("{}", e))
+            .expect("Table has no basic feasible solutions")
+    }
 
-        if new_table.get(self.rows - 1, self.cols - 1).signum() < 0 {
-            new_table.row_multiply(self.rows - 1, &BigFraction::frac_minus_one());
-        }
+    /// [`with_strict_bound`](Self::with_strict_bound), or `None` if no point
+    /// of this problem satisfies the equality. Fails if the repair cycles.
+    pub fn try_with_strict_bound(&self, lhs: &BigVector, rhs: &BigFraction) -> Result<Option<Optimize>, SolveError> {
+        let tableau = self.tableau.with_bound(&self.basics, &self.nonbasics, lhs, rhs);
+
+        let mut basics = self.basics.clone();
+        basics.push((self.rows - 1) + (self.cols - 1));
+        let mut opt = Optimize::new(tableau, basics, self.nonbasics.clone());
+
+        // The new row, and the column its artificial variable leaves on:
+        // preferably one the row wins the ratio test for, since that pivot
+        // keeps every right-hand side non-negative.
+        let bound = opt.rows - 2;
+        let candidates = || (0..opt.cols - 1).filter(|&col| opt.tableau.sign(bound, col) != 0);
+        let entering = candidates()
+            .find(|&col| opt.tableau.sign(bound, col) > 0 && opt.tableau.exiting(col) == Some(bound))
+            .or_else(|| candidates().min_by_key(|&col| opt.nonbasics[col]));
+
+        let Some(entering) = entering else {
+            // `lhs . x` is constant on this problem, so the equality either
+            // holds everywhere or nowhere.
+            return Ok((opt.tableau.sign(bound, opt.cols - 1) == 0).then(|| self.clone()));
+        };
+        opt.pivot(entering, bound);
 
-        let mut new_basics = self.basics.clone();
-        new_basics.push((self.rows - 1) + (self.cols - 1));
+        // The artificial variable is now nonbasic in `entering`, and fixed
+        // at zero by dropping its column.
+        let kept: Vec<usize> = (0..opt.cols - 1).filter(|&col| col != entering).collect();
+        let nonbasics = kept.iter().map(|&col| opt.nonbasics[col]).collect();
+        let tableau = opt.tableau.with_columns(&kept, opt.cols - 1);
+        let mut opt = Optimize::new(tableau, opt.basics, nonbasics);
 
-        let new_nonbasics = self.nonbasics.clone();
+        Ok(opt.repair()?.then_some(opt))
+    }
 
-        Optimize::from_table(new_table, new_basics, new_nonbasics, 1, &self.transform)
+    /// Pivot by the dual simplex until every right-hand side is
+    /// non-negative. Returns `false` if a row shows that none can be.
+    ///
+    /// With the objective row cleared every ratio ties, so the pivots are
+    /// picked by size instead: the most negative right-hand side leaves, on
+    /// its most negative entry. Once the pivots outnumber the rows, Bland's
+    /// rule takes over (the smallest basic variable leaves and the smallest
+    /// nonbasic one enters), which cannot cycle.
+    fn repair(&mut self) -> Result<bool, SolveError> {
+        let rhs = self.cols - 1;
+        for pivots in 0..MAX_ITERATIONS {
+            let bland = pivots >= self.rows as u64;
+            let infeasible = (0..(self.rows - 1)).filter(|&row| self.tableau.sign(row, rhs) < 0);
+            let exiting = if bland {
+                infeasible.min_by_key(|&row| self.basics[row])
+            } else {
+                infeasible.min_by(|&a, &b| self.tableau.compare((a, rhs), (b, rhs)))
+            };
+            let Some(exiting) = exiting else {
+                return Ok(true);
+            };
+
+            // The row's basic variable only grows with a negative entry.
+            let entering = (0..rhs).filter(|&col| self.tableau.sign(exiting, col) < 0);
+            let entering = if bland {
+                entering.min_by_key(|&col| self.nonbasics[col])
+            } else {
+                entering.min_by(|&a, &b| self.tableau.compare((exiting, a), (exiting, b)))
+            };
+            let Some(entering) = entering else {
+                return Ok(false);
+            };
+            self.pivot(entering, exiting);
+        }
+        Err(SolveError::IterationLimit)
     }
 
     fn from_table(
-        mut table: BigMatrix,
+        mut tableau: Tableau,
         basics: Vec<usize>,
         nonbasics: Vec<usize>,
         artificials: usize,
-        transform: &BigMatrix,
-    ) -> Optimize {
-        let rows = table.row_count();
-        let cols = table.col_count();
-
+    ) -> Result<Option<Optimize>, SolveError> {
+        let (rows, cols) = tableau.dimensions();
         let real_variables = (rows - 1) + (cols - 1) - artificials;
 
         // Phase 1: add artificial rows to objective
-        for basic_row in 0..(rows - 1) {
-            if basics[basic_row] < real_variables {
+        for (basic_row, &basic) in basics.iter().enumerate().take(rows - 1) {
+            if basic < real_variables {
                 continue;
             }
-            // Add row to objective row
-            for col in 0..cols {
-                let val = table.get(rows - 1, col).add_frac(table.get(basic_row, col));
-                table.set(rows - 1, col, val);
-            }
+            tableau.add_row(basic_row, rows - 1);
         }
 
-        let mut opt = Optimize::new(table, basics.clone(), nonbasics.clone(), BigMatrix::new(1, 1));
-        opt.solve();
+        // The phase 1 objective is bounded below by zero, so only the
+        // iteration limit can stop it.
+        let mut opt = Optimize::new(tableau, basics, nonbasics);
+        opt.solve()?;
 
         // Check feasibility
-        if opt.table.get(opt.rows - 1, opt.cols - 1).signum() != 0 {
-            panic!("Table has no basic feasible solutions");
+        if opt.tableau.sign(opt.rows - 1, opt.cols - 1) != 0 {
+            return Ok(None);
         }
 
         // Pivot out artificial variables
         for row in 0..(opt.rows - 1) {
             if opt.basics[row] >= real_variables {
                 for col in 0..(opt.cols - 1) {
-                    if opt.nonbasics[col] >= real_variables || opt.table.get(row, col).signum() == 0 {
+                    if opt.nonbasics[col] >= real_variables || opt.tableau.sign(row, col) == 0 {
                         continue;
                     }
                     opt.pivot(col, row);
@@ -285,35 +290,27 @@ impl Optimize {
 
         // Remove artificial columns
         let final_cols = cols - artificials;
-        let mut final_table = BigMatrix::new(rows, final_cols);
-
-        let mut c0 = 0usize;
-        let mut c1 = 0usize;
+        let mut kept = Vec::with_capacity(final_cols - 1);
         let mut final_nonbasics = vec![0usize; final_cols - 1];
 
-        while c0 < final_cols - 1 {
+        let mut c1 = 0usize;
+        while kept.len() < final_cols - 1 {
             while c1 < cols - 1 && opt.nonbasics[c1] >= real_variables {
                 c1 += 1;
             }
             if c1 >= cols - 1 {
                 break;
             }
-            for row in 0..(rows - 1) {
-                final_table.set(row, c0, opt.table.get(row, c1).clone());
-            }
-            final_nonbasics[c0] = opt.nonbasics[c1];
-            c0 += 1;
+            final_nonbasics[kept.len()] = opt.nonbasics[c1];
+            kept.push(c1);
             c1 += 1;
         }
 
-        for row in 0..(rows - 1) {
-            final_table.set(row, final_cols - 1, opt.table.get(row, cols - 1).clone());
-        }
-
-        Optimize::new(final_table, opt.basics.clone(), final_nonbasics, transform.clone())
+        let final_tableau = opt.tableau.with_columns(&kept, final_cols);
+        Ok(Some(Optimize::new(final_tableau, opt.basics, final_nonbasics)))
     }
 
-    fn from_inner_table(inner_table: &BigMatrix, transform: &BigMatrix) -> Optimize {
+    fn from_inner_table(inner_table: &BigMatrix, transform: &BigMatrix) -> Result<Option<Optimize>, SolveError> {
         let constraints = inner_table.row_count();
         let variables = inner_table.col_count() - 1;
 
@@ -367,11 +364,7 @@ impl Optimize {
                 }
                 let scale = inner.get(row, basics[basic_row]).clone();
                 if !scale.is_zero() {
-                    // row -= scale * basic_row
-                    for c in 0..inner.col_count() {
-                        let val = inner.get(row, c).sub_frac(&inner.get(basic_row, c).mul_frac(&scale));
-                        inner.set(row, c, val);
-                    }
+                    inner.row_subtract_scaled(row, basic_row, &scale);
                 }
             }
 
@@ -388,7 +381,495 @@ impl Optimize {
             final_nonbasics[i] = nonbasics[i];
         }
 
-        Optimize::from_table(table, basics, final_nonbasics, artificials, transform)
+        Optimize::from_table(Tableau::new(table, transform.clone()), basics, final_nonbasics, artificials)
+    }
+}
+
+/// The simplex table, with the transform from its variables back to `x`.
+///
+/// The table is dense on purpose. The bounds are unit vectors in `x`, but
+/// not after the transform to the slack variables: the reverser's tables
+/// are small and fill in as they are pivoted, so a revised simplex over
+/// sparse columns would mostly add the cost of solving against the basis at
+/// every step.
+#[derive(Clone)]
+enum Tableau {
+    Rational { table: BigMatrix, transform: BigMatrix },
+    /// The table is `table / denominator`, kept integral by fraction-free
+    /// (Edmonds) pivoting: every entry stays a minor of the starting table,
+    /// so each pivot divides exactly by the previous one, and no operation
+    /// pays for the gcd that keeps a fraction reduced. Gradients and bounds
+    /// are scaled to integers on the way in; a reduced basis is integral
+    /// after unscaling, so its inverse's rows share one small denominator.
+    Integral { table: Vec<Vec<Int>>, denominator: Int, transform: Vec<Vec<Int>> },
+}
+
+impl Tableau {
+    /// The integral form of the starting table if all its entries, and the
+    /// transform's, are integers; the rational one otherwise.
+    fn new(table: BigMatrix, transform: BigMatrix) -> Tableau {
+        match (integer_rows(&table), integer_rows(&transform)) {
+            (Some(table), Some(transform)) => Tableau::Integral { table, denominator: Int::int_one(), transform },
+            _ => Tableau::Rational { table, transform },
+        }
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        match self {
+            Tableau::Rational { table, .. } => (table.row_count(), table.col_count()),
+            Tableau::Integral { table, .. } => (table.len(), table[0].len()),
+        }
+    }
+
+    /// Dimension of `x`.
+    fn size(&self) -> usize {
+        match self {
+            Tableau::Rational { transform, .. } => transform.row_count(),
+            Tableau::Integral { transform, .. } => transform.len(),
+        }
+    }
+
+    fn sign(&self, row: usize, col: usize) -> i32 {
+        match self {
+            Tableau::Rational { table, .. } => table.get(row, col).signum(),
+            Tableau::Integral { table, .. } => int_sign(&table[row][col]),
+        }
+    }
+
+    /// Order of the entries at `a` and `b`, each a row and column.
+    fn compare(&self, a: (usize, usize), b: (usize, usize)) -> Ordering {
+        match self {
+            Tableau::Rational { table, .. } => table.get(a.0, a.1).cmp(table.get(b.0, b.1)),
+            Tableau::Integral { table, .. } => table[a.0][a.1].cmp(&table[b.0][b.1]),
+        }
+    }
+
+    fn value(&self, row: usize, col: usize) -> BigFraction {
+        match self {
+            Tableau::Rational { table, .. } => table.get(row, col).clone(),
+            Tableau::Integral { table, denominator, .. } => {
+                BigFraction::frac_from_int(table[row][col].clone()).div_int(denominator)
+            }
+        }
+    }
+
+    /// Set the objective row to minimize `gradient . x`. Returns the factor
+    /// the objective's value is scaled by.
+    fn set_objective(&mut self, basics: &[usize], nonbasics: &[usize], gradient: &BigVector) -> Int {
+        match self {
+            Tableau::Rational { table, transform } => {
+                let neg_transformed =
+                    rational_row(table, transform, basics, nonbasics, gradient, &BigFraction::frac_zero());
+                let obj = table.row_count() - 1;
+                for c in 0..table.col_count() {
+                    table.set(obj, c, neg_transformed.get(c).negate());
+                }
+                Int::int_one()
+            }
+            Tableau::Integral { table, denominator, transform } => {
+                let (lhs, rhs, scale) = scale_to_integers(gradient, &BigFraction::frac_zero());
+                let neg_transformed = integral_row(table, denominator, transform, basics, nonbasics, &lhs, &rhs);
+                let obj = table.len() - 1;
+                table[obj] = neg_transformed.iter().map(IntOps::int_neg).collect();
+                scale
+            }
+        }
+    }
+
+    /// The column to pivot on, if the objective can still improve.
+    fn entering(&self, bland: bool) -> Option<usize> {
+        match self {
+            Tableau::Rational { table, .. } => {
+                let obj = table.row_count() - 1;
+                entering_col((0..table.col_count() - 1).map(|col| table.get(obj, col)), &BigFraction::frac_zero(), bland)
+            }
+            Tableau::Integral { table, .. } => {
+                let obj = table.len() - 1;
+                let values = &table[obj];
+                entering_col(values[..values.len() - 1].iter(), &Int::int_zero(), bland)
+            }
+        }
+    }
+
+    /// The row leaving the basis when `entering` enters, by the ratio test.
+    fn exiting(&self, entering: usize) -> Option<usize> {
+        let mut exiting: Option<usize> = None;
+        match self {
+            Tableau::Rational { table, .. } => {
+                let rhs = table.col_count() - 1;
+                let mut candidate = BigFraction::frac_zero();
+                for row in 0..(table.row_count() - 1) {
+                    let x = table.get(row, entering);
+                    if x.signum() <= 0 {
+                        continue;
+                    }
+                    let y = table.get(row, rhs).div_frac(x);
+                    if exiting.is_some() && y >= candidate {
+                        continue;
+                    }
+                    exiting = Some(row);
+                    candidate = y;
+                }
+            }
+            Tableau::Integral { table, .. } => {
+                // Ratios `rhs / x` with `x > 0`, compared cross-multiplied.
+                let rhs = table[0].len() - 1;
+                for row in 0..(table.len() - 1) {
+                    let x = &table[row][entering];
+                    if !x.int_is_positive() {
+                        continue;
+                    }
+                    if let Some(best) = exiting {
+                        let y = table[row][rhs].int_mul(&table[best][entering]);
+                        if y >= table[best][rhs].int_mul(x) {
+                            continue;
+                        }
+                    }
+                    exiting = Some(row);
+                }
+            }
+        }
+        exiting
+    }
+
+    fn pivot(&mut self, entering: usize, exiting: usize) {
+        match self {
+            Tableau::Rational { table, .. } => {
+                let rows = table.row_count();
+
+                let pivot = table.get(exiting, entering).clone();
+                let recip = pivot.reciprocal().expect("Pivot entry is nonzero");
+
+                // Scale pivot row
+                for (col, value) in table.row_mut(exiting).iter_mut().enumerate() {
+                    if col != entering {
+                        value.mul_assign_frac(&recip);
+                    }
+                }
+
+                // Eliminate entering column from other rows
+                for row in 0..rows {
+                    if row == exiting || table.get(row, entering).is_zero() {
+                        continue;
+                    }
+                    let (values, pivot_row) = table.row_pair_mut(row, exiting);
+                    let x = values[entering].clone();
+                    for (col, (value, y)) in values.iter_mut().zip(pivot_row.iter()).enumerate() {
+                        if col != entering {
+                            value.sub_mul_assign_frac(&x, y);
+                        }
+                    }
+                    values[entering] = -x.mul_frac(&recip);
+                }
+
+                table.set(exiting, entering, recip);
+            }
+            Tableau::Integral { table, denominator, .. } => {
+                // With `p` the pivot entry and `d` the old denominator, the
+                // pivot row keeps its entries, the pivot becomes `d`, the
+                // pivot column is negated, and every other entry `a` becomes
+                // `(a p - a_col a_row) / d`, over the new denominator `p`.
+                let pivot_row = std::mem::take(&mut table[exiting]);
+                let pivot = pivot_row[entering].clone();
+
+                for (row, values) in table.iter_mut().enumerate() {
+                    if row == exiting {
+                        continue;
+                    }
+                    let x = values[entering].clone();
+                    for (col, (value, y)) in values.iter_mut().zip(&pivot_row).enumerate() {
+                        if col == entering {
+                            continue;
+                        }
+                        let scaled = value.int_mul(&pivot);
+                        let product = if x.int_is_zero() { scaled } else { scaled.int_sub(&x.int_mul(y)) };
+                        debug_assert!(product.int_rem(denominator).int_is_zero(), "inexact fraction-free pivot");
+                        *value = product.int_div(denominator);
+                    }
+                    values[entering] = x.int_neg();
+                }
+
+                table[exiting] = pivot_row;
+                table[exiting][entering] = denominator.clone();
+                *denominator = pivot;
+
+                // Keep the denominator positive, so signs read off directly.
+                if denominator.int_is_negative() {
+                    *denominator = denominator.int_neg();
+                    for value in table.iter_mut().flatten() {
+                        *value = value.int_neg();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Add row `source` to row `target`.
+    fn add_row(&mut self, source: usize, target: usize) {
+        match self {
+            Tableau::Rational { table, .. } => {
+                let (target, source) = table.row_pair_mut(target, source);
+                for (t, s) in target.iter_mut().zip(source.iter()) {
+                    t.add_assign_frac(s);
+                }
+            }
+            Tableau::Integral { table, .. } => {
+                for col in 0..table[target].len() {
+                    table[target][col] = table[target][col].int_add(&table[source][col]);
+                }
+            }
+        }
+    }
+
+    /// The table with the equality `lhs . x == rhs` as a new last
+    /// constraint row (right-hand side non-negative), and a cleared
+    /// objective row.
+    fn with_bound(&self, basics: &[usize], nonbasics: &[usize], lhs: &BigVector, rhs: &BigFraction) -> Tableau {
+        match self {
+            Tableau::Rational { table, transform } => {
+                let rows = table.row_count();
+                let cols = table.col_count();
+                let mut new_table = BigMatrix::new(rows + 1, cols);
+
+                for row in 0..(rows - 1) {
+                    for col in 0..cols {
+                        new_table.set(row, col, table.get(row, col).clone());
+                    }
+                }
+
+                let bound_row = rational_row(table, transform, basics, nonbasics, lhs, rhs);
+                for col in 0..cols {
+                    new_table.set(rows - 1, col, bound_row.get(col).clone());
+                }
+
+                if new_table.get(rows - 1, cols - 1).signum() < 0 {
+                    new_table.row_multiply(rows - 1, &BigFraction::frac_minus_one());
+                }
+
+                Tableau::Rational { table: new_table, transform: transform.clone() }
+            }
+            Tableau::Integral { table, denominator, transform } => {
+                let (lhs, rhs, _) = scale_to_integers(lhs, rhs);
+                let mut bound_row = integral_row(table, denominator, transform, basics, nonbasics, &lhs, &rhs);
+                if bound_row.last().is_some_and(IntOps::int_is_negative) {
+                    bound_row = bound_row.iter().map(IntOps::int_neg).collect();
+                }
+
+                let cols = bound_row.len();
+                let mut new_table = table[..table.len() - 1].to_vec();
+                new_table.push(bound_row);
+                new_table.push(vec![Int::int_zero(); cols]);
+
+                Tableau::Integral { table: new_table, denominator: denominator.clone(), transform: transform.clone() }
+            }
+        }
+    }
+
+    /// The table restricted to the columns `kept` (then the right-hand
+    /// side), `cols` wide, with a cleared objective row.
+    fn with_columns(&self, kept: &[usize], cols: usize) -> Tableau {
+        match self {
+            Tableau::Rational { table, transform } => {
+                let rows = table.row_count();
+                let rhs = table.col_count() - 1;
+                let mut final_table = BigMatrix::new(rows, cols);
+                for row in 0..(rows - 1) {
+                    for (c0, &c1) in kept.iter().enumerate() {
+                        final_table.set(row, c0, table.get(row, c1).clone());
+                    }
+                    final_table.set(row, cols - 1, table.get(row, rhs).clone());
+                }
+                Tableau::Rational { table: final_table, transform: transform.clone() }
+            }
+            Tableau::Integral { table, denominator, transform } => {
+                let rows = table.len();
+                let mut final_table = vec![vec![Int::int_zero(); cols]; rows];
+                for (values, final_values) in table[..rows - 1].iter().zip(&mut final_table) {
+                    for (c0, &c1) in kept.iter().enumerate() {
+                        final_values[c0] = values[c1].clone();
+                    }
+                    final_values[cols - 1] = values[values.len() - 1].clone();
+                }
+                Tableau::Integral { table: final_table, denominator: denominator.clone(), transform: transform.clone() }
+            }
+        }
+    }
+
+    /// `x` at the current basic solution.
+    fn point(&self, basics: &[usize]) -> BigVector {
+        match self {
+            Tableau::Rational { table, transform } => {
+                let rows = table.row_count();
+                let cols = table.col_count();
+                let tcols = transform.col_count();
+                let mut result = transform.get_col(tcols - 1);
+
+                for (row, &v0) in basics.iter().enumerate().take(rows - 1) {
+                    let scale = table.get(row, cols - 1);
+                    let col_vec = transform.get_col(v0);
+                    let scaled = col_vec.multiply_scalar(scale);
+                    result.subtract_assign(&scaled);
+                }
+                result
+            }
+            Tableau::Integral { table, denominator, transform } => {
+                let rows = table.len();
+                let rhs = table[0].len() - 1;
+                let data = transform
+                    .iter()
+                    .map(|tr_row| {
+                        let mut value = denominator.int_mul(&tr_row[tr_row.len() - 1]);
+                        for (values, &v0) in table[..rows - 1].iter().zip(basics) {
+                            value = value.int_sub(&values[rhs].int_mul(&tr_row[v0]));
+                        }
+                        BigFraction::frac_from_int(value).div_int(denominator)
+                    })
+                    .collect();
+                BigVector::from_data(data)
+            }
+        }
+    }
+}
+
+/// `rhs - lhs . x` as a row of the table, in terms of the current nonbasic
+/// variables.
+fn rational_row(
+    table: &BigMatrix,
+    transform: &BigMatrix,
+    basics: &[usize],
+    nonbasics: &[usize],
+    lhs: &BigVector,
+    rhs: &BigFraction,
+) -> BigVector {
+    let rows = table.row_count();
+    let cols = table.col_count();
+    let tcols = transform.col_count();
+    let mut transformed = BigVector::new(tcols);
+    transformed.set(tcols - 1, rhs.clone());
+
+    for (row, tr_row) in transform.rows().enumerate() {
+        let x = lhs.get(row);
+        for (col, value) in tr_row.iter().enumerate() {
+            transformed.get_mut(col).sub_mul_assign_frac(value, x);
+        }
+    }
+
+    let mut eliminated = BigVector::new(cols);
+    for (col, &nonbasic) in nonbasics.iter().enumerate().take(cols - 1) {
+        eliminated.set(col, transformed.get(nonbasic).clone());
+    }
+    eliminated.set(cols - 1, transformed.get(tcols - 1).clone());
+
+    for (t_row, &basic) in table.rows().zip(basics).take(rows - 1) {
+        let x = transformed.get(basic);
+        for (col, value) in t_row.iter().enumerate() {
+            eliminated.get_mut(col).sub_mul_assign_frac(value, x);
+        }
+    }
+
+    eliminated
+}
+
+/// [`rational_row`] for integral `lhs` and `rhs`, times the denominator.
+fn integral_row(
+    table: &[Vec<Int>],
+    denominator: &Int,
+    transform: &[Vec<Int>],
+    basics: &[usize],
+    nonbasics: &[usize],
+    lhs: &[Int],
+    rhs: &Int,
+) -> Vec<Int> {
+    let tcols = transform[0].len();
+    let mut transformed = vec![Int::int_zero(); tcols];
+    transformed[tcols - 1] = rhs.clone();
+
+    for (x, tr_row) in lhs.iter().zip(transform) {
+        if x.int_is_zero() {
+            continue;
+        }
+        for (t, y) in transformed.iter_mut().zip(tr_row) {
+            *t = t.int_sub(&x.int_mul(y));
+        }
+    }
+
+    let mut eliminated: Vec<Int> = nonbasics
+        .iter()
+        .chain(std::iter::once(&(tcols - 1)))
+        .map(|&v| denominator.int_mul(&transformed[v]))
+        .collect();
+
+    for (t_row, &v0) in table[..table.len() - 1].iter().zip(basics) {
+        let x = &transformed[v0];
+        if x.int_is_zero() {
+            continue;
+        }
+        for (e, y) in eliminated.iter_mut().zip(t_row) {
+            *e = e.int_sub(&x.int_mul(y));
+        }
+    }
+
+    eliminated
+}
+
+/// Index of the largest value above `zero`, or of the first one under
+/// Bland's rule.
+fn entering_col<'a, T: Ord + 'a>(values: impl Iterator<Item = &'a T>, zero: &T, bland: bool) -> Option<usize> {
+    let mut entering: Option<(usize, &T)> = None;
+    for (col, x) in values.enumerate() {
+        if x <= zero {
+            continue;
+        }
+        if entering.is_some_and(|(_, candidate)| x <= candidate) {
+            continue;
+        }
+        entering = Some((col, x));
+        if bland {
+            break;
+        }
+    }
+    entering.map(|(col, _)| col)
+}
+
+/// `lhs` and `rhs` times the least common multiple of their denominators,
+/// and that multiple.
+fn scale_to_integers(lhs: &BigVector, rhs: &BigFraction) -> (Vec<Int>, Int, Int) {
+    let values = || (0..lhs.dimension()).map(|i| lhs.get(i)).chain(std::iter::once(rhs));
+    let mut scale = Int::int_one();
+    for x in values() {
+        let den = x.denominator_int();
+        if !den.int_is_one() {
+            scale = scale.int_div(&scale.int_gcd(&den)).int_mul(&den);
+        }
+    }
+    let mut scaled: Vec<Int> = values()
+        .map(|x| x.numerator_int().int_mul(&scale.int_div(&x.denominator_int())))
+        .collect();
+    let rhs = scaled.pop().unwrap();
+    (scaled, rhs, scale)
+}
+
+/// The rows of `m` as integers, if they all are.
+fn integer_rows(m: &BigMatrix) -> Option<Vec<Vec<Int>>> {
+    (0..m.row_count())
+        .map(|row| {
+            (0..m.col_count())
+                .map(|col| {
+                    let x = m.get(row, col);
+                    x.denominator_int().int_is_one().then(|| x.numerator_int())
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn int_sign(x: &Int) -> i32 {
+    if x.int_is_positive() {
+        1
+    } else if x.int_is_negative() {
+        -1
+    } else {
+        0
     }
 }
 
@@ -426,7 +907,47 @@ impl OptimizeBuilder {
         self
     }
 
+    /// Constrain `lhs . x >= rhs`.
+    pub fn with_lower_bound(mut self, lhs: &BigVector, rhs: &BigFraction) -> Self {
+        assert_eq!(lhs.dimension(), self.size);
+        self.slacks.push(-1);
+        self.lefts.push(lhs.clone());
+        self.rights.push(rhs.clone());
+        self
+    }
+
+    /// Constrain `lhs . x <= rhs`.
+    pub fn with_upper_bound(mut self, lhs: &BigVector, rhs: &BigFraction) -> Self {
+        assert_eq!(lhs.dimension(), self.size);
+        self.slacks.push(1);
+        self.lefts.push(lhs.clone());
+        self.rights.push(rhs.clone());
+        self
+    }
+
+    /// Constrain `a x <= b`: one half-space per row of `a`.
+    pub fn with_inequalities(mut self, a: &BigMatrix, b: &BigVector) -> Self {
+        assert_eq!(a.row_count(), b.dimension());
+        for row in 0..a.row_count() {
+            self = self.with_upper_bound(&a.get_row(row), b.get(row));
+        }
+        self
+    }
+
+    /// Build the problem. Panics if no point satisfies every constraint or
+    /// phase 1 cycles; see [`try_build`](Self::try_build).
     pub fn build(self) -> Optimize {
+        self.try_build()
+            .unwrap_or_else(|e| panic!("{}", e))
+            .expect("Table has no basic feasible solutions")
+    }
+
+    /// Build the problem, or `None` if no point satisfies every constraint.
+    /// Fails if phase 1 reaches its iteration limit.
+    pub fn try_build(mut self) -> Result<Option<Optimize>, SolveError> {
+        if !self.presolve() {
+            return Ok(None);
+        }
         let variables = self.size + self.slacks.len();
         let mut constraint = 0usize;
         let mut slack = self.size;
@@ -451,11 +972,11 @@ impl OptimizeBuilder {
         }
 
         // Reduce real variables out
-        let mut pivot_rows = gauss_jordan::reduce(&mut table, &mut [], &|col, _| col < self.size);
+        let reduction = gauss_jordan::reduce(&mut table, &mut [], &|col, _| col < self.size);
 
         // For any real variables we couldn't remove, add slack pair
         for col in 0..self.size {
-            if pivot_rows[col] != -1 {
+            if reduction.is_pivot(col) {
                 continue;
             }
             table.set(constraint, col, BigFraction::frac_one());
@@ -466,23 +987,21 @@ impl OptimizeBuilder {
         }
 
         // Re-reduce
-        pivot_rows = gauss_jordan::reduce_all(&mut table);
+        let reduction = gauss_jordan::reduce_all(&mut table);
 
         // Check all real variables removed
         for col in 0..self.size {
-            assert!(
-                pivot_rows[col] != -1,
-                "Could not remove column from table"
-            );
+            assert!(reduction.is_pivot(col), "Could not remove column from table");
         }
 
-        constraint = 1 + pivot_rows.iter().copied().max().unwrap_or(-1) as usize;
+        // Rows past the rank reduced to zero
+        constraint = reduction.rank;
 
         // Build transform and inner table
         let slack_count = slack - self.size;
         let mut transform = BigMatrix::new(self.size, slack_count + 1);
         let inner_rows = if constraint > self.size { constraint - self.size } else { 0 };
-        let mut inner_table = BigMatrix::new(inner_rows.max(1), slack_count + 1);
+        let mut inner_table = BigMatrix::new(inner_rows, slack_count + 1);
 
         for row in 0..self.size {
             for col in 0..slack_count {
@@ -500,4 +1019,214 @@ impl OptimizeBuilder {
 
         Optimize::from_inner_table(&inner_table, &transform)
     }
+
+    /// Drop the constraints that others make redundant, so every solve on
+    /// the problem works on a smaller table: constraints with an empty
+    /// left-hand side, and all but the tightest lower and upper bound along
+    /// each direction. A lower and upper bound that meet become one equality,
+    /// without a slack variable. Returns `false` if two bounds contradict.
+    ///
+    /// The constraints kept stay in their order, as given.
+    fn presolve(&mut self) -> bool {
+        // A direction, scaled to lead with 1, and the tightest lower and
+        // upper bound along it: the constraint's index and its bound on
+        // `direction . x`.
+        struct Group {
+            direction: BigVector,
+            lower: Option<(usize, BigFraction)>,
+            upper: Option<(usize, BigFraction)>,
+        }
+
+        let mut groups: Vec<Group> = Vec::new();
+        for i in 0..self.slacks.len() {
+            let lhs = &self.lefts[i];
+            let Some(lead) = (0..self.size).map(|j| lhs.get(j)).find(|x| !x.is_zero()).cloned() else {
+                // `0 <= rhs` or `0 >= rhs`
+                if self.rights[i].signum() * self.slacks[i] < 0 {
+                    return false;
+                }
+                continue;
+            };
+            let bound = self.rights[i].div_frac(&lead);
+            let mut direction = lhs.clone();
+            direction.divide_scalar_assign(&lead);
+
+            let index = match groups.iter().position(|g| g.direction == direction) {
+                Some(index) => index,
+                None => {
+                    groups.push(Group { direction, lower: None, upper: None });
+                    groups.len() - 1
+                }
+            };
+            let group = &mut groups[index];
+            if (self.slacks[i] > 0) == (lead.signum() > 0) {
+                if group.upper.as_ref().is_none_or(|(_, b)| bound < *b) {
+                    group.upper = Some((i, bound));
+                }
+            } else if group.lower.as_ref().is_none_or(|(_, b)| bound > *b) {
+                group.lower = Some((i, bound));
+            }
+        }
+
+        let mut kept = vec![false; self.slacks.len()];
+        for group in groups {
+            match (group.lower, group.upper) {
+                (Some((_, lower)), Some((_, upper))) if lower > upper => return false,
+                (Some((i, lower)), Some((_, upper))) if lower == upper => {
+                    self.slacks[i] = 0;
+                    kept[i] = true;
+                }
+                (lower, upper) => {
+                    for (i, _) in lower.into_iter().chain(upper) {
+                        kept[i] = true;
+                    }
+                }
+            }
+        }
+
+        let slacks = std::mem::take(&mut self.slacks);
+        let lefts = std::mem::take(&mut self.lefts);
+        let rights = std::mem::take(&mut self.rights);
+        for (((slack, lhs), rhs), keep) in slacks.into_iter().zip(lefts).zip(rights).zip(kept) {
+            if keep {
+                self.slacks.push(slack);
+                self.lefts.push(lhs);
+                self.rights.push(rhs);
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vector(values: &[i64]) -> BigVector {
+        BigVector::from_data(values.iter().map(|&v| BigFraction::frac_from_i64(v)).collect())
+    }
+
+    /// The box `-3 <= x_i <= upper` with `x_0 + x_1 + x_2 <= 4`, and each
+    /// equality as a lower and an upper bound.
+    fn problem(upper: &BigFraction, equalities: &[(BigVector, BigFraction)]) -> Option<Optimize> {
+        let mut builder = OptimizeBuilder::of_size(3).with_upper_bound(&vector(&[1, 1, 1]), &BigFraction::frac_from_i64(4));
+        for i in 0..3 {
+            builder = builder
+                .with_lower_bound_idx(i, &BigFraction::frac_from_i64(-3))
+                .with_upper_bound_idx(i, upper);
+        }
+        for (lhs, rhs) in equalities {
+            builder = builder.with_lower_bound(lhs, rhs).with_upper_bound(lhs, rhs);
+        }
+        builder.try_build().unwrap()
+    }
+
+    fn ranges(lp: &Optimize) -> Vec<(BigFraction, BigFraction)> {
+        [[1, 0, 0], [0, 1, 0], [0, 0, 1], [1, -2, 1], [-1, 1, 3]]
+            .iter()
+            .map(|gradient| {
+                let gradient = vector(gradient);
+                (lp.clone().minimize(&gradient).1, lp.clone().maximize(&gradient).1)
+            })
+            .collect()
+    }
+
+    /// The same problem with its integral table written in fractions.
+    fn rational(lp: &Optimize) -> Optimize {
+        let Tableau::Integral { table, denominator, transform } = &lp.tableau else {
+            panic!("Table is not integral");
+        };
+        let matrix = |rows: &[Vec<Int>], denominator: &Int| {
+            let entry = |x: &Int| BigFraction::frac_from_int(x.clone()).div_int(denominator);
+            let rows: Vec<BigVector> =
+                rows.iter().map(|row| BigVector::from_data(row.iter().map(entry).collect())).collect();
+            BigMatrix::from_rows(&rows)
+        };
+        let tableau = Tableau::Rational {
+            table: matrix(table, denominator),
+            transform: matrix(transform, &Int::int_one()),
+        };
+        Optimize::new(tableau, lp.basics.clone(), lp.nonbasics.clone())
+    }
+
+    /// The minimum and maximum of each gradient, or why the solve failed.
+    fn optima(lp: &Optimize, gradients: &[BigVector]) -> Vec<[Result<BigFraction, SolveError>; 2]> {
+        gradients
+            .iter()
+            .map(|g| [lp.clone().try_minimize(g).map(|s| s.value), lp.clone().try_maximize(g).map(|s| s.value)])
+            .collect()
+    }
+
+    #[test]
+    fn test_integral_and_rational_tables_agree() {
+        let int = BigFraction::frac_from_i64;
+        let planar = |bounds: &[([i64; 2], i64)]| {
+            let mut builder = OptimizeBuilder::of_size(2);
+            for (lhs, rhs) in bounds {
+                builder = builder.with_upper_bound(&vector(lhs), &int(*rhs));
+            }
+            builder.build()
+        };
+        let gradients = [[1, 0], [0, 1], [1, 1], [1, -1], [-2, 1]].map(|g| vector(&g));
+
+        // A vertex where four constraints meet, so pivots there are degenerate.
+        let degenerate = planar(&[([-1, 0], 0), ([0, -1], 0), ([1, 0], 1), ([0, 1], 1), ([1, 1], 2), ([2, 1], 3)]);
+        // Open towards `x_0 + x_1`.
+        let unbounded = planar(&[([-1, 0], 0), ([0, -1], 0), ([1, -1], 1)]);
+        let unbounded_optima = optima(&unbounded, &gradients);
+        assert_eq!(unbounded_optima[2], [Ok(int(0)), Err(SolveError::Unbounded)]);
+        assert_eq!(unbounded_optima[3], [Err(SolveError::Unbounded), Ok(int(1))]);
+
+        for lp in [degenerate, unbounded] {
+            assert_eq!(optima(&lp, &gradients), optima(&rational(&lp), &gradients));
+        }
+
+        let gradients = [[1, 0, 0], [0, 1, 0], [1, -2, 1], [-1, 1, 3]].map(|g| vector(&g));
+        let lp = problem(&int(3), &[]).unwrap();
+        assert_eq!(optima(&lp, &gradients), optima(&rational(&lp), &gradients));
+        let (lhs, rhs) = (vector(&[1, 2, -1]), int(3));
+        let bounded = lp.with_strict_bound(&lhs, &rhs);
+        assert!(matches!(bounded.tableau, Tableau::Integral { .. }));
+        assert_eq!(optima(&bounded, &gradients), optima(&rational(&lp).with_strict_bound(&lhs, &rhs), &gradients));
+    }
+
+    #[test]
+    fn test_strict_bound_matches_rebuilt_problem() {
+        let equalities = [
+            (vector(&[1, 2, -1]), BigFraction::frac_from_i64(3)),
+            // Repeated, and constant on the problem: both hold everywhere.
+            (vector(&[1, 2, -1]), BigFraction::frac_from_i64(3)),
+            (vector(&[0, 0, 0]), BigFraction::frac_zero()),
+            (vector(&[0, 1, 1]), BigFraction::frac_new(-1, 2)),
+            (vector(&[2, -1, 0]), BigFraction::frac_from_i64(1)),
+        ];
+        // An integral table, and a rational one.
+        for upper in [BigFraction::frac_from_i64(3), BigFraction::frac_new(7, 2)] {
+            let mut lp = problem(&upper, &[]).unwrap();
+            for count in 1..=equalities.len() {
+                let (lhs, rhs) = &equalities[count - 1];
+                lp = lp.try_with_strict_bound(lhs, rhs).unwrap().unwrap();
+                let rebuilt = problem(&upper, &equalities[..count]).unwrap();
+                assert_eq!(ranges(&lp), ranges(&rebuilt), "after {} equalities", count);
+            }
+        }
+    }
+
+    #[test]
+    fn test_strict_bound_infeasible() {
+        let lp = problem(&BigFraction::frac_from_i64(3), &[]).unwrap();
+        assert!(lp.try_with_strict_bound(&vector(&[1, 1, 1]), &BigFraction::frac_from_i64(5)).unwrap().is_none());
+        assert!(lp.try_with_strict_bound(&vector(&[1, 0, 0]), &BigFraction::frac_from_i64(-4)).unwrap().is_none());
+        assert!(lp.try_with_strict_bound(&vector(&[0, 0, 0]), &BigFraction::frac_from_i64(1)).unwrap().is_none());
+
+        // A single point: every equality on it either holds or not.
+        let mut point = lp;
+        for (i, value) in [3, 3, -2].into_iter().enumerate() {
+            let mut lhs = vector(&[0, 0, 0]);
+            lhs.set(i, BigFraction::frac_one());
+            point = point.with_strict_bound(&lhs, &BigFraction::frac_from_i64(value));
+        }
+        assert!(point.try_with_strict_bound(&vector(&[1, 1, 0]), &BigFraction::frac_from_i64(6)).unwrap().is_some());
+        assert!(point.try_with_strict_bound(&vector(&[0, 1, 1]), &BigFraction::frac_from_i64(2)).unwrap().is_none());
+    }
 }
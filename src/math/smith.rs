@@ -0,0 +1,239 @@
+use super::int_type::{Int, IntOps};
+
+/// Smith normal form `D = U * A * V` of an integer matrix `A`, where `U` and
+/// `V` are unimodular and `D` is diagonal with each entry dividing the next.
+///
+/// A system of congruences `A x == b (mod m)` becomes `D y == U b (mod m)`
+/// with `x = V y`, which splits into one congruence per diagonal entry.
+#[derive(Clone, Debug)]
+pub struct SmithNormalForm {
+    /// The diagonal of `D`, `min(rows, cols)` entries: the invariant factors,
+    /// positive, then zeros.
+    pub diagonal: Vec<Int>,
+    /// `U`, rows x rows.
+    pub left: Vec<Vec<Int>>,
+    /// `V`, cols x cols.
+    pub right: Vec<Vec<Int>>,
+}
+
+impl SmithNormalForm {
+    /// Number of nonzero invariant factors.
+    pub fn rank(&self) -> usize {
+        self.diagonal.iter().take_while(|d| !d.int_is_zero()).count()
+    }
+}
+
+/// Smith normal form of the `rows x cols` integer matrix `matrix`, given as
+/// rows. The diagonal is built one entry at a time: the smallest nonzero
+/// entry left is moved to the corner, and its row and column are cleared by
+/// Euclidean steps until the corner divides everything that is left.
+pub fn smith_normal_form(matrix: &[Vec<Int>]) -> SmithNormalForm {
+    let rows = matrix.len();
+    let cols = matrix.first().map_or(0, Vec::len);
+    assert!(matrix.iter().all(|row| row.len() == cols), "Matrix rows have different lengths");
+
+    let mut a = matrix.to_vec();
+    let mut left = identity(rows);
+    let mut right = identity(cols);
+    let mut diagonal = Vec::with_capacity(rows.min(cols));
+
+    for t in 0..rows.min(cols) {
+        loop {
+            let Some((pr, pc)) = smallest_entry(&a, t) else {
+                diagonal.resize(rows.min(cols), Int::int_zero());
+                return SmithNormalForm { diagonal, left, right };
+            };
+            a.swap(t, pr);
+            left.swap(t, pr);
+            swap_cols(&mut a, t, pc);
+            swap_cols(&mut right, t, pc);
+
+            let mut clear = true;
+            for i in (t + 1)..rows {
+                let q = a[i][t].int_div(&a[t][t]);
+                if !q.int_is_zero() {
+                    subtract_row(&mut a, i, t, &q);
+                    subtract_row(&mut left, i, t, &q);
+                }
+                clear &= a[i][t].int_is_zero();
+            }
+            for j in (t + 1)..cols {
+                let q = a[t][j].int_div(&a[t][t]);
+                if !q.int_is_zero() {
+                    subtract_col(&mut a, j, t, &q);
+                    subtract_col(&mut right, j, t, &q);
+                }
+                clear &= a[t][j].int_is_zero();
+            }
+            if !clear {
+                // A remainder is now smaller than the corner.
+                continue;
+            }
+
+            // The corner must divide the rest; if not, adding the offending
+            // row brings in an entry whose remainder is smaller.
+            let corner = &a[t][t];
+            let offending =
+                ((t + 1)..rows).find(|&i| a[i][(t + 1)..].iter().any(|x| !x.int_rem(corner).int_is_zero()));
+            match offending {
+                Some(i) => {
+                    add_row(&mut a, t, i);
+                    add_row(&mut left, t, i);
+                }
+                None => break,
+            }
+        }
+
+        if a[t][t].int_is_negative() {
+            negate_row(&mut a, t);
+            negate_row(&mut left, t);
+        }
+        diagonal.push(a[t][t].clone());
+    }
+
+    SmithNormalForm { diagonal, left, right }
+}
+
+fn identity(size: usize) -> Vec<Vec<Int>> {
+    (0..size)
+        .map(|i| (0..size).map(|j| if i == j { Int::int_one() } else { Int::int_zero() }).collect())
+        .collect()
+}
+
+/// Position of the nonzero entry of least absolute value in the submatrix
+/// from `(t, t)` on, if any.
+fn smallest_entry(a: &[Vec<Int>], t: usize) -> Option<(usize, usize)> {
+    let mut best: Option<(usize, usize, Int)> = None;
+    for (i, row) in a.iter().enumerate().skip(t) {
+        for (j, x) in row.iter().enumerate().skip(t) {
+            if x.int_is_zero() {
+                continue;
+            }
+            let size = x.int_abs();
+            if best.as_ref().is_none_or(|(_, _, b)| size < *b) {
+                best = Some((i, j, size));
+            }
+        }
+    }
+    best.map(|(i, j, _)| (i, j))
+}
+
+/// `row[target] -= q * row[source]`
+fn subtract_row(a: &mut [Vec<Int>], target: usize, source: usize, q: &Int) {
+    let (src, dst) = if source < target {
+        let (head, tail) = a.split_at_mut(target);
+        (&head[source], &mut tail[0])
+    } else {
+        let (head, tail) = a.split_at_mut(source);
+        (&tail[0], &mut head[target])
+    };
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d = d.int_sub(&s.int_mul(q));
+    }
+}
+
+/// `row[target] += row[source]`
+fn add_row(a: &mut [Vec<Int>], target: usize, source: usize) {
+    subtract_row(a, target, source, &Int::int_one().int_neg());
+}
+
+fn negate_row(a: &mut [Vec<Int>], row: usize) {
+    for x in a[row].iter_mut() {
+        *x = x.int_neg();
+    }
+}
+
+/// `col[target] -= q * col[source]`
+fn subtract_col(a: &mut [Vec<Int>], target: usize, source: usize, q: &Int) {
+    for row in a.iter_mut() {
+        row[target] = row[target].int_sub(&row[source].int_mul(q));
+    }
+}
+
+fn swap_cols(a: &mut [Vec<Int>], c1: usize, c2: usize) {
+    for row in a.iter_mut() {
+        row.swap(c1, c2);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::big_fraction::{BigFraction, FracOps};
+    use crate::math::big_matrix::BigMatrix;
+    use crate::math::big_vector::BigVector;
+
+    fn ints(rows: &[&[i64]]) -> Vec<Vec<Int>> {
+        rows.iter().map(|row| row.iter().map(|&x| Int::int_from_i64(x)).collect()).collect()
+    }
+
+    fn multiply(a: &[Vec<Int>], b: &[Vec<Int>]) -> Vec<Vec<Int>> {
+        a.iter()
+            .map(|row| {
+                (0..b[0].len())
+                    .map(|j| row.iter().zip(b).fold(Int::int_zero(), |sum, (x, y)| sum.int_add(&x.int_mul(&y[j]))))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn determinant(a: &[Vec<Int>]) -> BigFraction {
+        let rows: Vec<BigVector> = a
+            .iter()
+            .map(|row| BigVector::from_data(row.iter().cloned().map(BigFraction::frac_from_int).collect()))
+            .collect();
+        BigMatrix::from_rows(&rows).determinant()
+    }
+
+    /// Checks `U * A * V == D`, that `U` and `V` are unimodular and that the
+    /// diagonal is a divisibility chain, and returns the diagonal.
+    fn check(matrix: &[&[i64]]) -> Vec<i64> {
+        let a = ints(matrix);
+        let snf = smith_normal_form(&a);
+        let (rows, cols) = (a.len(), a[0].len());
+        let d = multiply(&multiply(&snf.left, &a), &snf.right);
+        for (i, row) in d.iter().enumerate() {
+            for (j, x) in row.iter().enumerate() {
+                let expected = if i == j { snf.diagonal[i].clone() } else { Int::int_zero() };
+                assert_eq!(*x, expected, "D[{}][{}] of {:?}", i, j, matrix);
+            }
+        }
+        for unimodular in [&snf.left, &snf.right] {
+            let det = determinant(unimodular);
+            assert!(det == BigFraction::frac_from_i64(1) || det == BigFraction::frac_from_i64(-1));
+        }
+        assert_eq!(snf.diagonal.len(), rows.min(cols));
+        for pair in snf.diagonal.windows(2) {
+            assert!(!pair[0].int_is_negative());
+            if pair[0].int_is_zero() {
+                assert!(pair[1].int_is_zero());
+            } else {
+                assert!(pair[1].int_rem(&pair[0]).int_is_zero(), "{:?} does not divide {:?}", pair[0], pair[1]);
+            }
+        }
+        snf.diagonal.iter().map(IntOps::int_to_i64).collect()
+    }
+
+    #[test]
+    fn test_square() {
+        assert_eq!(check(&[&[2, 4, 4], &[-6, 6, 12], &[10, -4, -16]]), [2, 6, 12]);
+        // Coprime corners only become a chain after mixing rows.
+        assert_eq!(check(&[&[2, 0], &[0, 3]]), [1, 6]);
+        assert_eq!(check(&[&[-4, 0], &[0, 6]]), [2, 12]);
+    }
+
+    #[test]
+    fn test_singular() {
+        assert_eq!(check(&[&[1, 2, 3], &[4, 5, 6], &[7, 8, 9]]), [1, 3, 0]);
+        assert_eq!(check(&[&[0, 0], &[0, 0]]), [0, 0]);
+        assert_eq!(smith_normal_form(&ints(&[&[0, 0], &[0, 5]])).rank(), 1);
+    }
+
+    #[test]
+    fn test_non_square() {
+        assert_eq!(check(&[&[2, 4, 6], &[4, 8, 14]]), [2, 2]);
+        assert_eq!(check(&[&[2, 4], &[4, 8], &[6, 14]]), [2, 2]);
+        assert_eq!(check(&[&[6, 10, 15]]), [1]);
+        assert_eq!(check(&[&[3, 6], &[6, 12], &[9, 18]]), [3, 0]);
+    }
+}
@@ -1,9 +1,23 @@
+use crate::lcg::rand::JavaRng;
 use super::jrand::JRand;
+use super::xoroshiro::block_seed;
 use crate::math::mth;
+use std::fmt;
+use std::str::FromStr;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Minecraft version enum (relevant for population seed calculation).
+///
+/// Stops at 1.17: 1.18 moved dungeons below y = 0 and changed the decoration
+/// steps their feature salts come from, which is not modelled, so 1.18 and
+/// later versions are rejected rather than cracked with 1.17 rules.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum MCVersion {
+    /// 1.6 and earlier releases, which place dungeons below y = 128.
+    V1_6,
+    V1_7,
     V1_8,
     V1_9,
     V1_10,
@@ -28,10 +42,118 @@ impl MCVersion {
     pub fn is_between(&self, lower: MCVersion, upper: MCVersion) -> bool {
         (*self as u8) >= (lower as u8) && (*self as u8) <= (upper as u8)
     }
+
+    /// Exclusive bound of the `nextInt` call picking a dungeon's height.
+    pub fn dungeon_height_bound(&self) -> i32 {
+        if self.is_older_than(MCVersion::V1_7) {
+            128
+        } else {
+            256
+        }
+    }
+}
+
+/// Multipliers of the region coordinates in [`ChunkRand::set_region_seed`].
+pub const REGION_MULTIPLIER_X: i64 = 341873128712;
+pub const REGION_MULTIPLIER_Z: i64 = 132897987541;
+
+impl fmt::Display for MCVersion {
+    /// The release name, e.g. `1.16`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            MCVersion::V1_6 => "1.6",
+            MCVersion::V1_7 => "1.7",
+            MCVersion::V1_8 => "1.8",
+            MCVersion::V1_9 => "1.9",
+            MCVersion::V1_10 => "1.10",
+            MCVersion::V1_11 => "1.11",
+            MCVersion::V1_12 => "1.12",
+            MCVersion::V1_13 => "1.13",
+            MCVersion::V1_14 => "1.14",
+            MCVersion::V1_15 => "1.15",
+            MCVersion::V1_16 => "1.16",
+            MCVersion::V1_17 => "1.17",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for MCVersion {
+    type Err = String;
+
+    /// Parse a release (`1.16`), a patch release (`1.16.5`), a pre-release
+    /// or release candidate (`1.16-pre3`, `1.16.5-rc1`) or a snapshot
+    /// (`20w45a`), mapping each to the version it belongs to.
+    fn from_str(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        let minor = if let Some(release) = s.strip_prefix("1.") {
+            let release = release.split(['-', ' ']).next().unwrap_or("");
+            release.split('.').next().and_then(|minor| minor.parse::<u32>().ok())
+        } else {
+            snapshot_minor(s)
+        };
+
+        match minor {
+            Some(0..=6) => Ok(MCVersion::V1_6),
+            Some(7) => Ok(MCVersion::V1_7),
+            Some(8) => Ok(MCVersion::V1_8),
+            Some(9) => Ok(MCVersion::V1_9),
+            Some(10) => Ok(MCVersion::V1_10),
+            Some(11) => Ok(MCVersion::V1_11),
+            Some(12) => Ok(MCVersion::V1_12),
+            Some(13) => Ok(MCVersion::V1_13),
+            Some(14) => Ok(MCVersion::V1_14),
+            Some(15) => Ok(MCVersion::V1_15),
+            Some(16) => Ok(MCVersion::V1_16),
+            Some(17) => Ok(MCVersion::V1_17),
+            Some(_) => Err(format!(
+                "Unsupported version: {} (1.17 and earlier are supported; 1.18+ dungeon generation is not modelled)",
+                s
+            )),
+            None => Err(format!("Unknown version: {}", s)),
+        }
+    }
+}
+
+/// Last snapshot week of each release, as `(year, week, minor version)`.
+const SNAPSHOT_RELEASES: [(u32, u32, u32); 17] = [
+    (13, 26, 6),
+    (13, 49, 7),
+    (14, 34, 8),
+    (15, 51, 9),
+    (16, 15, 9),
+    (16, 21, 10),
+    (16, 50, 11),
+    (17, 31, 12),
+    (17, 50, 13),
+    (18, 33, 13),
+    (18, 50, 14),
+    (19, 14, 14),
+    (19, 46, 15),
+    (20, 30, 16),
+    (20, 51, 17),
+    (21, 20, 17),
+    (21, 52, 18),
+];
+
+/// Minor version of the release a `YYwWWx` snapshot led up to.
+fn snapshot_minor(s: &str) -> Option<u32> {
+    let (year, rest) = s.split_once('w')?;
+    let year: u32 = year.parse().ok()?;
+    let week: u32 = rest.get(..2)?.parse().ok()?;
+    if year < 13 {
+        return Some(6);
+    }
+    SNAPSHOT_RELEASES
+        .iter()
+        .find(|&&(y, last_week, _)| y == year && week <= last_week)
+        .map(|&(_, _, minor)| minor)
+        .or((year > 21).then_some(18))
 }
 
 /// Port of mc_core's ChunkRand.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ChunkRand {
     pub jrand: JRand,
 }
@@ -43,6 +165,22 @@ impl ChunkRand {
         }
     }
 
+    /// Set the terrain seed, which only depends on the chunk coordinates.
+    pub fn set_terrain_seed(&mut self, chunk_x: i32, chunk_z: i32, _version: MCVersion) -> i64 {
+        let seed = (chunk_x as i64)
+            .wrapping_mul(REGION_MULTIPLIER_X)
+            .wrapping_add((chunk_z as i64).wrapping_mul(REGION_MULTIPLIER_Z));
+        self.jrand.set_seed(seed, true);
+        seed & mth::MASK_48
+    }
+
+    /// Set the seed of block `x`, `y`, `z`, hashed like `Mth.getSeed`.
+    pub fn set_position_seed(&mut self, x: i32, y: i32, z: i32, _version: MCVersion) -> i64 {
+        let seed = block_seed(x, y, z);
+        self.jrand.set_seed(seed, true);
+        seed & mth::MASK_48
+    }
+
     /// Set the population seed. For 1.13+, uses |1L; for older, uses /2*2+1.
     /// `x` and `z` are the block coordinates of the negative-most corner of the chunk.
     pub fn set_population_seed(&mut self, world_seed: i64, x: i32, z: i32, version: MCVersion) -> i64 {
@@ -63,10 +201,145 @@ impl ChunkRand {
         seed & mth::MASK_48
     }
 
+    /// Set the carver seed used by caves and ravines. The multipliers are two
+    /// `nextLong` calls of the world seed RNG, unchanged across versions.
+    /// `chunk_x` and `chunk_z` are chunk coordinates.
+    pub fn set_carver_seed(&mut self, world_seed: i64, chunk_x: i32, chunk_z: i32, _version: MCVersion) -> i64 {
+        self.jrand.set_seed(world_seed, true);
+        let a = self.jrand.next_long();
+        let b = self.jrand.next_long();
+
+        let seed = (chunk_x as i64).wrapping_mul(a) ^ (chunk_z as i64).wrapping_mul(b) ^ world_seed;
+        self.jrand.set_seed(seed, true);
+        seed & mth::MASK_48
+    }
+
+    /// Set the seed deciding the structure position in a region.
+    /// `region_x` and `region_z` are region coordinates (chunk coords divided by the spacing).
+    pub fn set_region_seed(
+        &mut self,
+        structure_seed: i64,
+        region_x: i32,
+        region_z: i32,
+        salt: i32,
+        _version: MCVersion,
+    ) -> i64 {
+        let seed = (region_x as i64)
+            .wrapping_mul(REGION_MULTIPLIER_X)
+            .wrapping_add((region_z as i64).wrapping_mul(REGION_MULTIPLIER_Z))
+            .wrapping_add(structure_seed)
+            .wrapping_add(salt as i64);
+        self.jrand.set_seed(seed, true);
+        seed & mth::MASK_48
+    }
+
+    /// Set the weak seed, which only depends on the 16x16 chunk area around
+    /// `chunk_x`, `chunk_z`.
+    pub fn set_weak_seed(&mut self, world_seed: i64, chunk_x: i32, chunk_z: i32, _version: MCVersion) -> i64 {
+        let (area_x, area_z) = (chunk_x >> 4, chunk_z >> 4);
+        let seed = (area_x ^ (area_z << 4)) as i64 ^ world_seed;
+        self.jrand.set_seed(seed, true);
+        seed & mth::MASK_48
+    }
+
     /// Set the decorator seed. Only for 1.13+.
     pub fn set_decorator_seed(&mut self, population_seed: i64, salt: i32, _version: MCVersion) -> i64 {
         let seed = population_seed.wrapping_add(salt as i64);
         self.jrand.set_seed(seed, true);
         seed & mth::MASK_48
     }
+
+    /// Set the decorator seed of feature `index` in generation step `step`,
+    /// the salt 1.16+ derives from them. Only for 1.16+.
+    pub fn set_feature_seed(&mut self, population_seed: i64, index: i32, step: i32, version: MCVersion) -> i64 {
+        assert!(!version.is_older_than(MCVersion::V1_16), "feature seeds are only used from 1.16");
+        self.set_decorator_seed(population_seed, index + 10000 * step, version)
+    }
+
+    /// Set the seed deciding whether block `x`, `y`, `z` turns to deepslate,
+    /// as in 1.17's DepthBasedReplacingBaseStoneSource. Only for 1.17.
+    pub fn set_base_stone_seed(&mut self, world_seed: i64, x: i32, y: i32, z: i32, version: MCVersion) -> i64 {
+        assert!(!version.is_older_than(MCVersion::V1_17), "base stone seeds are only used from 1.17");
+        self.jrand.set_seed(world_seed, true);
+        let a = self.jrand.next_long();
+        let b = self.jrand.next_long();
+        let c = self.jrand.next_long();
+
+        let seed = (x as i64).wrapping_mul(a) ^ (y as i64).wrapping_mul(b) ^ (z as i64).wrapping_mul(c) ^ world_seed;
+        self.jrand.set_seed(seed, true);
+        seed & mth::MASK_48
+    }
+
+    /// Set the seed deciding whether chunk `chunk_x`, `chunk_z` is a slime chunk.
+    pub fn set_slime_seed(&mut self, world_seed: i64, chunk_x: i32, chunk_z: i32, _version: MCVersion) -> i64 {
+        let (x, z) = (chunk_x, chunk_z);
+        let seed = world_seed
+            .wrapping_add(x.wrapping_mul(x).wrapping_mul(0x4c1906) as i64)
+            .wrapping_add(x.wrapping_mul(0x5ac0db) as i64)
+            .wrapping_add((z.wrapping_mul(z) as i64).wrapping_mul(0x4307a7))
+            .wrapping_add(z.wrapping_mul(0x5f24f) as i64)
+            ^ 0x3ad8025f;
+        self.jrand.set_seed(seed, true);
+        seed & mth::MASK_48
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERSIONS: [MCVersion; 12] = [
+        MCVersion::V1_6,
+        MCVersion::V1_7,
+        MCVersion::V1_8,
+        MCVersion::V1_9,
+        MCVersion::V1_10,
+        MCVersion::V1_11,
+        MCVersion::V1_12,
+        MCVersion::V1_13,
+        MCVersion::V1_14,
+        MCVersion::V1_15,
+        MCVersion::V1_16,
+        MCVersion::V1_17,
+    ];
+
+    fn parse(s: &str) -> Result<MCVersion, String> {
+        s.parse()
+    }
+
+    #[test]
+    fn test_version_round_trip() {
+        for version in VERSIONS {
+            assert_eq!(parse(&version.to_string()), Ok(version));
+        }
+    }
+
+    #[test]
+    fn test_parse_release_names() {
+        assert_eq!(parse("1.16.5"), Ok(MCVersion::V1_16));
+        assert_eq!(parse(" 1.12.2 "), Ok(MCVersion::V1_12));
+        assert_eq!(parse("1.14-pre3"), Ok(MCVersion::V1_14));
+        assert_eq!(parse("1.16.5-rc1"), Ok(MCVersion::V1_16));
+        assert_eq!(parse("1.0"), Ok(MCVersion::V1_6));
+        assert_eq!(parse("1.4.7"), Ok(MCVersion::V1_6));
+    }
+
+    #[test]
+    fn test_parse_snapshots() {
+        assert_eq!(parse("12w30e"), Ok(MCVersion::V1_6));
+        assert_eq!(parse("14w34d"), Ok(MCVersion::V1_8));
+        assert_eq!(parse("17w31a"), Ok(MCVersion::V1_12));
+        assert_eq!(parse("17w43a"), Ok(MCVersion::V1_13));
+        assert_eq!(parse("20w45a"), Ok(MCVersion::V1_17));
+        assert_eq!(parse("21w20a"), Ok(MCVersion::V1_17));
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_input() {
+        for s in ["", "1.", "1.x", "2.0", "16", "latest", "20wxxa", "1.18", "1.20.1", "21w37a", "23w13a"] {
+            assert!(parse(s).is_err(), "{:?} parsed", s);
+        }
+        assert!(parse("1.18").unwrap_err().starts_with("Unsupported version"));
+        assert!(parse("latest").unwrap_err().starts_with("Unknown version"));
+    }
 }
@@ -0,0 +1,98 @@
+use crate::lcg::rand::JavaRng;
+use crate::mc::jrand::JRand;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// End pillars (1.9 and later): ten obsidian pillars on a circle of radius 42
+// around the exit portal. Their positions are fixed, but which pillar gets
+// which size is a shuffle seeded from the world seed. `new Random(seed)` only
+// keeps the lower 48 bits, so the layout is shared by all world seeds of a
+// structure seed and prunes structure seeds before they are expanded.
+
+pub const PILLAR_COUNT: usize = 10;
+
+/// Block position of pillar `index`, counting counterclockwise from (42, 0).
+pub fn pillar_position(index: usize) -> (i32, i32) {
+    let angle = 2.0 * (-std::f64::consts::PI + std::f64::consts::PI / 10.0 * index as f64);
+    ((42.0 * angle.cos()).floor() as i32, (42.0 * angle.sin()).floor() as i32)
+}
+
+/// Top height of a pillar of size `size` (0..10).
+pub fn pillar_height(size: u8) -> i32 {
+    76 + 3 * size as i32
+}
+
+/// Radius of a pillar of size `size` (0..10).
+pub fn pillar_radius(size: u8) -> i32 {
+    2 + size as i32 / 3
+}
+
+/// Whether a pillar of size `size` has an iron-bar cage around its crystal.
+pub fn pillar_is_caged(size: u8) -> bool {
+    size == 1 || size == 2
+}
+
+/// Size of each pillar of `world_seed`, indexed like [`pillar_position`].
+/// Mirrors EndSpikeFeature.getSpikesForLevel.
+pub fn pillar_sizes(world_seed: i64) -> [u8; PILLAR_COUNT] {
+    let shuffle_seed = JRand::new(world_seed).next_long() & 0xFFFF;
+    let mut rand = JRand::new(shuffle_seed);
+
+    // Collections.shuffle
+    let mut sizes: [u8; PILLAR_COUNT] = std::array::from_fn(|i| i as u8);
+    for i in (2..=PILLAR_COUNT).rev() {
+        sizes.swap(i - 1, rand.next_int(i as i32) as usize);
+    }
+    sizes
+}
+
+/// Observed pillar heights, used to drop cracked seeds with another layout.
+/// Pillars left unset are not checked.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PillarLayout {
+    pub(crate) heights: [Option<i32>; PILLAR_COUNT],
+}
+
+impl PillarLayout {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the top height of pillar `index` (see [`pillar_position`]).
+    ///
+    /// Panics unless `index` is in `0..PILLAR_COUNT`.
+    pub fn height(mut self, index: usize, height: i32) -> Self {
+        assert!(index < PILLAR_COUNT, "pillar index {} is outside 0..{}", index, PILLAR_COUNT);
+        self.heights[index] = Some(height);
+        self
+    }
+
+    /// Whether `world_seed` generates every recorded height.
+    pub fn matches(&self, world_seed: i64) -> bool {
+        let sizes = pillar_sizes(world_seed);
+        self.heights
+            .iter()
+            .zip(sizes)
+            .all(|(height, size)| height.is_none_or(|h| h == pillar_height(size)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_matches_own_pillars() {
+        let sizes = pillar_sizes(123456789);
+        let layout = PillarLayout::new().height(0, pillar_height(sizes[0])).height(9, pillar_height(sizes[9]));
+        assert!(layout.matches(123456789));
+        assert!(!PillarLayout::new().height(9, pillar_height(sizes[9]) + 1).matches(123456789));
+    }
+
+    #[test]
+    #[should_panic(expected = "pillar index 10 is outside 0..10")]
+    fn test_height_rejects_out_of_range_index() {
+        let _ = PillarLayout::new().height(PILLAR_COUNT, 76);
+    }
+}
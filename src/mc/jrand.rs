@@ -1,8 +1,12 @@
-use crate::lcg::lcg::LCG;
+use crate::lcg::lcg::{LCG, LCG128};
+use crate::lcg::rand::{JavaRng, Rand};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Port of mcseed's JRand - Java's java.util.Random equivalent.
 /// This is separate from latticg's Rand - this is used for MC world gen simulation.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct JRand {
     seed: i64,
 }
@@ -32,46 +36,133 @@ impl JRand {
         self.seed
     }
 
-    pub fn next(&mut self, bits: i32) -> i32 {
-        self.seed = LCG::JAVA.next_seed(self.seed);
-        (self.seed >> (48 - bits)) as i32
+    pub fn advance(&mut self, calls: i64) {
+        let skip = LCG::JAVA.combine(calls);
+        self.seed = skip.next_seed(self.seed);
     }
 
-    pub fn next_int(&mut self, bound: i32) -> i32 {
-        if bound <= 0 {
-            panic!("bound must be positive");
+    /// Advance by a precombined skip such as [`LCG::JAVA_SKIP_NEG_5`].
+    pub fn advance_lcg(&mut self, skip: &LCG) {
+        self.seed = skip.next_seed(self.seed);
+    }
+
+    /// `Mth.nextInt`: uniform in `min..=max`, or `min` without a call if `min >= max`.
+    pub fn next_int_between(&mut self, min: i32, max: i32) -> i32 {
+        if min >= max {
+            return min;
         }
+        self.next_int(max - min + 1) + min
+    }
 
-        if (bound & (-bound)) == bound {
-            // power of 2
-            return ((bound as i64).wrapping_mul(self.next(31) as i64) >> 31) as i32;
+    /// `Mth.nextFloat`: uniform in `min..max`, or `min` without a call if `min >= max`.
+    pub fn next_float_between(&mut self, min: f32, max: f32) -> f32 {
+        if min >= max {
+            return min;
         }
+        self.next_float() * (max - min) + min
+    }
 
-        loop {
-            let bits = self.next(31);
-            let value = bits % bound;
-            if bits - value + (bound - 1) >= 0 {
-                return value;
+    /// `Mth.nextDouble`: uniform in `min..max`, or `min` without a call if `min >= max`.
+    pub fn next_double_between(&mut self, min: f64, max: f64) -> f64 {
+        if min >= max {
+            return min;
+        }
+        self.next_double() * (max - min) + min
+    }
+
+    /// `triangle(center, spread)`: `center` plus a triangular offset in `-spread..spread`.
+    pub fn next_triangle(&mut self, center: f64, spread: f64) -> f64 {
+        center + spread * (self.next_double() - self.next_double())
+    }
+
+    /// The average of two `nextInt(bound)` rolls, as used by triangular
+    /// structure placement (ocean monuments, woodland mansions).
+    pub fn next_int_triangular(&mut self, bound: i32) -> i32 {
+        (self.next_int(bound) + self.next_int(bound)) / 2
+    }
+
+    /// `ints(count)`: the next `count` `nextInt()` values.
+    pub fn ints(&mut self, count: usize) -> impl Iterator<Item = i32> + '_ {
+        (0..count).map(move |_| self.next(32))
+    }
+
+    /// `ints(count, origin, bound)`: the next `count` values in `origin..bound`,
+    /// drawn like Java 8 does.
+    pub fn ints_between(&mut self, count: usize, origin: i32, bound: i32) -> impl Iterator<Item = i32> + '_ {
+        assert!(origin < bound, "bound must be greater than origin");
+        (0..count).map(move |_| {
+            let range = bound.wrapping_sub(origin);
+            if range > 0 {
+                return self.next_int(range) + origin;
             }
+            // The range does not fit an int, so reject until inside it.
+            loop {
+                let value = self.next(32);
+                if value >= origin && value < bound {
+                    return value;
+                }
+            }
+        })
+    }
+
+    /// `longs(count)`: the next `count` `nextLong()` values.
+    pub fn longs(&mut self, count: usize) -> impl Iterator<Item = i64> + '_ {
+        (0..count).map(move |_| self.next_long())
+    }
+}
+
+impl JavaRng for JRand {
+    fn next(&mut self, bits: i32) -> i32 {
+        self.seed = LCG::JAVA.next_seed(self.seed);
+        (self.seed >> (48 - bits)) as i32
+    }
+}
+
+impl From<&JRand> for Rand {
+    fn from(rand: &JRand) -> Self {
+        Rand::of_internal_seed(&LCG::JAVA, rand.seed)
+    }
+}
+
+impl TryFrom<&Rand> for JRand {
+    type Error = String;
+
+    /// Fails unless `rand` runs the Java LCG.
+    fn try_from(rand: &Rand) -> Result<Self, String> {
+        if *rand.lcg() != LCG128::JAVA {
+            return Err("Only a Rand on the Java LCG converts to JRand".to_string());
         }
+        Ok(JRand::of_internal_seed(rand.get_seed()))
     }
+}
 
-    pub fn next_long(&mut self) -> i64 {
-        ((self.next(32) as i64) << 32).wrapping_add(self.next(32) as i64)
+/// Plugs the Java-faithful generator into the `rand` ecosystem.
+/// `next_u32`/`next_u64` are `nextInt()`/`nextLong()` and `fill_bytes` is `nextBytes`.
+#[cfg(feature = "rand-compat")]
+impl rand_core::RngCore for JRand {
+    fn next_u32(&mut self) -> u32 {
+        self.next(32) as u32
     }
 
-    pub fn next_float(&mut self) -> f32 {
-        self.next(24) as f32 / (1 << 24) as f32
+    fn next_u64(&mut self) -> u64 {
+        self.next_long() as u64
     }
 
-    pub fn next_double(&mut self) -> f64 {
-        let hi = (self.next(26) as i64) << 27;
-        let lo = self.next(27) as i64;
-        (hi + lo) as f64 * (1.0f64 / (1i64 << 53) as f64)
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.next_bytes(dest);
     }
+}
 
-    pub fn advance(&mut self, calls: i64) {
-        let skip = LCG::JAVA.combine(calls);
-        self.seed = skip.next_seed(self.seed);
+/// Seeds like `new Random(seed)`, the seed read as a little-endian `long`.
+#[cfg(feature = "rand-compat")]
+impl rand_core::SeedableRng for JRand {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        JRand::new(i64::from_le_bytes(seed))
+    }
+
+    fn seed_from_u64(state: u64) -> Self {
+        JRand::new(state as i64)
     }
 }
@@ -3,3 +3,8 @@ pub mod chunk_rand;
 pub mod population_reverser;
 pub mod next_long_reverser;
 pub mod hensel;
+pub mod end_pillars;
+pub mod structure_filter;
+pub mod slime;
+pub mod xoroshiro;
+pub mod seed_text;
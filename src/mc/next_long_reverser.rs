@@ -1,4 +1,9 @@
+use crate::lattice::enumerate::SearchError;
+use crate::lcg::rand::JavaRng;
 use crate::mc::jrand::JRand;
+use crate::math::mth;
+use crate::reverser::random_reverser::JavaRandomReverser;
+use std::collections::BTreeSet;
 
 /// Port of mc_core's NextLongReverser.
 /// Converts 48-bit structure seeds to 64-bit world seeds by reversing nextLong().
@@ -20,6 +25,47 @@ pub fn get_next_long_equivalents(structure_seed: i64) -> Vec<i64> {
     next_longs
 }
 
+/// Returns the internal seeds whose nextLong has the top `known_bits` bits of
+/// `next_long`, sorted. The lower bits of `next_long` are ignored. Each
+/// unknown bit doubles the result, so this is only practical with most of
+/// the upper 48 bits known.
+pub fn get_seeds_partial(next_long: i64, known_bits: u32) -> Result<Vec<i64>, SearchError> {
+    assert!((1..=64).contains(&known_bits), "known_bits must be in 1..=64");
+
+    let low_mask = u64::MAX.checked_shr(known_bits).unwrap_or(0);
+    let min = next_long as u64 & !low_mask;
+    let max = min | low_mask;
+
+    // nextLong = (next(32) << 32) + next(32) with the second call signed, so
+    // a negative second call borrows from the first. Solve both cases.
+    let mut seeds = BTreeSet::new();
+    for borrow in [0u64, 1] {
+        let (sign_min, sign_max) = if borrow == 0 { (0, 0x7fff_ffff) } else { (0x8000_0000, 0xffff_ffff) };
+        let (low_min, low_max) = if known_bits > 32 {
+            ((min & 0xffff_ffff).max(sign_min), (max & 0xffff_ffff).min(sign_max))
+        } else {
+            (sign_min, sign_max)
+        };
+        if low_min > low_max {
+            continue;
+        }
+
+        let mut reverser = JavaRandomReverser::new(Vec::new());
+        reverser.add_next_int_unbounded_call(
+            ((min >> 32) + borrow) as u32 as i32,
+            ((max >> 32) + borrow) as u32 as i32,
+        );
+        reverser.add_next_int_unbounded_call(low_min as u32 as i32, low_max as u32 as i32);
+        for seed in reverser.find_all_valid_seeds()? {
+            let mut r = JRand::of_internal_seed(seed);
+            if r.next_long() as u64 & !low_mask == min {
+                seeds.insert(seed);
+            }
+        }
+    }
+    Ok(seeds.into_iter().collect())
+}
+
 fn add_seeds_to_list(structure_seed: i64, seed_list: &mut Vec<i64>) {
     let lower_bits = structure_seed & 0xffff_ffffi64;
     let mut upper_bits = (structure_seed as u64 >> 32) as i64;
@@ -35,11 +81,11 @@ fn add_seeds_to_list(structure_seed: i64, seed_list: &mut Vec<i64>) {
     let low_max = ((lower_bits + 1) << (16 - bits_of_danger)) - 1;
     let upper_min = ((upper_bits << 16) - 107048004364969i64) >> bits_of_danger;
 
-    let m1lv = floor_div(
+    let m1lv = mth::floor_div(
         low_max.wrapping_mul(-33441).wrapping_add(upper_min.wrapping_mul(17549)),
         1i64 << (31 - bits_of_danger),
     ) + 1;
-    let m2lv = floor_div(
+    let m2lv = mth::floor_div(
         low_min.wrapping_mul(46603).wrapping_add(upper_min.wrapping_mul(39761)),
         1i64 << (32 - bits_of_danger),
     ) + 1;
@@ -78,13 +124,48 @@ fn add_seeds_to_list(structure_seed: i64, seed_list: &mut Vec<i64>) {
     }
 }
 
-/// Java's Math.floorDiv
-fn floor_div(x: i64, y: i64) -> i64 {
-    let r = x / y;
-    // If the signs are different and there's a remainder, subtract 1
-    if (x ^ y) < 0 && (r * y != x) {
-        r - 1
-    } else {
-        r
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lcg::lcg::LCG;
+
+    fn next_long(seed: i64) -> i64 {
+        JRand::of_internal_seed(seed).next_long()
+    }
+
+    /// An internal seed whose nextLong has a negative low word, so its high
+    /// word is one less than the first `next(32)`, and one whose is not.
+    fn seeds() -> [i64; 2] {
+        let find = |negative: bool| (1..).map(|i| i * 0x1_2345_6789).find(|&s| ((next_long(s) as i32) < 0) == negative);
+        [find(false).unwrap(), find(true).unwrap()]
+    }
+
+    /// Every seed whose nextLong has the high word `high`, by trying all
+    /// states after a first `next(32)` of `high` or `high + 1`.
+    fn brute_force_high_word(high: i32) -> Vec<i64> {
+        let mut seeds: Vec<i64> = [high, high.wrapping_add(1)]
+            .iter()
+            .flat_map(|&first| (0..1 << 16).map(move |low| (first as u32 as i64) << 16 | low))
+            .map(|state| LCG::JAVA_SKIP_NEG_1.next_seed(state))
+            .filter(|&seed| (next_long(seed) >> 32) as i32 == high)
+            .collect();
+        seeds.sort_unstable();
+        seeds
+    }
+
+    #[test]
+    fn test_get_seeds_partial() {
+        for seed in seeds() {
+            let value = next_long(seed);
+            for known_bits in [64, 48, 40, 33, 32] {
+                let found = get_seeds_partial(value, known_bits).unwrap();
+                assert!(found.contains(&seed), "{} bits of {:x}", known_bits, value);
+                let shift = 64 - known_bits;
+                assert!(found.iter().all(|&s| next_long(s) >> shift == value >> shift));
+                if known_bits == 32 {
+                    assert_eq!(found, brute_force_high_word((value >> 32) as i32));
+                }
+            }
+        }
     }
 }
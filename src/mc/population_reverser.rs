@@ -1,6 +1,7 @@
 use crate::lcg::lcg::LCG;
+use crate::lcg::rand::JavaRng;
 use crate::math::mth;
-use crate::mc::chunk_rand::{ChunkRand, MCVersion};
+use crate::mc::chunk_rand::{ChunkRand, MCVersion, REGION_MULTIPLIER_X, REGION_MULTIPLIER_Z};
 use crate::mc::hensel;
 use std::collections::HashSet;
 
@@ -8,16 +9,15 @@ use std::collections::HashSet;
 
 const M1: i64 = LCG::JAVA.multiplier;
 
-// Precompute LCG combine(2) and combine(4). We compute these once per call
-// instead of using lazy_static, since const fn isn't available for combine.
-fn lcg_params() -> (i64, i64, i64, i64) {
-    let lcg2 = LCG::JAVA.combine(2);
-    let lcg4 = LCG::JAVA.combine(4);
+// Multipliers and addends of LCG combine(2) and combine(4).
+const fn lcg_params() -> (i64, i64, i64, i64) {
+    let (lcg2, lcg4) = (LCG::JAVA_SKIP_2, LCG::JAVA_SKIP_4);
     (lcg2.multiplier, lcg2.addend, lcg4.multiplier, lcg4.addend)
 }
 
 /// Reverse population seed to world seeds.
 /// This is the main entry point, matching ChunkRandomReverser.reversePopulationSeed.
+/// `version` only selects between the pre-1.13 and the 1.13+ formula.
 pub fn reverse_population_seed(population_seed: i64, x: i32, z: i32, version: MCVersion) -> Vec<i64> {
     let pop_seed = population_seed & mth::MASK_48;
 
@@ -28,6 +28,27 @@ pub fn reverse_population_seed(population_seed: i64, x: i32, z: i32, version: MC
     reverse(pop_seed, x, z, version)
 }
 
+/// Reverse a decorator seed (1.13+) to the population seed it was made from.
+/// Inverse of [`ChunkRand::set_decorator_seed`]: the population seed is unique,
+/// but a list is returned like the other reversals. Empty before 1.13, which
+/// has no decorator seeds.
+pub fn reverse_decorator_seed(decorator_seed: i64, salt: i32, version: MCVersion) -> Vec<i64> {
+    if version.is_older_than(MCVersion::V1_13) {
+        return Vec::new();
+    }
+    vec![decorator_seed.wrapping_sub(salt as i64) & mth::MASK_48]
+}
+
+/// Reverse a region seed to the structure seed it was made from.
+/// Inverse of [`ChunkRand::set_region_seed`]; the structure seed is unique.
+pub fn reverse_region_seed(region_seed: i64, region_x: i32, region_z: i32, salt: i32) -> Vec<i64> {
+    let structure_seed = region_seed
+        .wrapping_sub((region_x as i64).wrapping_mul(REGION_MULTIPLIER_X))
+        .wrapping_sub((region_z as i64).wrapping_mul(REGION_MULTIPLIER_Z))
+        .wrapping_sub(salt as i64);
+    vec![structure_seed & mth::MASK_48]
+}
+
 fn reverse(population_seed: i64, x: i32, z: i32, version: MCVersion) -> Vec<i64> {
     let (m2_val, a2_val, m4_val, a4_val) = lcg_params();
 
@@ -35,7 +56,6 @@ fn reverse(population_seed: i64, x: i32, z: i32, version: MCVersion) -> Vec<i64>
     // For efficiency we compute on the fly instead of full 65536 tables.
 
     let mut world_seeds = Vec::new();
-    let mut rand = ChunkRand::new();
 
     let e = population_seed & mth::MASK_32;
     let f = population_seed & mth::MASK_16;
@@ -74,11 +94,12 @@ fn reverse(population_seed: i64, x: i32, z: i32, version: MCVersion) -> Vec<i64>
         return world_seeds;
     }
 
-    let first_mult_inv = mth::mod_inverse_16(first_multiplier >> mult_trailing_zeroes);
+    let first_mult_inv = mth::mod_inverse_16(first_multiplier >> mult_trailing_zeroes)
+        .expect("Multiplier is odd once its trailing zeroes are shifted out");
 
     let offsets = get_offsets(x, z, version);
 
-    while c < (1i64 << 16) {
+    search_low_bits(c, increment, &|c, world_seeds, rand| {
         let target = (c ^ f) & mth::MASK_16;
         let x_term = ((m2_val.wrapping_mul((c ^ M1) & mth::MASK_16).wrapping_add(a2_val)) as u64 >> 16) as i64;
         let z_term = ((m4_val.wrapping_mul((c ^ M1) & mth::MASK_16).wrapping_add(a4_val)) as u64 >> 16) as i64;
@@ -94,18 +115,48 @@ fn reverse(population_seed: i64, x: i32, z: i32, version: MCVersion) -> Vec<i64>
                 x,
                 z,
                 population_seed,
-                &mut world_seeds,
-                &mut rand,
+                world_seeds,
+                rand,
                 version,
             );
         }
+    })
+}
+
+/// Search callback for one value of the low 16 seed bits.
+type LowBitsSearch<'a> = dyn Fn(i64, &mut Vec<i64>, &mut ChunkRand) + Sync + 'a;
 
+/// Run `search` for the low 16 bits `start`, `start + increment`, ... below 2^16.
+#[cfg(not(feature = "rayon"))]
+fn search_low_bits(start: i64, increment: i64, search: &LowBitsSearch) -> Vec<i64> {
+    let mut world_seeds = Vec::new();
+    let mut rand = ChunkRand::new();
+    let mut c = start;
+    while c < (1i64 << 16) {
+        search(c, &mut world_seeds, &mut rand);
         c += increment;
     }
-
     world_seeds
 }
 
+/// Same as the sequential version, spread over the rayon thread pool. The
+/// results are merged in order of the low bits, so the output is unchanged.
+#[cfg(feature = "rayon")]
+fn search_low_bits(start: i64, increment: i64, search: &LowBitsSearch) -> Vec<i64> {
+    use rayon::prelude::*;
+
+    let values: Vec<i64> = (start..1i64 << 16).step_by(increment as usize).collect();
+    values
+        .par_iter()
+        .map_init(ChunkRand::new, |rand, &c| {
+            let mut world_seeds = Vec::new();
+            search(c, &mut world_seeds, rand);
+            world_seeds
+        })
+        .flatten_iter()
+        .collect()
+}
+
 fn add_world_seeds(
     first_addend: i64,
     mult_trailing_zeroes: u32,
@@ -264,7 +315,8 @@ fn get_seed_from_chunkseed_pre13(chunkseed: i64, x: i32, z: i32) -> Vec<i64> {
 
     let first_multiplier = (m2_val.wrapping_mul(x as i64).wrapping_add(m4_val.wrapping_mul(z as i64))) & mth::MASK_16;
     let mult_trailing_zeroes = first_multiplier.trailing_zeros();
-    let first_mult_inv = mth::mod_inverse_16(first_multiplier >> mult_trailing_zeroes);
+    let first_mult_inv = mth::mod_inverse_16(first_multiplier >> mult_trailing_zeroes)
+        .expect("Multiplier is odd once its trailing zeroes are shifted out");
 
     let xcount = (x as i64).trailing_zeros();
     let zcount = (z as i64).trailing_zeros();
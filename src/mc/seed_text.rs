@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+
+// Text seeds: a seed typed on the world creation screen that is not a number
+// becomes its Java `String.hashCode`. The hash is an `int`, so text seeds are
+// always in `i32` range, and a wordlist of guesses can be matched back to the
+// cracked world seeds.
+
+/// Java `String.hashCode` of `text`, sign-extended like the world seed it becomes.
+pub fn hash_seed(text: &str) -> i64 {
+    text.encode_utf16().fold(0i32, |h, unit| h.wrapping_mul(31).wrapping_add(unit as i32)) as i64
+}
+
+/// The world seed the world creation screen makes of `text`: the number
+/// itself if it parses as a long, its [`hash_seed`] otherwise.
+pub fn seed_from_text(text: &str) -> i64 {
+    let text = text.trim();
+    text.parse::<i64>().unwrap_or_else(|_| hash_seed(text))
+}
+
+/// Candidate text seeds by the world seed each one turns into.
+#[derive(Clone, Debug, Default)]
+pub struct Wordlist {
+    words: BTreeMap<i64, Vec<String>>,
+}
+
+impl Wordlist {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A wordlist with one candidate per item of `words`.
+    pub fn from_words<S: Into<String>>(words: impl IntoIterator<Item = S>) -> Self {
+        let mut list = Self::new();
+        for word in words {
+            list.add(word);
+        }
+        list
+    }
+
+    /// A wordlist with one candidate per non-empty line of `text`.
+    pub fn from_lines(text: &str) -> Self {
+        Self::from_words(text.lines().map(str::trim).filter(|line| !line.is_empty()))
+    }
+
+    pub fn add(&mut self, word: impl Into<String>) {
+        let word = word.into();
+        self.words.entry(seed_from_text(&word)).or_default().push(word);
+    }
+
+    /// The candidates that turn into `world_seed`.
+    pub fn words_for(&self, world_seed: i64) -> &[String] {
+        self.words.get(&world_seed).map_or(&[], Vec::as_slice)
+    }
+
+    /// The world seed of every candidate.
+    pub fn seeds(&self) -> impl Iterator<Item = i64> + '_ {
+        self.words.keys().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_seed_matches_java() {
+        assert_eq!(hash_seed(""), 0);
+        assert_eq!(hash_seed("hello"), 99162322);
+        assert_eq!(hash_seed("Minecraft"), -1595926131);
+        assert_eq!(seed_from_text(" 404 "), 404);
+        assert_eq!(Wordlist::from_lines("hello\n\nworld\n").words_for(99162322), ["hello"]);
+    }
+}
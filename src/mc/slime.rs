@@ -0,0 +1,43 @@
+use crate::lcg::rand::JavaRng;
+use crate::mc::chunk_rand::{ChunkRand, MCVersion};
+
+// Slime chunks are picked by a `Random` seeded from the world seed and the
+// chunk coordinates, so like structures they only depend on the lower 48 bits.
+
+/// Whether chunk `chunk_x`, `chunk_z` is a slime chunk for `seed` (world or
+/// structure seed). Mirrors Chunk.getRandomWithSeed in the slime spawn check.
+pub fn is_slime_chunk(seed: i64, chunk_x: i32, chunk_z: i32) -> bool {
+    let mut rand = ChunkRand::new();
+    rand.set_slime_seed(seed, chunk_x, chunk_z, MCVersion::V1_17);
+    rand.jrand.next_int(10) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORLD_SEED: i64 = -4172144997902289642;
+
+    #[test]
+    fn test_slime_chunks_near_origin() {
+        // Worked out with the Java slime check.
+        let expected = [
+            (-6, -5), (-5, 2), (-5, 3), (-4, -4), (-3, -4), (-2, -5), (-2, 3), (-1, -5), (1, 0), (1, 1),
+            (2, -5), (3, 0), (4, -4), (4, -2), (4, 3), (5, -6), (5, -5), (6, -6), (6, -5),
+        ];
+        let found: Vec<(i32, i32)> = (-6..=6)
+            .flat_map(|x| (-6..=6).map(move |z| (x, z)))
+            .filter(|&(x, z)| is_slime_chunk(WORLD_SEED, x, z))
+            .collect();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn test_slime_chunks_far_out() {
+        // `x * x` overflows an int here.
+        assert!(is_slime_chunk(WORLD_SEED, 46342, -1874999));
+        assert!(!is_slime_chunk(WORLD_SEED, 46342, -1875000));
+        // Only the structure seed matters.
+        assert!(is_slime_chunk(WORLD_SEED ^ (0x1234 << 48), 46342, -1874999));
+    }
+}
@@ -0,0 +1,151 @@
+use crate::lcg::rand::JavaRng;
+use crate::mc::chunk_rand::{ChunkRand, MCVersion};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// Cross-check against structures seen in the same world. Structure positions
+// only depend on the lower 48 bits of the world seed: the world is split into
+// square regions and each region's RNG, seeded by `set_region_seed`, picks the
+// chunk of at most one structure. A known structure chunk therefore rejects
+// almost every wrong structure seed before it is expanded to world seeds.
+// Biome checks are not modelled; a structure that was seen passed them.
+
+/// Structures whose position can be checked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StructureType {
+    Village,
+    DesertPyramid,
+    JungleTemple,
+    SwampHut,
+    Igloo,
+    OceanMonument,
+    WoodlandMansion,
+    /// 1.13+.
+    BuriedTreasure,
+}
+
+/// How a structure picks its chunks.
+enum Placement {
+    /// One attempt per region at `nextInt(spacing - separation)` on each axis.
+    Uniform { spacing: i32, separation: i32, salt: i32 },
+    /// Like `Uniform`, but each axis averages two rolls.
+    Triangular { spacing: i32, separation: i32, salt: i32 },
+    /// Every chunk is its own region and has the structure with this chance.
+    Chance { salt: i32, chance: f32 },
+}
+
+impl StructureType {
+    /// Placement in `version`, or `None` if the structure does not generate there.
+    fn placement(self, version: MCVersion) -> Option<Placement> {
+        let pre_1_13 = version.is_older_than(MCVersion::V1_13);
+        // Before 1.13 all scattered features share one salt.
+        let temple = |salt| Placement::Uniform {
+            spacing: 32,
+            separation: 8,
+            salt: if pre_1_13 { 14357617 } else { salt },
+        };
+        Some(match self {
+            StructureType::Village => Placement::Uniform { spacing: 32, separation: 8, salt: 10387312 },
+            StructureType::DesertPyramid => temple(14357617),
+            StructureType::JungleTemple => temple(14357619),
+            StructureType::SwampHut => temple(14357620),
+            StructureType::Igloo if version.is_older_than(MCVersion::V1_9) => return None,
+            StructureType::Igloo => temple(14357618),
+            StructureType::OceanMonument if version.is_older_than(MCVersion::V1_8) => return None,
+            StructureType::OceanMonument => Placement::Triangular { spacing: 32, separation: 5, salt: 10387313 },
+            StructureType::WoodlandMansion if version.is_older_than(MCVersion::V1_11) => return None,
+            StructureType::WoodlandMansion => Placement::Triangular { spacing: 80, separation: 20, salt: 10387319 },
+            StructureType::BuriedTreasure if pre_1_13 => return None,
+            StructureType::BuriedTreasure => Placement::Chance { salt: 10387320, chance: 0.01 },
+        })
+    }
+}
+
+/// A structure seen at chunk `chunk_x`, `chunk_z`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct KnownStructure {
+    pub structure: StructureType,
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+}
+
+impl KnownStructure {
+    pub fn new(structure: StructureType, chunk_x: i32, chunk_z: i32) -> Self {
+        KnownStructure { structure, chunk_x, chunk_z }
+    }
+}
+
+/// Whether `structure_seed` places `known` where it was seen. Structures
+/// that do not generate in `version` are not checked.
+pub fn structure_matches(structure_seed: i64, known: &KnownStructure, version: MCVersion) -> bool {
+    let Some(placement) = known.structure.placement(version) else {
+        return true;
+    };
+    let mut rand = ChunkRand::new();
+    let (chunk_x, chunk_z) = (known.chunk_x, known.chunk_z);
+
+    match placement {
+        Placement::Chance { salt, chance } => {
+            rand.set_region_seed(structure_seed, chunk_x, chunk_z, salt, version);
+            rand.jrand.next_float() < chance
+        }
+        Placement::Uniform { spacing, separation, salt } | Placement::Triangular { spacing, separation, salt } => {
+            let (region_x, region_z) = (chunk_x.div_euclid(spacing), chunk_z.div_euclid(spacing));
+            rand.set_region_seed(structure_seed, region_x, region_z, salt, version);
+            let bound = spacing - separation;
+            let mut roll = || match placement {
+                Placement::Triangular { .. } => rand.jrand.next_int_triangular(bound),
+                _ => rand.jrand.next_int(bound),
+            };
+            let offset_x = roll();
+            let offset_z = roll();
+            offset_x == chunk_x.rem_euclid(spacing) && offset_z == chunk_z.rem_euclid(spacing)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORLD_SEED: i64 = -4172144997902289642;
+
+    fn matches(structure: StructureType, chunk_x: i32, chunk_z: i32, version: MCVersion) -> bool {
+        structure_matches(WORLD_SEED, &KnownStructure::new(structure, chunk_x, chunk_z), version)
+    }
+
+    #[test]
+    fn test_known_structures() {
+        // Worked out with the Java region placement.
+        let seen = [
+            (StructureType::Village, -17, 66),
+            (StructureType::Village, 96, -127),
+            (StructureType::DesertPyramid, 23, -18),
+            (StructureType::SwampHut, 17, -26),
+            (StructureType::OceanMonument, -54, 51),
+            (StructureType::WoodlandMansion, 128, -67),
+            (StructureType::BuriedTreasure, -39, 30),
+        ];
+        for (structure, x, z) in seen {
+            assert!(matches(structure, x, z, MCVersion::V1_16), "{:?} at {}, {}", structure, x, z);
+            // Only the structure seed matters.
+            let known = KnownStructure::new(structure, x, z);
+            assert!(structure_matches(WORLD_SEED ^ (0x1234 << 48), &known, MCVersion::V1_16));
+            assert!(!structure_matches(123456789, &known, MCVersion::V1_16));
+            assert!(!matches(structure, x, z + 1, MCVersion::V1_16));
+        }
+    }
+
+    #[test]
+    fn test_version_specific_placement() {
+        // Before 1.13 the swamp hut shares the desert pyramid's salt.
+        assert!(matches(StructureType::SwampHut, 23, -18, MCVersion::V1_12));
+        assert!(!matches(StructureType::SwampHut, 17, -26, MCVersion::V1_12));
+        // Structures that do not generate yet are not checked.
+        assert!(matches(StructureType::Igloo, 0, 0, MCVersion::V1_8));
+        assert!(matches(StructureType::WoodlandMansion, 0, 0, MCVersion::V1_10));
+        assert!(matches(StructureType::BuriedTreasure, 0, 0, MCVersion::V1_12));
+    }
+}
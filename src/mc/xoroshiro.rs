@@ -0,0 +1,174 @@
+// Xoroshiro128++ as used by 1.18+ world generation (XoroshiroRandomSource).
+//
+// The 128-bit state is derived from a 64-bit seed through two rounds of
+// Stafford's mix13. Both the mix and the state step are bijections, so the
+// seed can be recovered from a state and the generator can be stepped back,
+// which is what reversing 1.18+ observations builds on.
+
+use crate::math::mth;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+const GOLDEN_RATIO_64: u64 = 0x9E37_79B9_7F4A_7C15;
+const SILVER_RATIO_64: u64 = 0x6A09_E667_F3BC_C909;
+
+const MIX_MULTIPLIER_1: u64 = 0xBF58_476D_1CE4_E5B9;
+const MIX_MULTIPLIER_2: u64 = 0x94D0_49BB_1331_11EB;
+
+/// Port of Mojang's Xoroshiro128PlusPlus plus the random source methods on top.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Xoroshiro {
+    lo: u64,
+    hi: u64,
+}
+
+impl Xoroshiro {
+    /// A generator with the given state. An all-zero state is replaced like in Java.
+    pub fn new(lo: i64, hi: i64) -> Self {
+        if lo == 0 && hi == 0 {
+            return Xoroshiro { lo: GOLDEN_RATIO_64, hi: SILVER_RATIO_64 };
+        }
+        Xoroshiro { lo: lo as u64, hi: hi as u64 }
+    }
+
+    /// The generator `new XoroshiroRandomSource(seed)` creates.
+    pub fn from_seed(seed: i64) -> Self {
+        let lo = seed as u64 ^ SILVER_RATIO_64;
+        let hi = lo.wrapping_add(GOLDEN_RATIO_64);
+        Xoroshiro::new(mix_stafford13(lo as i64), mix_stafford13(hi as i64))
+    }
+
+    /// The `(lo, hi)` state.
+    pub fn state(&self) -> (i64, i64) {
+        (self.lo as i64, self.hi as i64)
+    }
+
+    /// The seed [`from_seed`](Self::from_seed) built this state from, or
+    /// `None` if the state did not come from a seed.
+    pub fn seed(&self) -> Option<i64> {
+        let seed = unmix_stafford13(self.lo as i64) ^ SILVER_RATIO_64 as i64;
+        (Xoroshiro::from_seed(seed) == *self).then_some(seed)
+    }
+
+    pub fn next_long(&mut self) -> i64 {
+        let (s0, mut s1) = (self.lo, self.hi);
+        let result = s0.wrapping_add(s1).rotate_left(17).wrapping_add(s0);
+        s1 ^= s0;
+        self.lo = s0.rotate_left(49) ^ s1 ^ (s1 << 21);
+        self.hi = s1.rotate_left(28);
+        result as i64
+    }
+
+    /// Undo one [`next_long`](Self::next_long) step.
+    pub fn previous(&mut self) {
+        let s1 = self.hi.rotate_right(28);
+        let s0 = (self.lo ^ s1 ^ (s1 << 21)).rotate_right(49);
+        self.lo = s0;
+        self.hi = s1 ^ s0;
+    }
+
+    /// The top `bits` bits of the next long.
+    pub fn next_bits(&mut self, bits: u32) -> i64 {
+        ((self.next_long() as u64) >> (64 - bits)) as i64
+    }
+
+    pub fn next_int(&mut self, bound: i32) -> i32 {
+        if bound <= 0 {
+            panic!("bound must be positive");
+        }
+
+        // Lemire's multiply-and-reject on the low 32 bits
+        let bound = bound as u64;
+        let mut product = (self.next_long() as u32 as u64) * bound;
+        if (product & 0xFFFF_FFFF) < bound {
+            let threshold = (bound as u32).wrapping_neg() as u64 % bound;
+            while (product & 0xFFFF_FFFF) < threshold {
+                product = (self.next_long() as u32 as u64) * bound;
+            }
+        }
+        (product >> 32) as i32
+    }
+
+    pub fn next_float(&mut self) -> f32 {
+        self.next_bits(24) as f32 * 5.960_464_5e-8
+    }
+
+    pub fn next_double(&mut self) -> f64 {
+        self.next_bits(53) as f64 * 1.110_223_024_625_156_5e-16
+    }
+
+    /// The positional factory `forkPositional()` makes, as `(lo, hi)` seeds.
+    pub fn fork_positional(&mut self) -> (i64, i64) {
+        (self.next_long(), self.next_long())
+    }
+
+    /// The generator a positional factory with seeds `(lo, hi)` gives for
+    /// block `x`, `y`, `z`.
+    pub fn at(positional: (i64, i64), x: i32, y: i32, z: i32) -> Self {
+        Xoroshiro::new(block_seed(x, y, z) ^ positional.0, positional.1)
+    }
+}
+
+/// Mojang's `Mth.getSeed`, the hash of a block position.
+pub fn block_seed(x: i32, y: i32, z: i32) -> i64 {
+    let mut seed = (x.wrapping_mul(3129871) as i64) ^ (z as i64).wrapping_mul(116129781) ^ y as i64;
+    seed = seed.wrapping_mul(seed).wrapping_mul(42317861).wrapping_add(seed.wrapping_mul(11));
+    seed >> 16
+}
+
+/// Stafford's mix13, turning a seed into one half of a state.
+pub fn mix_stafford13(seed: i64) -> i64 {
+    let mut z = seed as u64;
+    z = (z ^ (z >> 30)).wrapping_mul(MIX_MULTIPLIER_1);
+    z = (z ^ (z >> 27)).wrapping_mul(MIX_MULTIPLIER_2);
+    (z ^ (z >> 31)) as i64
+}
+
+/// Inverse of [`mix_stafford13`].
+pub fn unmix_stafford13(mixed: i64) -> i64 {
+    let mut z = unshift_right(mixed as u64, 31);
+    z = unshift_right(z.wrapping_mul(inverse_odd(MIX_MULTIPLIER_2)), 27);
+    z = unshift_right(z.wrapping_mul(inverse_odd(MIX_MULTIPLIER_1)), 30);
+    z as i64
+}
+
+/// Inverse of `z ^ (z >> shift)`.
+fn unshift_right(value: u64, shift: u32) -> u64 {
+    let mut z = value;
+    let mut s = shift;
+    while s < 64 {
+        z ^= value >> s;
+        s += shift;
+    }
+    z
+}
+
+/// Multiplicative inverse of an odd number mod 2^64.
+fn inverse_odd(a: u64) -> u64 {
+    mth::mod_inverse_pow2(a as i64, 64).expect("Mix multipliers are odd") as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reversal_round_trip() {
+        for seed in [0i64, 1, -1, 123456789, i64::MIN] {
+            assert_eq!(unmix_stafford13(mix_stafford13(seed)), seed);
+
+            let mut rand = Xoroshiro::from_seed(seed);
+            assert_eq!(rand.seed(), Some(seed));
+            let start = rand.clone();
+            for _ in 0..10 {
+                rand.next_long();
+            }
+            for _ in 0..10 {
+                rand.previous();
+            }
+            assert_eq!(rand, start);
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use crate::lattice::enumerate::SearchError;
+use crate::lcg::lcg::LCG;
+use crate::lcg::rand::{JavaRng, Rand};
+use crate::reverser::filtered_skip::FilteredSkip;
+use crate::reverser::random_reverser::JavaRandomReverser;
+
+/// An observed `java.util.Random` call.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JavaCall {
+    /// `nextInt(bound)` with a result in `min..=max`.
+    NextInt { bound: i32, min: i32, max: i32 },
+    /// `nextInt()` with a result in `min..=max`.
+    NextIntUnbounded { min: i32, max: i32 },
+    /// `nextFloat()` with a result in `min..max`.
+    NextFloat { min: f32, max: f32 },
+    /// `nextFloat()` with the exact result `value`.
+    NextFloatExact { value: f32 },
+    NextBoolean { value: bool },
+}
+
+/// `nextInt(bound)`, to be turned into a [`JavaCall`].
+#[derive(Clone, Copy, Debug)]
+pub struct NextInt(pub i32);
+
+impl NextInt {
+    pub fn equal_to(self, value: i32) -> JavaCall {
+        self.between(value, value)
+    }
+
+    /// A result in `min..=max`.
+    pub fn between(self, min: i32, max: i32) -> JavaCall {
+        JavaCall::NextInt { bound: self.0, min, max }
+    }
+}
+
+/// `nextInt()`, to be turned into a [`JavaCall`].
+#[derive(Clone, Copy, Debug)]
+pub struct NextIntUnbounded;
+
+impl NextIntUnbounded {
+    pub fn equal_to(self, value: i32) -> JavaCall {
+        self.between(value, value)
+    }
+
+    /// A result in `min..=max`.
+    pub fn between(self, min: i32, max: i32) -> JavaCall {
+        JavaCall::NextIntUnbounded { min, max }
+    }
+}
+
+/// `nextFloat()`, to be turned into a [`JavaCall`].
+#[derive(Clone, Copy, Debug)]
+pub struct NextFloat;
+
+impl NextFloat {
+    pub fn equal_to(self, value: f32) -> JavaCall {
+        JavaCall::NextFloatExact { value }
+    }
+
+    /// A result in `min..max`.
+    pub fn between(self, min: f32, max: f32) -> JavaCall {
+        JavaCall::NextFloat { min, max }
+    }
+}
+
+/// `nextBoolean()`, to be turned into a [`JavaCall`].
+#[derive(Clone, Copy, Debug)]
+pub struct NextBoolean;
+
+impl NextBoolean {
+    pub fn equal_to(self, value: bool) -> JavaCall {
+        JavaCall::NextBoolean { value }
+    }
+}
+
+/// The analytic chance that the true seed is found, next to a Monte Carlo
+/// measurement of it, from [`DynamicProgram::check_success_chance`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SuccessChanceCheck {
+    /// [`JavaRandomReverser::success_chance`] of the program.
+    pub predicted: f64,
+    /// Fraction of the sampled seeds the lattice model holds for.
+    pub measured: f64,
+    /// Number of seeds sampled.
+    pub samples: u32,
+}
+
+enum Step {
+    Call(JavaCall),
+    Skip(i64),
+}
+
+/// A LattiCG-style description of a sequence of `java.util.Random` calls, from
+/// which the lattice constraints are built. Every call advances the seed once,
+/// so the returned seeds are the internal seeds before the first call:
+///
+/// ```
+/// # use dungeon_cracker::lcg::lcg::LCG;
+/// # use dungeon_cracker::lcg::rand::{JavaRng, Rand};
+/// use dungeon_cracker::reverser::dynamic_program::{DynamicProgram, NextFloat, NextInt};
+/// # let seed = 0x1234_5678_9ABC;
+/// # let mut rand = Rand::of_internal_seed(&LCG::JAVA, seed);
+/// # let rolls: Vec<i32> = (0..12).map(|_| rand.next_int(16)).collect();
+/// # rand.advance(2);
+/// # let skipped = rand.next_int(4);
+/// # let low = (rand.next_float() * 4.0).floor() / 4.0;
+///
+/// let mut program = DynamicProgram::new();
+/// for roll in rolls {
+///     program = program.add(NextInt(16).equal_to(roll));
+/// }
+/// let seeds = program
+///     .skip(2)
+///     .filtered_skip(move |r| r.next_int(4) == skipped, 1)
+///     .add(NextFloat.between(low, low + 0.25))
+///     .reverse()
+///     .unwrap();
+/// assert!(seeds.contains(&seed));
+/// ```
+#[derive(Default)]
+pub struct DynamicProgram {
+    steps: Vec<Step>,
+    filtered_skips: Vec<FilteredSkip>,
+    index: i64,
+}
+
+impl DynamicProgram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Observe the next call.
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(mut self, call: JavaCall) -> Self {
+        self.steps.push(Step::Call(call));
+        self.index += 1;
+        self
+    }
+
+    /// Skip `count` unobserved calls.
+    pub fn skip(mut self, count: i64) -> Self {
+        self.steps.push(Step::Skip(count));
+        self.index += count;
+        self
+    }
+
+    /// Skip `count` calls, keeping only seeds for which `filter` holds on the
+    /// RNG at the first of them. Filters are checked after enumeration.
+    pub fn filtered_skip(
+        mut self,
+        filter: impl Fn(&mut dyn JavaRng) -> bool + Send + Sync + 'static,
+        count: i64,
+    ) -> Self {
+        self.filtered_skips.push(FilteredSkip::new(self.index, Box::new(filter)));
+        self.skip(count)
+    }
+
+    /// Number of calls the program spans.
+    pub fn call_count(&self) -> i64 {
+        self.index
+    }
+
+    /// The reverser holding the program's constraints, for callers that
+    /// estimate or split the enumeration themselves.
+    pub fn reverser(mut self) -> JavaRandomReverser {
+        let filtered_skips = std::mem::take(&mut self.filtered_skips);
+        self.constraints(filtered_skips)
+    }
+
+    /// Replay the observed calls on `samples` random seeds, drawn from a
+    /// `java.util.Random` seeded with `sample_seed`, using Java's own
+    /// `nextInt`, and count the seeds for which every call advances the seed
+    /// exactly once, as the lattice assumes. A measurement far from the
+    /// prediction means the call sequence does not match the model.
+    pub fn check_success_chance(&self, samples: u32, sample_seed: i64) -> SuccessChanceCheck {
+        let predicted = self.constraints(Vec::new()).success_chance();
+        let expected_end = LCG::JAVA.combine(self.index);
+        let mut sampler = Rand::of_seed_scrambled(&LCG::JAVA, sample_seed);
+
+        let mut held = 0u32;
+        for _ in 0..samples {
+            let start = sampler.next_long();
+            let mut rand = Rand::of_internal_seed(&LCG::JAVA, start);
+            for step in &self.steps {
+                match *step {
+                    Step::Call(JavaCall::NextInt { bound, .. }) => {
+                        rand.next_int(bound);
+                    }
+                    Step::Call(JavaCall::NextFloat { .. } | JavaCall::NextFloatExact { .. }) => {
+                        rand.next_float();
+                    }
+                    Step::Call(JavaCall::NextIntUnbounded { .. }) => {
+                        rand.next(32);
+                    }
+                    Step::Call(JavaCall::NextBoolean { .. }) => {
+                        rand.next_boolean();
+                    }
+                    Step::Skip(count) => rand.advance(count),
+                }
+            }
+            if rand.get_seed() == expected_end.next_seed(start) {
+                held += 1;
+            }
+        }
+
+        SuccessChanceCheck {
+            predicted,
+            measured: if samples == 0 { 1.0 } else { held as f64 / samples as f64 },
+            samples,
+        }
+    }
+
+    /// A reverser with the program's constraints and `filtered_skips`.
+    fn constraints(&self, filtered_skips: Vec<FilteredSkip>) -> JavaRandomReverser {
+        let mut reverser = JavaRandomReverser::new(filtered_skips);
+        for step in &self.steps {
+            match *step {
+                Step::Call(JavaCall::NextInt { bound, min, max }) => reverser.add_next_int_call(bound, min, max),
+                Step::Call(JavaCall::NextIntUnbounded { min, max }) => reverser.add_next_int_unbounded_call(min, max),
+                Step::Call(JavaCall::NextFloat { min, max }) => reverser.add_next_float_call(min, max),
+                Step::Call(JavaCall::NextFloatExact { value }) => reverser.add_next_float_call_exact(value),
+                Step::Call(JavaCall::NextBoolean { value }) => {
+                    // nextBoolean() is the top bit, like nextInt(2)
+                    let bit = value as i32;
+                    reverser.add_next_int_call(2, bit, bit)
+                }
+                Step::Skip(count) => reverser.add_unmeasured_seeds(count),
+            }
+        }
+        reverser
+    }
+
+    /// All internal seeds before the first call that fit the program.
+    pub fn reverse(self) -> Result<Vec<i64>, SearchError> {
+        self.reverser().find_all_valid_seeds()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reverse_known_seed() {
+        let seed = 0xBEEF_CAFE_1234;
+        let mut rand = Rand::of_internal_seed(&LCG::JAVA, seed);
+        let float = rand.next_float();
+        let skipped = rand.next_int(3);
+        let rolls: Vec<i32> = (0..4).map(|_| rand.next_int(64)).collect();
+
+        let program = rolls.iter().fold(
+            DynamicProgram::new()
+                .add(NextFloat.equal_to(float))
+                .filtered_skip(move |r| r.next_int(3) == skipped, 1),
+            |program, &roll| program.add(NextInt(64).equal_to(roll)),
+        );
+        assert_eq!(program.call_count(), 6);
+        assert_eq!(program.reverse().unwrap(), vec![seed]);
+    }
+
+    #[test]
+    fn test_success_chance_matches_measurement() {
+        // 2^31 mod 1.5e9 leaves nextInt a 30% chance to reject per call.
+        let program = DynamicProgram::new()
+            .add(NextInt(1_500_000_000).between(0, 749_999_999))
+            .add(NextInt(3).equal_to(1))
+            .add(NextInt(1_500_000_000).between(0, 1_499_999_999));
+        let check = program.check_success_chance(4000, 42);
+        assert!(check.predicted < 0.5, "predicted {}", check.predicted);
+        assert!((check.predicted - check.measured).abs() < 0.03, "{:?}", check);
+    }
+}
@@ -1,17 +1,27 @@
-use crate::lcg::lcg::LCG;
-use crate::lcg::rand::Rand;
+use crate::lcg::lcg::LCG128;
+use crate::lcg::rand::{JavaRng, Rand};
+
+/// Predicate on the RNG state at a filtered skip.
+pub type SkipFilter = dyn Fn(&mut dyn JavaRng) -> bool + Send + Sync;
 
 /// A filtered skip in the random call sequence.
 /// Stores the combined LCG at this skip position and a predicate to test state.
+/// The predicate sees a [`JavaRng`], so the same closure also runs on a `JRand`.
 pub struct FilteredSkip {
-    pub skip_lcg: LCG,
-    pub filter: Box<dyn Fn(&mut Rand) -> bool + Send + Sync>,
+    pub skip_lcg: LCG128,
+    pub filter: Box<SkipFilter>,
 }
 
 impl FilteredSkip {
-    pub fn new(current_index: i64, filter: Box<dyn Fn(&mut Rand) -> bool + Send + Sync>) -> Self {
+    pub fn new(current_index: i64, filter: Box<SkipFilter>) -> Self {
+        Self::with_lcg(LCG128::JAVA, current_index, filter)
+    }
+
+    /// A filtered skip for a reverser over `lcg`, an [`LCG`](crate::lcg::lcg::LCG)
+    /// or an [`LCG128`].
+    pub fn with_lcg(lcg: impl Into<LCG128>, current_index: i64, filter: Box<SkipFilter>) -> Self {
         FilteredSkip {
-            skip_lcg: LCG::JAVA.combine(current_index),
+            skip_lcg: lcg.into().combine(current_index),
             filter,
         }
     }
@@ -1,2 +1,5 @@
 pub mod random_reverser;
 pub mod filtered_skip;
+pub mod dynamic_program;
+
+pub use dynamic_program::DynamicProgram;
@@ -1,37 +1,110 @@
-use crate::lcg::lcg::LCG;
+use crate::cancel::CancelToken;
+use crate::lcg::lcg::LCG128;
 use crate::lcg::rand::Rand;
-use crate::lattice::enumerate;
+use crate::lattice::babai;
+use crate::lattice::enumerate::{
+    self, BranchProgress, EnumerationBackend, EnumerationStats, LimitedResults, SearchError, SearchLimits,
+};
 use crate::lattice::lll;
 use crate::math::big_fraction::{BigFraction, FracOps};
 use crate::math::big_matrix::BigMatrix;
 use crate::math::big_vector::BigVector;
+use crate::math::error::MathError;
 use crate::math::int_type::{Int, IntOps};
-use crate::math::lu_decomposition;
+use crate::math::lu_decomposition::{self, LuDecomposition};
 use crate::math::mth;
 use crate::reverser::filtered_skip::FilteredSkip;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+/// Everything a [`JavaRandomReverser`] needs to enumerate after LLL, from
+/// [`prepared`](JavaRandomReverser::prepared). A coordinator reduces once and
+/// ships this to workers, which either rebuild the reverser with
+/// [`from_prepared`](JavaRandomReverser::from_prepared) or, having built the
+/// same constraints themselves, skip the reduction with
+/// [`set_prepared`](JavaRandomReverser::set_prepared). Big integers are
+/// decimal strings, identical for both integer backends.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PreparedReverser {
+    pub(crate) lcg: LCG128,
+    pub(crate) mins: Vec<String>,
+    pub(crate) maxes: Vec<String>,
+    pub(crate) call_indices: Vec<i64>,
+    pub(crate) scrambled_bounds: Vec<(i64, u64, u64)>,
+    pub(crate) success_chance: f64,
+    /// Rows of the LLL-reduced basis.
+    pub(crate) basis: Vec<Vec<String>>,
+}
+
+/// Where the work and time of a [`JavaRandomReverser`] search went.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// Work done by the lattice enumeration.
+    pub enumeration: EnumerationStats,
+    /// Lattice points found, before the filtered skips.
+    pub candidates: usize,
+    /// Seeds left after the filtered skips.
+    pub seeds: usize,
+    /// Time spent on LLL reduction; zero if the basis was already reduced.
+    pub reduction_time: Duration,
+    /// Time spent enumerating lattice points, filtering excluded.
+    pub enumeration_time: Duration,
+    /// Time spent turning lattice points into seeds and filtering them.
+    pub filter_time: Duration,
+}
 
 /// Combined RandomReverser + JavaRandomReverser.
 /// Builds lattice constraints from java.util.Random call observations,
 /// then uses LLL reduction + enumeration to find matching seeds.
+///
+/// Constraints may still be added after a search; the next search reduces
+/// the extended lattice again.
 pub struct JavaRandomReverser {
     modulus: Int,
     mult: Int,
-    lcg: LCG,
+    lcg: LCG128,
     mins: Vec<Int>,
     maxes: Vec<Int>,
     call_indices: Vec<i64>,
     filtered_skips: Vec<FilteredSkip>,
+    /// Exact `(call index, min, max)` bounds of scrambled seed constraints
+    /// that the lattice only covers loosely.
+    scrambled_bounds: Vec<(i64, u64, u64)>,
+    /// Generators of the constraint lattice, one column per dimension.
     lattice: Option<BigMatrix>,
+    /// LLL-reduced basis of `lattice`, dropped whenever a constraint is added.
+    reduced: Option<BigMatrix>,
     current_call_index: i64,
     dimensions: usize,
     success_chance: f64,
+    backend: EnumerationBackend,
 }
 
 impl JavaRandomReverser {
     pub fn new(filtered_skips: Vec<FilteredSkip>) -> Self {
-        let lcg = LCG::JAVA;
-        let modulus = Int::int_from_i64(lcg.modulus);
-        let mult = Int::int_from_i64(lcg.multiplier).int_rem(&modulus);
+        Self::with_lcg(LCG128::JAVA, filtered_skips)
+    }
+
+    /// A reverser for another LCG with a power-of-two modulus of up to 2^64,
+    /// an [`LCG`](crate::lcg::lcg::LCG) such as the 48-bit generators of mods
+    /// and other games, or an [`LCG128`] such as [`LCG128::MMIX`]. The call
+    /// helpers read `next(bits)` as the top `bits` bits of the seed, like
+    /// Java; build `filtered_skips` with [`FilteredSkip::with_lcg`] for the
+    /// same LCG. Seeds of a 64-bit LCG are returned as `i64` bit patterns.
+    pub fn with_lcg(lcg: impl Into<LCG128>, filtered_skips: Vec<FilteredSkip>) -> Self {
+        let lcg = lcg.into();
+        assert!(
+            lcg.modulus.is_power_of_two() && lcg.modulus <= 1 << 64,
+            "LCG modulus {} is not a power of two of at most 64 bits",
+            lcg.modulus
+        );
+        let modulus = Int::from(lcg.modulus);
+        let mult = Int::from(lcg.multiplier).int_rem(&modulus);
         JavaRandomReverser {
             modulus,
             mult,
@@ -40,21 +113,39 @@ impl JavaRandomReverser {
             maxes: Vec::new(),
             call_indices: Vec::new(),
             filtered_skips,
+            scrambled_bounds: Vec::new(),
             lattice: None,
+            reduced: None,
             current_call_index: 0,
             dimensions: 0,
             success_chance: 1.0,
+            backend: EnumerationBackend::default(),
         }
     }
 
-    /// Add a constraint on the measured seed value (in internal 48-bit representation).
+    /// Choose how full searches enumerate the reduced lattice. The partial
+    /// and per-branch searches always use the LP backend, whose branch
+    /// numbering the workers share.
+    pub fn set_enumeration_backend(&mut self, backend: EnumerationBackend) {
+        self.backend = backend;
+    }
+
+    /// Add a constraint on the measured seed value (in internal representation).
     pub fn add_measured_seed(&mut self, min: i64, max: i64) {
         self.add_measured_seed_big(Int::int_from_i64(min), Int::int_from_i64(max));
     }
 
     pub fn add_measured_seed_big(&mut self, min: Int, max: Int) {
-        let min = mod_big(&min, &self.modulus);
-        let mut max = mod_big(&max, &self.modulus);
+        self.current_call_index += 1;
+        self.constrain_current_seed(min, max);
+    }
+
+    /// Constrain the internal seed at the current call index, without
+    /// advancing it.
+    fn constrain_current_seed(&mut self, min: Int, max: Int) {
+        self.assert_extensible();
+        let min = mth::mod_int(&min, &self.modulus);
+        let mut max = mth::mod_int(&max, &self.modulus);
         if max < min {
             max = max.int_add(&self.modulus);
         }
@@ -62,7 +153,6 @@ impl JavaRandomReverser {
         self.mins.push(min);
         self.maxes.push(max);
         self.dimensions += 1;
-        self.current_call_index += 1;
         self.call_indices.push(self.current_call_index);
 
         let dim = self.dimensions;
@@ -72,7 +162,7 @@ impl JavaRandomReverser {
             if let Some(ref old_lattice) = self.lattice {
                 for row in 0..dim {
                     for col in 0..(dim - 1) {
-                        new_lattice.set(row, col, old_lattice.get(row, col).clone());
+                        new_lattice[(row, col)] = old_lattice[(row, col)].clone();
                     }
                 }
             }
@@ -80,9 +170,10 @@ impl JavaRandomReverser {
 
         let exp = Int::int_from_i64(self.call_indices[dim - 1] - self.call_indices[0]);
         let temp_mult = self.mult.int_modpow(&exp, &self.modulus);
-        new_lattice.set(0, dim - 1, BigFraction::frac_from_bigint(temp_mult));
-        new_lattice.set(dim, dim - 1, BigFraction::frac_from_bigint(self.modulus.clone()));
+        new_lattice[(0, dim - 1)] = BigFraction::frac_from_bigint(temp_mult);
+        new_lattice[(dim, dim - 1)] = BigFraction::frac_from_bigint(self.modulus.clone());
         self.lattice = Some(new_lattice);
+        self.reduced = None;
     }
 
     /// Add a constraint on the seed modulo a different modulus.
@@ -95,8 +186,9 @@ impl JavaRandomReverser {
     }
 
     pub fn add_modulo_measured_seed_big(&mut self, min: Int, max: Int, measured_mod: Int) {
-        let min = mod_big(&min, &measured_mod);
-        let mut max = mod_big(&max, &measured_mod);
+        self.assert_extensible();
+        let min = mth::mod_int(&min, &measured_mod);
+        let mut max = mth::mod_int(&max, &measured_mod);
         if max < min {
             max = max.int_add(&measured_mod);
         }
@@ -125,7 +217,7 @@ impl JavaRandomReverser {
                 if let Some(ref old) = self.lattice {
                     for row in 0..(dim - 1) {
                         for col in 0..(dim - 2) {
-                            new_lattice.set(row, col, old.get(row, col).clone());
+                            new_lattice[(row, col)] = old[(row, col)].clone();
                         }
                     }
                 }
@@ -133,12 +225,13 @@ impl JavaRandomReverser {
 
             let exp = Int::int_from_i64(self.call_indices[dim - 1] - self.call_indices[0]);
             let temp_mult = self.mult.int_modpow(&exp, &self.modulus);
-            new_lattice.set(0, dim - 2, BigFraction::frac_from_bigint(temp_mult.clone()));
-            new_lattice.set(0, dim - 1, BigFraction::frac_from_bigint(temp_mult));
-            new_lattice.set(dim - 1, dim - 1, BigFraction::frac_from_bigint(self.modulus.clone()));
-            new_lattice.set(dim - 1, dim - 2, BigFraction::frac_from_bigint(self.modulus.clone()));
-            new_lattice.set(dim, dim - 1, BigFraction::frac_from_bigint(measured_mod));
+            new_lattice[(0, dim - 2)] = BigFraction::frac_from_bigint(temp_mult.clone());
+            new_lattice[(0, dim - 1)] = BigFraction::frac_from_bigint(temp_mult);
+            new_lattice[(dim - 1, dim - 1)] = BigFraction::frac_from_bigint(self.modulus.clone());
+            new_lattice[(dim - 1, dim - 2)] = BigFraction::frac_from_bigint(self.modulus.clone());
+            new_lattice[(dim, dim - 1)] = BigFraction::frac_from_bigint(measured_mod);
             self.lattice = Some(new_lattice);
+            self.reduced = None;
         } else {
             // Modulus divides evenly
             self.mins.push(min);
@@ -154,7 +247,7 @@ impl JavaRandomReverser {
                 if let Some(ref old) = self.lattice {
                     for row in 0..dim {
                         for col in 0..(dim - 1) {
-                            new_lattice.set(row, col, old.get(row, col).clone());
+                            new_lattice[(row, col)] = old[(row, col)].clone();
                         }
                     }
                 }
@@ -162,17 +255,53 @@ impl JavaRandomReverser {
 
             let exp = Int::int_from_i64(self.call_indices[dim - 1] - self.call_indices[0]);
             let temp_mult = self.mult.int_modpow(&exp, &self.modulus);
-            new_lattice.set(0, dim - 1, BigFraction::frac_from_bigint(temp_mult));
-            new_lattice.set(dim, dim - 1, BigFraction::frac_from_bigint(measured_mod));
+            new_lattice[(0, dim - 1)] = BigFraction::frac_from_bigint(temp_mult);
+            new_lattice[(dim, dim - 1)] = BigFraction::frac_from_bigint(measured_mod);
             self.lattice = Some(new_lattice);
+            self.reduced = None;
         }
     }
 
+    /// Reversers from [`from_prepared`](Self::from_prepared) only have the
+    /// reduced basis, not the generators new constraints extend.
+    fn assert_extensible(&self) {
+        assert!(
+            self.dimensions == 0 || self.lattice.is_some(),
+            "constraints cannot be added to a reverser rebuilt from a PreparedReverser"
+        );
+    }
+
     /// Skip some unmeasured seeds (advance the call index without adding constraints).
     pub fn add_unmeasured_seeds(&mut self, num_seeds: i64) {
         self.current_call_index += num_seeds;
     }
 
+    /// Constrain the value that would be passed to `setSeed` to reach the
+    /// current state, i.e. the internal seed before the `^ multiplier`
+    /// scramble, to `min..=max`. Does not advance the call index.
+    ///
+    /// Ranges covering whole blocks of low bits (a known seed with a few
+    /// unknown low bits) stay a single lattice constraint. Other ranges are
+    /// widened to such a block and the exact bounds are checked on the results.
+    ///
+    /// For a 64-bit LCG the bounds are read as unsigned.
+    pub fn add_scrambled_seed_constraint(&mut self, min: i64, max: i64) {
+        let mask = (self.lcg.modulus - 1) as u64;
+        let scramble = self.lcg.multiplier as u64 & mask;
+        let (min, max) = (min as u64 & mask, max as u64 & mask);
+        assert!(min <= max, "empty scrambled seed range {}..={}", min, max);
+
+        // XOR with the scramble keeps an aligned block of low bits contiguous.
+        let free_bits = 64 - (min ^ max).leading_zeros();
+        let low = ((1u128 << free_bits) - 1) as u64;
+        let block = (min ^ scramble) & !low;
+        self.constrain_current_seed(Int::from(block), Int::from(block | low));
+
+        if min & low != 0 || max & low != low {
+            self.scrambled_bounds.push((self.current_call_index, min, max));
+        }
+    }
+
     /// Get the current number of lattice dimensions.
     pub fn dimensions(&self) -> usize {
         self.dimensions
@@ -183,42 +312,133 @@ impl JavaRandomReverser {
         self.success_chance
     }
 
+    /// Inclusive lower bound of each dimension, in internal seed units.
+    pub fn mins(&self) -> &[Int] {
+        &self.mins
+    }
+
+    /// Inclusive upper bound of each dimension, in internal seed units.
+    pub fn maxes(&self) -> &[Int] {
+        &self.maxes
+    }
+
+    /// LCG call index each dimension constrains. The two dimensions of a
+    /// `nextInt` call with a non-power-of-two bound share an index.
+    pub fn call_indices(&self) -> &[i64] {
+        &self.call_indices
+    }
+
+    /// The lattice generators, one row per generator and one column per
+    /// dimension, scaled as they are handed to LLL. `None` without constraints.
+    pub fn scaled_lattice(&self) -> Option<BigMatrix> {
+        let lattice = self.lattice.as_ref()?;
+        Some(lattice * &self.scales())
+    }
+
+    /// The LLL-reduced basis (unscaled), if the lattice has been reduced.
+    pub fn reduced_lattice(&self) -> Option<&BigMatrix> {
+        self.reduced.as_ref()
+    }
+
     // ---- JavaRandomReverser-specific methods ----
 
+    /// Number of low seed bits `next(bits)` drops: 48 - `bits` for Java.
+    fn dropped_bits(&self, bits: u32) -> u32 {
+        let seed_bits = self.lcg.modulus.trailing_zeros();
+        assert!(seed_bits >= bits, "next({}) needs a seed of at least {} bits", bits, bits);
+        seed_bits - bits
+    }
+
     /// Add a nextInt(n) call with known result (min == max) or range.
     pub fn add_next_int_call(&mut self, n: i32, min: i32, max: i32) {
         assert!(n > 0, "nextInt bound must be positive");
 
         if (n & (-n)) == n {
             // n is a power of 2
-            let log = n.trailing_zeros() as i64;
-            self.add_measured_seed(
-                min as i64 * (1i64 << (48 - log)),
-                max as i64 * (1i64 << (48 - log)) + (1i64 << (48 - log)) - 1,
-            );
+            let unit = 1i128 << self.dropped_bits(n.trailing_zeros());
+            self.add_measured_seed_big(Int::from(min as i128 * unit), Int::from(max as i128 * unit + unit - 1));
         } else {
-            self.add_modulo_measured_seed(
-                min as i64 * (1i64 << 17),
-                (max as i64 * (1i64 << 17)) | 0x1ffff,
-                n as i64 * (1i64 << 17),
+            let unit = 1i128 << self.dropped_bits(31);
+            self.add_modulo_measured_seed_big(
+                Int::from(min as i128 * unit),
+                Int::from(max as i128 * unit + unit - 1),
+                Int::from(n as i128 * unit),
             );
         }
     }
 
     /// Add a nextInt() call (unbounded 32-bit) with known range.
     pub fn add_next_int_unbounded_call(&mut self, min: i32, max: i32) {
-        self.add_measured_seed(
-            min as i64 * (1i64 << 16),
-            max as i64 * (1i64 << 16) + (1i64 << 16) - 1,
+        let unit = 1i128 << self.dropped_bits(32);
+        self.add_measured_seed_big(Int::from(min as i128 * unit), Int::from(max as i128 * unit + unit - 1));
+    }
+
+    /// Add a nextFloat() call with a result in `min..max` (min inclusive, max exclusive).
+    pub fn add_next_float_call(&mut self, min: f32, max: f32) {
+        // nextFloat() is next(24) / 2^24, so the range maps to a range of the top 24 bits.
+        let shift = self.dropped_bits(24);
+        let scale = (1i64 << 24) as f64;
+        let low = ((min as f64 * scale).ceil() as i64).max(0);
+        let high = ((max as f64 * scale).ceil() as i64 - 1).min((1i64 << 24) - 1);
+        assert!(low <= high, "no float in {}..{} is a nextFloat result", min, max);
+        let unit = 1i128 << shift;
+        self.add_measured_seed_big(Int::from(low as i128 * unit), Int::from((high as i128 + 1) * unit - 1));
+    }
+
+    /// Add a nextFloat() call with the exact result `value`.
+    pub fn add_next_float_call_exact(&mut self, value: f32) {
+        let shift = self.dropped_bits(24);
+        let scaled = value as f64 * (1i64 << 24) as f64;
+        assert!(
+            (0.0..1.0).contains(&value) && scaled.fract() == 0.0,
+            "{} is not a nextFloat result",
+            value
+        );
+        let unit = 1i128 << shift;
+        let bits = scaled as i128;
+        self.add_measured_seed_big(Int::from(bits * unit), Int::from((bits + 1) * unit - 1));
+    }
+
+    /// Add a call whose internal seed has bits `low_bit..high_bit` (as an
+    /// integer) in `min..=max`, e.g. bits 16..32 known from some other output.
+    /// Constrains the seed modulo `2^high_bit`; lower bits stay free.
+    pub fn add_seed_bits_call(&mut self, low_bit: u32, high_bit: u32, min: i64, max: i64) {
+        let seed_bits = self.lcg.modulus.trailing_zeros();
+        assert!(
+            low_bit < high_bit && high_bit <= seed_bits,
+            "bit window {}..{} is outside the {}-bit seed",
+            low_bit,
+            high_bit,
+            seed_bits
+        );
+        let width = high_bit - low_bit;
+        assert!(
+            0 <= min && min <= max && max >> width == 0,
+            "{}..={} does not fit in {} bits",
+            min,
+            max,
+            width
+        );
+        if self.dimensions == 0 {
+            // Results are read back from the first dimension, so it must span
+            // the whole seed rather than its low `high_bit` bits.
+            self.constrain_current_seed(Int::int_zero(), Int::from(self.lcg.modulus - 1));
+        }
+        let unit = 1i128 << low_bit;
+        self.add_modulo_measured_seed_big(
+            Int::from(min as i128 * unit),
+            Int::from((max as i128 + 1) * unit - 1),
+            Int::from(1i128 << high_bit),
         );
     }
 
     /// Consume nextInt calls without observing them.
     pub fn consume_next_int_calls(&mut self, num_calls: i32, bound: i32) {
-        let residue = (1i64 << 48) % ((1i64 << 17) * bound as i64);
+        let shift = self.dropped_bits(31);
+        let residue = self.lcg.modulus % ((1u128 << shift) * bound as u128);
         if residue != 0 {
             self.success_chance *= f64::powi(
-                1.0 - residue as f64 / (1i64 << 48) as f64,
+                1.0 - residue as f64 / self.lcg.modulus as f64,
                 num_calls,
             );
         }
@@ -226,60 +446,317 @@ impl JavaRandomReverser {
     }
 
     /// Find all valid seeds by building the lattice, reducing with LLL, and enumerating.
-    pub fn find_all_valid_seeds(&mut self) -> Vec<i64> {
+    pub fn find_all_valid_seeds(&mut self) -> Result<Vec<i64>, SearchError> {
+        self.find_all_valid_seeds_with_progress(&CancelToken::new(), &mut |_, _| {})
+    }
+
+    /// Same as [`find_all_valid_seeds`](Self::find_all_valid_seeds), but reports
+    /// depth-0 enumeration progress through `on_branch`, together with the
+    /// valid seeds found in the branch that just finished. The first report
+    /// (`branches_done == 0`) is made once LLL reduction has finished.
+    ///
+    /// If `cancel` is triggered, enumeration stops and the seeds found so far
+    /// are returned.
+    pub fn find_all_valid_seeds_with_progress(
+        &mut self,
+        cancel: &CancelToken,
+        on_branch: &mut dyn FnMut(BranchProgress, &[i64]),
+    ) -> Result<Vec<i64>, SearchError> {
+        Ok(self.search(cancel, on_branch)?.0)
+    }
+
+    /// Same as [`find_all_valid_seeds`](Self::find_all_valid_seeds), also
+    /// returning how much work each phase of the search did.
+    pub fn find_all_valid_seeds_with_stats(&mut self) -> Result<(Vec<i64>, SearchStats), SearchError> {
+        self.search(&CancelToken::new(), &mut |_, _| {})
+    }
+
+    /// Reduce, enumerate and filter, measuring each phase.
+    fn search(
+        &mut self,
+        cancel: &CancelToken,
+        on_branch: &mut dyn FnMut(BranchProgress, &[i64]),
+    ) -> Result<(Vec<i64>, SearchStats), SearchError> {
+        let mut stats = SearchStats::default();
         if self.dimensions == 0 {
             // Degenerate: no constraints
-            return (0..self.lcg.modulus).collect();
+            let seeds: Vec<i64> = self.all_seeds().collect();
+            stats.seeds = seeds.len();
+            return Ok((seeds, stats));
         }
 
         verbose_eprintln!("[lattice]   Creating lattice ({} dimensions)...", self.dimensions);
-        self.create_lattice();
+        let (reduced, reduction_time) = timed(|| self.create_lattice());
+        reduced?;
+        stats.reduction_time = reduction_time;
         verbose_eprintln!("[lattice]   Lattice created and LLL-reduced.");
 
         let (lattice, lower, upper, offset) = self.prepare_enumerate_params();
 
         verbose_eprintln!("[lattice]   Enumerating lattice points...");
-        let results = enumerate::enumerate_bounds(&lattice, &lower, &upper, &offset);
+        // Filter each branch as it finishes so `on_branch` only sees valid seeds.
+        let mut seeds = Vec::new();
+        let mut filter_time = Duration::ZERO;
+        let (enumerated, total_time) = timed(|| {
+            enumerate::enumerate_bounds_with_backend(
+                &lattice, &lower, &upper, &offset, self.backend, cancel,
+                &mut |progress, points| {
+                    let start = seeds.len();
+                    if !points.is_empty() {
+                        let (valid, elapsed) = timed(|| self.filter_results(points));
+                        seeds.extend(valid);
+                        filter_time += elapsed;
+                    }
+                    on_branch(progress, &seeds[start..]);
+                },
+            )
+        });
+        let (results, enumeration) = enumerated?;
         verbose_eprintln!("[lattice]   Enumeration found {} candidate(s).", results.len());
 
-        self.filter_results(&results)
+        stats.enumeration = enumeration;
+        stats.candidates = results.len();
+        stats.seeds = seeds.len();
+        stats.enumeration_time = total_time.saturating_sub(filter_time);
+        stats.filter_time = filter_time;
+        Ok((seeds, stats))
+    }
+
+    /// Same as [`find_all_valid_seeds`](Self::find_all_valid_seeds), but stops
+    /// once one of `limits` is reached or `stop_when` returns true for a valid
+    /// seed (which is still returned), flagging the result as truncated.
+    pub fn find_valid_seeds_limited(
+        &mut self,
+        limits: &SearchLimits,
+        stop_when: &mut dyn FnMut(i64) -> bool,
+    ) -> Result<LimitedResults<i64>, SearchError> {
+        enumerate::collect_limited(limits, &mut |&seed| stop_when(seed), |cancel, sink| {
+            if self.dimensions == 0 {
+                // Degenerate: every seed is valid, so hand them over one by one.
+                for seed in self.all_seeds() {
+                    if cancel.is_cancelled() {
+                        break;
+                    }
+                    sink(&[seed]);
+                }
+                return Ok(());
+            }
+            self.find_all_valid_seeds_with_progress(cancel, &mut |_, seeds| sink(seeds)).map(drop)
+        })
+    }
+
+    /// Fast mode for constraints expected to pin down a single seed: try the
+    /// Babai nearest-plane point of the reduced lattice first (see
+    /// [`babai::nearest_point`]) and return its seed if it lies in the box
+    /// and passes the filtered skips. Otherwise falls back to
+    /// [`find_all_valid_seeds`](Self::find_all_valid_seeds).
+    ///
+    /// When the quick point verifies, any other valid seeds are not looked
+    /// for, so only use this where
+    /// [`estimate_solution_count`](Self::estimate_solution_count) is well
+    /// below one.
+    pub fn find_valid_seeds_fast(&mut self) -> Result<Vec<i64>, SearchError> {
+        if self.dimensions == 0 {
+            return self.find_all_valid_seeds();
+        }
+        self.create_lattice()?;
+        let (lattice, lower, upper, offset) = self.prepare_enumerate_params();
+        if let Some(point) = babai::nearest_point(&lattice, &lower, &upper, &offset) {
+            let seeds = self.filter_results(&[point]);
+            if !seeds.is_empty() {
+                return Ok(seeds);
+            }
+        }
+        verbose_eprintln!("[lattice]   Nearest-plane point did not verify, enumerating.");
+        self.find_all_valid_seeds()
+    }
+
+    /// Expected number of enumeration candidates (lattice points inside the
+    /// constraint box, before filtered skips) for random observations, by the
+    /// Gaussian heuristic: box volume over the lattice determinant. Needs
+    /// neither LLL reduction nor enumeration, so it is cheap enough to refuse
+    /// infeasible searches before starting them.
+    pub fn estimate_solution_count(&self) -> f64 {
+        if self.dimensions == 0 {
+            return self.lcg.modulus as f64;
+        }
+
+        let log_volume: f64 = self
+            .mins
+            .iter()
+            .zip(&self.maxes)
+            .map(|(min, max)| max.int_sub(min).int_add_i64(1).int_to_f64_approx().log2())
+            .sum();
+        let generators = self.lattice.as_ref().or(self.reduced.as_ref()).unwrap();
+        let log_det = lattice_det(generators).int_log2_approx();
+        (log_volume - log_det).exp2()
     }
 
     /// Get the number of depth-0 branches for parallel enumeration.
     /// Must be called after create_lattice().
-    pub fn get_branch_count(&mut self) -> i64 {
+    pub fn get_branch_count(&mut self) -> Result<i64, SearchError> {
         if self.dimensions == 0 {
-            return 1;
+            return Ok(1);
         }
-        self.create_lattice();
+        self.create_lattice()?;
         let (lattice, lower, upper, offset) = self.prepare_enumerate_params();
         enumerate::get_branch_count(&lattice, &lower, &upper, &offset)
     }
 
+    /// Estimated work of each depth-0 branch, in branch order (see
+    /// [`enumerate::get_branch_weights`]). Hand the ranges of
+    /// [`enumerate::balanced_branch_ranges`] to workers instead of equal
+    /// branch counts.
+    pub fn get_branch_weights(&mut self) -> Result<Vec<u64>, SearchError> {
+        if self.dimensions == 0 {
+            return Ok(vec![1]);
+        }
+        self.create_lattice()?;
+        let (lattice, lower, upper, offset) = self.prepare_enumerate_params();
+        enumerate::get_branch_weights(&lattice, &lower, &upper, &offset)
+    }
+
     /// Find valid seeds for a subset of depth-0 branches [branch_start, branch_end).
     /// Each worker calls this with a different range.
-    pub fn find_seeds_for_branches(&mut self, branch_start: i64, branch_end: i64) -> Vec<i64> {
+    pub fn find_seeds_for_branches(&mut self, branch_start: i64, branch_end: i64) -> Result<Vec<i64>, SearchError> {
         if self.dimensions == 0 {
             if branch_start == 0 {
-                return (0..self.lcg.modulus).collect();
+                return Ok(self.all_seeds().collect());
             }
-            return vec![];
+            return Ok(vec![]);
         }
 
-        self.create_lattice();
+        self.create_lattice()?;
         let (lattice, lower, upper, offset) = self.prepare_enumerate_params();
 
         verbose_eprintln!("[lattice]   Enumerating branches [{}, {})...", branch_start, branch_end);
         let results = enumerate::enumerate_bounds_partial(
             &lattice, &lower, &upper, &offset, branch_start, branch_end,
-        );
+        )?;
+        verbose_eprintln!("[lattice]   Partial enumeration found {} candidate(s).", results.len());
+
+        Ok(self.filter_results(&results))
+    }
+
+    /// Reduce the lattice if needed and export the state partial enumeration
+    /// needs. Filtered skips are closures and are not part of it.
+    pub fn prepared(&mut self) -> Result<PreparedReverser, MathError> {
+        let basis = if self.dimensions == 0 {
+            Vec::new()
+        } else {
+            self.create_lattice()?;
+            let reduced = self.reduced.as_ref().unwrap();
+            reduced.rows().map(|row| row.iter().map(|x| x.round().to_string()).collect()).collect()
+        };
+        Ok(PreparedReverser {
+            lcg: self.lcg.clone(),
+            mins: self.mins.iter().map(|v| v.to_string()).collect(),
+            maxes: self.maxes.iter().map(|v| v.to_string()).collect(),
+            call_indices: self.call_indices.clone(),
+            scrambled_bounds: self.scrambled_bounds.clone(),
+            success_chance: self.success_chance,
+            basis,
+        })
+    }
+
+    /// Rebuild a reduced reverser from [`prepared`](Self::prepared), with the
+    /// same `filtered_skips` as the original. No constraints can be added to it.
+    ///
+    /// Without the original constraints there are no generators to compare
+    /// the basis with, so it is only checked to be square and nonsingular.
+    pub fn from_prepared(state: &PreparedReverser, filtered_skips: Vec<FilteredSkip>) -> Result<Self, String> {
+        let dims = state.call_indices.len();
+        if state.mins.len() != dims || state.maxes.len() != dims {
+            return Err("Prepared reverser bounds do not match its call indices".to_string());
+        }
+        let parse = |entry: &String| -> Result<Int, String> {
+            entry.parse().map_err(|_| format!("Invalid bound: {}", entry))
+        };
+
+        let mut reverser = Self::with_lcg(state.lcg.clone(), filtered_skips);
+        reverser.mins = state.mins.iter().map(parse).collect::<Result<_, _>>()?;
+        reverser.maxes = state.maxes.iter().map(parse).collect::<Result<_, _>>()?;
+        reverser.call_indices = state.call_indices.clone();
+        reverser.scrambled_bounds = state.scrambled_bounds.clone();
+        reverser.success_chance = state.success_chance;
+        reverser.current_call_index = state.call_indices.last().copied().unwrap_or(0);
+        reverser.dimensions = dims;
+        if dims > 0 {
+            let basis = parse_basis(&state.basis, dims)?;
+            if basis.determinant().is_zero() {
+                return Err("Reduced basis is singular".to_string());
+            }
+            reverser.reduced = Some(basis);
+        }
+        Ok(reverser)
+    }
+
+    /// Use the basis of `state`, exported by [`prepared`](Self::prepared) from
+    /// a reverser with the same constraints as this one, instead of running
+    /// LLL. Fails unless the constraints match and the basis spans the same
+    /// lattice as this reverser's generators.
+    pub fn set_prepared(&mut self, state: &PreparedReverser) -> Result<(), String> {
+        let decimal = |values: &[Int]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let same_constraints = state.lcg == self.lcg
+            && state.call_indices == self.call_indices
+            && state.scrambled_bounds == self.scrambled_bounds
+            && state.mins == decimal(&self.mins)
+            && state.maxes == decimal(&self.maxes);
+        if !same_constraints {
+            return Err("Prepared reverser has different constraints".to_string());
+        }
+        if self.dimensions == 0 {
+            return Ok(());
+        }
+        let basis = parse_basis(&state.basis, self.dimensions)?;
+        if !spans_lattice(&basis, self.lattice.as_ref().unwrap()) {
+            return Err("Reduced basis does not span the constraint lattice".to_string());
+        }
+        self.reduced = Some(basis);
+        Ok(())
+    }
+
+    /// Number of branches when work is split over the first `depth + 1`
+    /// enumeration dimensions instead of only the narrowest one. Deeper
+    /// splits cost more to count but balance better across workers.
+    pub fn get_branch_count_at_depth(&mut self, depth: usize) -> Result<i64, SearchError> {
+        if self.dimensions == 0 {
+            return Ok(1);
+        }
+        self.create_lattice()?;
+        let (lattice, lower, upper, offset) = self.prepare_enumerate_params();
+        enumerate::get_branch_count_at_depth(&lattice, &lower, &upper, &offset, depth)
+    }
+
+    /// Find valid seeds for the branches [branch_start, branch_end) of the
+    /// split at `depth` (see [`get_branch_count_at_depth`](Self::get_branch_count_at_depth)).
+    pub fn find_seeds_for_branches_at_depth(
+        &mut self,
+        depth: usize,
+        branch_start: i64,
+        branch_end: i64,
+    ) -> Result<Vec<i64>, SearchError> {
+        if self.dimensions == 0 {
+            return self.find_seeds_for_branches(branch_start, branch_end);
+        }
+
+        self.create_lattice()?;
+        let (lattice, lower, upper, offset) = self.prepare_enumerate_params();
+        let results = enumerate::enumerate_bounds_partial_at_depth(
+            &lattice, &lower, &upper, &offset, depth, branch_start, branch_end,
+        )?;
         verbose_eprintln!("[lattice]   Partial enumeration found {} candidate(s).", results.len());
 
-        self.filter_results(&results)
+        Ok(self.filter_results(&results))
+    }
+
+    /// Every seed, for a reverser without constraints.
+    fn all_seeds(&self) -> impl Iterator<Item = i64> {
+        (0..self.lcg.modulus).map(|seed| seed as u64 as i64)
     }
 
     /// Prepare the enumeration parameters (lattice, lower, upper, offset).
-    fn prepare_enumerate_params(&self) -> (BigMatrix, BigVector, BigVector, BigVector) {
+    pub(crate) fn prepare_enumerate_params(&self) -> (BigMatrix, BigVector, BigVector, BigVector) {
         let dims = self.dimensions;
         let mut lower = BigVector::new(dims);
         let mut upper = BigVector::new(dims);
@@ -289,14 +766,14 @@ impl JavaRandomReverser {
         for i in 0..dims {
             lower.set(i, BigFraction::frac_from_bigint(self.mins[i].clone()));
             upper.set(i, BigFraction::frac_from_bigint(self.maxes[i].clone()));
-            offset.set(i, BigFraction::frac_from_bigint(Int::int_from_i64(rand.get_seed())));
+            offset.set(i, BigFraction::frac_from_bigint(Int::from(rand.get_seed() as u64)));
 
             if i != dims - 1 {
                 rand.advance(self.call_indices[i + 1] - self.call_indices[i]);
             }
         }
 
-        let lattice = self.lattice.as_ref().unwrap().transpose();
+        let lattice = self.reduced.as_ref().unwrap().transpose();
         (lattice, lower, upper, offset)
     }
 
@@ -307,11 +784,27 @@ impl JavaRandomReverser {
         let mut seeds: Vec<i64> = results
             .iter()
             .filter_map(|vec| {
+                // Enumeration only yields points inside the box, so anything
+                // else is a bug; drop it rather than wrap it into a bogus seed.
+                // The box spans less than two periods, so the low 64 bits
+                // of the point reduce to its seed.
                 let n = vec.get(0).numerator_int();
-                Some(r.next_seed(n.int_to_i64()))
+                let in_box = n >= self.mins[0] && n <= self.maxes[0];
+                debug_assert!(in_box, "lattice point {} is outside the seed range", vec);
+                in_box.then(|| r.next_seed(n.int_to_i64() as u64 as u128) as u64 as i64)
             })
             .collect();
 
+        if !self.scrambled_bounds.is_empty() {
+            let scramble = self.lcg.multiplier & (self.lcg.modulus - 1);
+            seeds.retain(|&seed| {
+                self.scrambled_bounds.iter().all(|&(index, min, max)| {
+                    let scrambled = self.lcg.combine(index).next_seed(seed as u64 as u128) ^ scramble;
+                    (min as u128..=max as u128).contains(&scrambled)
+                })
+            });
+        }
+
         // Filter by filtered skips
         if !self.filtered_skips.is_empty() {
             verbose_eprintln!("[lattice]   Filtering {} seed(s) with {} filtered skip(s)...", seeds.len(), self.filtered_skips.len());
@@ -329,7 +822,33 @@ impl JavaRandomReverser {
         seeds
     }
 
-    fn create_lattice(&mut self) {
+    /// Scale and LLL-reduce the lattice. Only the first call does any work, so
+    /// branch counting and enumeration on one reverser see the same basis.
+    ///
+    /// After constraints are added the reduction starts over from the sparse
+    /// generators. The previous reduced basis, extended by the new
+    /// dimensions, is dense and reduces more slowly; `bench_warm_lll_start`
+    /// in the tests compares the two.
+    fn create_lattice(&mut self) -> Result<(), MathError> {
+        if self.reduced.is_some() {
+            return Ok(());
+        }
+        let scales = self.scales();
+        let scaled = self.lattice.as_ref().unwrap() * &scales;
+
+        // LLL reduction
+        let params = lll::LLLParams::recommended();
+        let result = lll::reduce(&scaled, &params);
+
+        // Unscale
+        let scales_inv = lu_decomposition::inverse(&scales)?;
+        self.reduced = Some(&result.reduced_basis * &scales_inv);
+        Ok(())
+    }
+
+    /// Diagonal matrix stretching every dimension to the LCM of the box side
+    /// lengths, so LLL sees the constraint box as a cube.
+    fn scales(&self) -> BigMatrix {
         let dims = self.dimensions;
 
         // Compute side lengths
@@ -347,25 +866,287 @@ impl JavaRandomReverser {
         // Scaling matrix
         let mut scales = BigMatrix::new(dims, dims);
         for i in 0..dims {
-            scales.set(i, i, BigFraction::frac_from_bigint(lcm.int_div(&side_lengths[i])));
+            scales[(i, i)] = BigFraction::frac_from_bigint(lcm.int_div(&side_lengths[i]));
         }
+        scales
+    }
+}
+
+/// Run `f`, measuring its wall-clock time. wasm has no clock, so the time is
+/// always zero there.
+fn timed<R>(f: impl FnOnce() -> R) -> (R, Duration) {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let start = Instant::now();
+        let result = f();
+        (result, start.elapsed())
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        (f(), Duration::ZERO)
+    }
+}
 
-        let unscaled = self.lattice.as_ref().unwrap().clone();
-        let scaled = unscaled.multiply_matrix(&scales);
+/// One line per dimension: the call index it constrains and its bounds.
+impl fmt::Display for JavaRandomReverser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "JavaRandomReverser ({} dimensions, success chance {})", self.dimensions, self.success_chance)?;
+        for i in 0..self.dimensions {
+            let width = self.maxes[i].int_sub(&self.mins[i]).int_add_i64(1);
+            writeln!(
+                f,
+                "  [{}] call {}: {} ..= {} ({} values)",
+                i, self.call_indices[i], self.mins[i], self.maxes[i], width
+            )?;
+        }
+        Ok(())
+    }
+}
 
-        // LLL reduction
-        let params = lll::LLLParams::recommended();
-        let result = lll::reduce(&scaled, &params);
+/// The `dims x dims` basis with the decimal rows `rows`.
+fn parse_basis(rows: &[Vec<String>], dims: usize) -> Result<BigMatrix, String> {
+    if rows.len() != dims || rows.iter().any(|row| row.len() != dims) {
+        return Err(format!("Reduced basis does not have {} dimensions", dims));
+    }
+    let parse = |entry: &String| -> Result<BigFraction, String> {
+        let value: Int = entry.parse().map_err(|_| format!("Invalid basis entry: {}", entry))?;
+        Ok(BigFraction::frac_from_bigint(value))
+    };
+    let rows = rows
+        .iter()
+        .map(|row| row.iter().map(parse).collect::<Result<Vec<_>, _>>().map(BigVector::from_data))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(BigMatrix::from_rows(&rows))
+}
 
-        // Unscale
-        let scales_inv = lu_decomposition::inverse(&scales);
-        self.lattice = Some(result.reduced_basis.multiply_matrix(&scales_inv));
+/// Whether the rows of the square `basis` span the same integer lattice as
+/// the rows of `generators`: every generator is an integer combination of
+/// the basis, so the basis spans a superlattice, and both have the same
+/// determinant, so it is no larger.
+fn spans_lattice(basis: &BigMatrix, generators: &BigMatrix) -> bool {
+    let det = lattice_det(generators);
+    if det.int_is_zero() || basis.determinant().frac_abs() != BigFraction::frac_from_bigint(det) {
+        return false;
     }
+    let Ok(lu) = LuDecomposition::new(&basis.transpose()) else {
+        return false;
+    };
+    generators.rows().all(|row| {
+        let coefficients = lu.solve(&BigVector::from_data(row.to_vec()));
+        (0..coefficients.dimension()).all(|i| coefficients.get(i).denominator_int().int_is_one())
+    })
 }
 
-/// Int modulo (always non-negative).
-fn mod_big(a: &Int, m: &Int) -> Int {
-    let r = a.int_rem(m);
-    let shifted = r.int_add(m);
-    shifted.int_rem(m)
+/// Determinant of the integer lattice spanned by the rows of `generators`,
+/// which may be linearly dependent, or zero if they do not span every
+/// dimension. Eliminates each column with Euclid's algorithm on the rows,
+/// which keeps the lattice unchanged.
+fn lattice_det(generators: &BigMatrix) -> Int {
+    let cols = generators.col_count();
+    let mut rows: Vec<Vec<Int>> = generators.rows().map(|row| row.iter().map(FracOps::round).collect()).collect();
+
+    let mut det = Int::int_one();
+    for col in 0..cols {
+        loop {
+            let Some(pivot) = (col..rows.len())
+                .filter(|&r| !rows[r][col].int_is_zero())
+                .min_by(|&a, &b| rows[a][col].int_abs().cmp(&rows[b][col].int_abs()))
+            else {
+                return Int::int_zero();
+            };
+            rows.swap(col, pivot);
+
+            let (top, rest) = rows.split_at_mut(col + 1);
+            let pivot_row = &top[col];
+            let mut eliminated = true;
+            for row in rest.iter_mut() {
+                if row[col].int_is_zero() {
+                    continue;
+                }
+                let quotient = row[col].int_div(&pivot_row[col]);
+                for (value, p) in row.iter_mut().zip(pivot_row.iter()).skip(col) {
+                    *value = value.int_sub(&quotient.int_mul(p));
+                }
+                eliminated &= row[col].int_is_zero();
+            }
+            if eliminated {
+                break;
+            }
+        }
+        det = det.int_mul(&rows[col][col].int_abs());
+    }
+    det
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lcg::rand::JavaRng;
+
+    /// A reverser for the MMIX LCG observing `nextInt(256)` results of
+    /// `seed`, each within `slack` of the true value, with a filtered skip
+    /// at call 12, and the generator after those calls.
+    fn mmix_reverser(seed: i64, calls: usize, slack: i32) -> (JavaRandomReverser, Rand) {
+        let mut rand = Rand::of_internal_seed(LCG128::MMIX, seed);
+        let skip_value = {
+            let mut ahead = rand.clone();
+            ahead.advance(12);
+            ahead.next_int(10)
+        };
+        let filter = FilteredSkip::with_lcg(LCG128::MMIX, 12, Box::new(move |r| r.next_int(10) == skip_value));
+        let mut reverser = JavaRandomReverser::with_lcg(LCG128::MMIX, vec![filter]);
+        for _ in 0..calls {
+            let value = rand.next_int(256);
+            reverser.add_next_int_call(256, (value - slack).max(0), (value + slack).min(255));
+        }
+        (reverser, rand)
+    }
+
+    #[test]
+    fn test_reverse_64_bit_lcg() {
+        let seed = 0xFEDC_BA98_7654_3210u64 as i64;
+        let (mut reverser, _) = mmix_reverser(seed, 10, 0);
+        assert!(reverser.find_all_valid_seeds().unwrap().contains(&seed));
+    }
+
+    #[test]
+    fn test_backends_agree_on_reverser_lattice() {
+        let seed = 0xFEDC_BA98_7654_3210u64 as i64;
+        let (mut reverser, mut rand) = mmix_reverser(seed, 9, 2);
+        let value = rand.next_int(100);
+        reverser.add_next_int_call(100, value - 1, value + 1);
+        let value = rand.next_float();
+        reverser.add_next_float_call(value - 0.02, value + 0.02);
+
+        let mut found = Vec::new();
+        for backend in [EnumerationBackend::LinearProgram, EnumerationBackend::SphereDecoding] {
+            reverser.set_enumeration_backend(backend);
+            let mut seeds = reverser.find_all_valid_seeds().unwrap();
+            seeds.sort_unstable();
+            found.push(seeds);
+        }
+        assert!(found[0].contains(&seed));
+        assert_eq!(found[0], found[1]);
+    }
+
+    /// A Java reverser for `calls` exact `nextInt(16)` results of `seed`.
+    fn java_reverser(seed: i64, calls: usize) -> JavaRandomReverser {
+        let mut rand = Rand::of_internal_seed(LCG128::JAVA, seed);
+        let mut reverser = JavaRandomReverser::new(vec![]);
+        for _ in 0..calls {
+            let value = rand.next_int(16);
+            reverser.add_next_int_call(16, value, value);
+        }
+        reverser
+    }
+
+    #[test]
+    fn test_fast_seeds_are_valid_seeds() {
+        let seed = 0x1234_5678_9ABC;
+        let mut reverser = java_reverser(seed, 14);
+        assert_eq!(reverser.find_valid_seeds_fast().unwrap(), [seed]);
+        assert_eq!(reverser.find_all_valid_seeds().unwrap(), [seed]);
+
+        // Looser bounds leave several valid seeds, one of them the nearest point.
+        let seed = 0xFEDC_BA98_7654_3210u64 as i64;
+        let mut reverser = mmix_reverser(seed, 9, 1).0;
+        let fast = reverser.find_valid_seeds_fast().unwrap();
+        let all = reverser.find_all_valid_seeds().unwrap();
+        assert!(all.contains(&seed));
+        assert!(!fast.is_empty() && fast.iter().all(|s| all.contains(s)), "{:?} is not a subset of {:?}", fast, all);
+    }
+
+    #[test]
+    fn test_set_prepared_checks_the_lattice() {
+        let seed = 0x1234_5678_9ABC;
+        let prepared = java_reverser(seed, 14).prepared().unwrap();
+
+        let mut reverser = java_reverser(seed, 14);
+        reverser.set_prepared(&prepared).unwrap();
+        assert_eq!(reverser.find_all_valid_seeds().unwrap(), [seed]);
+
+        let basis = parse_basis(&prepared.basis, 14).unwrap();
+        let decimal = |v: BigVector| (0..14).map(|i| v.get(i).round().to_string()).collect::<Vec<_>>();
+
+        // Adding one basis vector to another keeps the lattice.
+        let mut sheared = prepared.clone();
+        sheared.basis[1] = decimal(basis.get_row(0).add(&basis.get_row(1)));
+        assert!(java_reverser(seed, 14).set_prepared(&sheared).is_ok());
+
+        // Doubling one spans a sublattice of index two.
+        let mut doubled = prepared.clone();
+        doubled.basis[0] = decimal(basis.get_row(0).add(&basis.get_row(0)));
+        assert!(java_reverser(seed, 14).set_prepared(&doubled).is_err());
+
+        let mut truncated = prepared.clone();
+        truncated.basis.pop();
+        assert!(java_reverser(seed, 14).set_prepared(&truncated).is_err());
+        assert!(java_reverser(seed, 13).set_prepared(&prepared).is_err());
+        assert!(java_reverser(seed + 1, 14).set_prepared(&prepared).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_prepared_reverser_round_trip() {
+        let seed = 0xBEEF_CAFE_F00D;
+        let mut original = java_reverser(seed, 14);
+        let json = serde_json::to_string(&original.prepared().unwrap()).unwrap();
+        let prepared: PreparedReverser = serde_json::from_str(&json).unwrap();
+        assert_eq!(prepared, original.prepared().unwrap());
+
+        let seeds = original.find_all_valid_seeds().unwrap();
+        assert!(seeds.contains(&seed));
+        let mut rebuilt = JavaRandomReverser::from_prepared(&prepared, vec![]).unwrap();
+        assert_eq!(rebuilt.find_all_valid_seeds().unwrap(), seeds);
+        let mut reused = java_reverser(seed, 14);
+        reused.set_prepared(&prepared).unwrap();
+        assert_eq!(reused.find_all_valid_seeds().unwrap(), seeds);
+    }
+
+    /// Times LLL on the generators of a lattice one constraint larger than
+    /// an already reduced one, against LLL on that reduced basis extended
+    /// by the new dimension. See `create_lattice`.
+    #[test]
+    #[ignore]
+    fn bench_warm_lll_start() {
+        let params = lll::LLLParams::recommended();
+        for (calls, bound) in [(12, 64), (16, 64), (20, 16), (24, 16), (30, 8)] {
+            let mut rand = Rand::of_internal_seed(LCG128::JAVA, 987654321);
+            let mut reverser = JavaRandomReverser::new(vec![]);
+            for _ in 0..calls {
+                let value = rand.next_int(bound);
+                reverser.add_next_int_call(bound, value, value);
+            }
+            let previous_scales = reverser.scales();
+            let previous = lll::reduce(&(reverser.lattice.as_ref().unwrap() * &previous_scales), &params);
+            let previous_basis = &previous.reduced_basis * &lu_decomposition::inverse(&previous_scales).unwrap();
+
+            let value = rand.next_int(bound);
+            reverser.add_next_int_call(bound, value, value);
+            let generators = reverser.lattice.clone().unwrap();
+            let scales = reverser.scales();
+
+            // Only the first generator and the new modulus row reach the new
+            // dimension, so each reduced vector extends by its coefficient
+            // on the first generator.
+            let (rows, dim) = (previous_basis.row_count(), generators.col_count());
+            let mut warm = BigMatrix::new(rows + 1, dim);
+            for row in 0..rows {
+                for col in 0..dim - 1 {
+                    warm.set(row, col, previous_basis.get(row, col).clone());
+                }
+                warm.set(row, dim - 1, previous.transformations.get(row, 0).mul_frac(generators.get(0, dim - 1)));
+            }
+            warm.set(rows, dim - 1, generators.get(dim, dim - 1).clone());
+            assert!(spans_lattice(&lll::reduce_default(&warm).reduced_basis, &generators));
+
+            let start = Instant::now();
+            lll::reduce(&(&generators * &scales), &params);
+            let cold = start.elapsed();
+            let start = Instant::now();
+            lll::reduce(&(&warm * &scales), &params);
+            let warm = start.elapsed();
+            println!("{:2} calls: generators {:?}, previous basis {:?}", calls + 1, cold, warm);
+        }
+    }
 }
@@ -1,8 +1,10 @@
 use wasm_bindgen::prelude::*;
 use serde::{Serialize, Deserialize};
 
+use crate::dungeon::crack_request::CrackRequest;
 use crate::dungeon::reverse_dungeon::{self, BiomeType, FloorSize};
 use crate::mc::chunk_rand::MCVersion;
+use crate::reverser::random_reverser::PreparedReverser;
 
 #[derive(Serialize, Deserialize)]
 pub struct WasmCrackResult {
@@ -15,29 +17,18 @@ pub struct WasmCrackResult {
 #[derive(Serialize, Deserialize)]
 pub struct WasmPrepareResult {
     pub total_branches: i64,
+    pub branches_per_possibility: Vec<i64>,
     pub possibilities: usize,
     pub dimensions: usize,
     pub info_bits: f32,
+    pub expected_candidates: f64,
+    pub success_chance: f64,
+    /// Pass these, as JSON, to [`crack_dungeon_partial_prepared_wasm`] so
+    /// workers skip LLL.
+    pub prepared_reversers: Vec<PreparedReverser>,
     pub error: Option<String>,
 }
 
-/// Parse a version string into MCVersion.
-fn parse_version(s: &str) -> Result<MCVersion, String> {
-    match s {
-        "1.8" => Ok(MCVersion::V1_8),
-        "1.9" => Ok(MCVersion::V1_9),
-        "1.10" => Ok(MCVersion::V1_10),
-        "1.11" => Ok(MCVersion::V1_11),
-        "1.12" => Ok(MCVersion::V1_12),
-        "1.13" => Ok(MCVersion::V1_13),
-        "1.14" => Ok(MCVersion::V1_14),
-        "1.15" => Ok(MCVersion::V1_15),
-        "1.16" => Ok(MCVersion::V1_16),
-        "1.17" => Ok(MCVersion::V1_17),
-        _ => Err(format!("Unknown version: {}", s)),
-    }
-}
-
 /// Parse a biome string into BiomeType.
 fn parse_biome(s: &str) -> Result<BiomeType, String> {
     match s.to_lowercase().as_str() {
@@ -102,7 +93,7 @@ fn crack_dungeon_inner(
     floor_size_str: &str,
     floor_grid: &[u8],
 ) -> WasmCrackResult {
-    let version = match parse_version(version) {
+    let version = match version.parse::<MCVersion>() {
         Ok(v) => v,
         Err(e) => return WasmCrackResult {
             dungeon_seeds: vec![], structure_seeds: vec![], world_seeds: vec![],
@@ -126,7 +117,7 @@ fn crack_dungeon_inner(
         },
     };
 
-    match reverse_dungeon::crack_dungeon(spawner_x, spawner_y, spawner_z, version, biome, &sequence) {
+    match reverse_dungeon::crack_dungeon(spawner_x, spawner_y, spawner_z, version, biome, &sequence, None) {
         Ok(result) => WasmCrackResult {
             dungeon_seeds: result.dungeon_seeds.iter().map(|s| s.to_string()).collect(),
             structure_seeds: result.structure_seeds.iter().map(|s| s.to_string()).collect(),
@@ -154,7 +145,7 @@ pub fn prepare_crack_wasm(
 ) -> String {
     let result = prepare_crack_inner(spawner_x, spawner_y, spawner_z, version, biome, floor_size, floor_grid);
     serde_json::to_string(&result).unwrap_or_else(|e| {
-        format!(r#"{{"error":"Serialization error: {}","total_branches":0,"possibilities":0,"dimensions":0,"info_bits":0}}"#, e)
+        format!(r#"{{"error":"Serialization error: {}","total_branches":0,"branches_per_possibility":[],"possibilities":0,"dimensions":0,"info_bits":0,"expected_candidates":0,"success_chance":0,"prepared_reversers":[]}}"#, e)
     })
 }
 
@@ -167,10 +158,11 @@ fn prepare_crack_inner(
     floor_size_str: &str,
     floor_grid: &[u8],
 ) -> WasmPrepareResult {
-    let version = match parse_version(version) {
+    let version = match version.parse::<MCVersion>() {
         Ok(v) => v,
         Err(e) => return WasmPrepareResult {
-            total_branches: 0, possibilities: 0, dimensions: 0, info_bits: 0.0,
+            total_branches: 0, branches_per_possibility: vec![], possibilities: 0, dimensions: 0, info_bits: 0.0,
+            expected_candidates: 0.0, success_chance: 0.0, prepared_reversers: vec![],
             error: Some(e),
         },
     };
@@ -178,7 +170,8 @@ fn prepare_crack_inner(
     let biome = match parse_biome(biome) {
         Ok(b) => b,
         Err(e) => return WasmPrepareResult {
-            total_branches: 0, possibilities: 0, dimensions: 0, info_bits: 0.0,
+            total_branches: 0, branches_per_possibility: vec![], possibilities: 0, dimensions: 0, info_bits: 0.0,
+            expected_candidates: 0.0, success_chance: 0.0, prepared_reversers: vec![],
             error: Some(e),
         },
     };
@@ -186,21 +179,27 @@ fn prepare_crack_inner(
     let sequence = match build_sequence(floor_grid, floor_size_str) {
         Ok(s) => s,
         Err(e) => return WasmPrepareResult {
-            total_branches: 0, possibilities: 0, dimensions: 0, info_bits: 0.0,
+            total_branches: 0, branches_per_possibility: vec![], possibilities: 0, dimensions: 0, info_bits: 0.0,
+            expected_candidates: 0.0, success_chance: 0.0, prepared_reversers: vec![],
             error: Some(e),
         },
     };
 
-    match reverse_dungeon::prepare_crack(spawner_x, spawner_y, spawner_z, version, biome, &sequence) {
+    match reverse_dungeon::prepare_crack(spawner_x, spawner_y, spawner_z, version, biome, &sequence, None) {
         Ok(result) => WasmPrepareResult {
             total_branches: result.total_branches,
+            branches_per_possibility: result.branches_per_possibility,
             possibilities: result.possibilities,
             dimensions: result.dimensions,
             info_bits: result.info_bits,
+            expected_candidates: result.expected_candidates,
+            success_chance: result.success_chance,
+            prepared_reversers: result.prepared_reversers,
             error: None,
         },
         Err(e) => WasmPrepareResult {
-            total_branches: 0, possibilities: 0, dimensions: 0, info_bits: 0.0,
+            total_branches: 0, branches_per_possibility: vec![], possibilities: 0, dimensions: 0, info_bits: 0.0,
+            expected_candidates: 0.0, success_chance: 0.0, prepared_reversers: vec![],
             error: Some(e),
         },
     }
@@ -219,10 +218,32 @@ pub fn crack_dungeon_partial_wasm(
     floor_grid: &[u8],
     branch_start: i32,
     branch_end: i32,
+) -> String {
+    crack_dungeon_partial_prepared_wasm(
+        spawner_x, spawner_y, spawner_z, version, biome, floor_size, floor_grid,
+        branch_start, branch_end, "",
+    )
+}
+
+/// Like [`crack_dungeon_partial_wasm`], reusing the `prepared_reversers` of
+/// the prepare step's JSON, given as a JSON array, instead of running LLL
+/// again. An empty string reduces the lattices as usual.
+#[wasm_bindgen]
+pub fn crack_dungeon_partial_prepared_wasm(
+    spawner_x: i32,
+    spawner_y: i32,
+    spawner_z: i32,
+    version: &str,
+    biome: &str,
+    floor_size: &str,
+    floor_grid: &[u8],
+    branch_start: i32,
+    branch_end: i32,
+    prepared_reversers: &str,
 ) -> String {
     let result = crack_partial_inner(
         spawner_x, spawner_y, spawner_z, version, biome, floor_size, floor_grid,
-        branch_start as i64, branch_end as i64,
+        branch_start as i64, branch_end as i64, prepared_reversers,
     );
     serde_json::to_string(&result).unwrap_or_else(|e| {
         format!(r#"{{"error":"Serialization error: {}","dungeon_seeds":[],"structure_seeds":[],"world_seeds":[]}}"#, e)
@@ -239,8 +260,9 @@ fn crack_partial_inner(
     floor_grid: &[u8],
     branch_start: i64,
     branch_end: i64,
+    prepared_reversers: &str,
 ) -> WasmCrackResult {
-    let version = match parse_version(version) {
+    let version = match version.parse::<MCVersion>() {
         Ok(v) => v,
         Err(e) => return WasmCrackResult {
             dungeon_seeds: vec![], structure_seeds: vec![], world_seeds: vec![],
@@ -264,10 +286,23 @@ fn crack_partial_inner(
         },
     };
 
-    match reverse_dungeon::crack_dungeon_partial(
-        spawner_x, spawner_y, spawner_z, version, biome, &sequence,
-        branch_start, branch_end,
-    ) {
+    let prepared: Vec<PreparedReverser> = match prepared_reversers {
+        "" => Vec::new(),
+        json => match serde_json::from_str(json) {
+            Ok(p) => p,
+            Err(e) => return WasmCrackResult {
+                dungeon_seeds: vec![], structure_seeds: vec![], world_seeds: vec![],
+                error: Some(format!("Invalid prepared reversers: {}", e)),
+            },
+        },
+    };
+
+    let request = CrackRequest::new(spawner_x, spawner_y, spawner_z)
+        .version(version)
+        .biome(biome)
+        .floor(sequence)
+        .prepared_reversers(prepared);
+    match request.crack_partial(branch_start, branch_end) {
         Ok(result) => WasmCrackResult {
             dungeon_seeds: result.dungeon_seeds.iter().map(|s| s.to_string()).collect(),
             structure_seeds: result.structure_seeds.iter().map(|s| s.to_string()).collect(),
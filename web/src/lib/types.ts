@@ -81,8 +81,11 @@ export interface CrackResult {
 // ── Prepare result ──
 export interface PrepareResult {
   total_branches: number;
+  branches_per_possibility?: number[];
   dimensions?: number;
   info_bits?: number;
+  expected_candidates?: number;
+  success_chance?: number;
   possibilities?: number;
   error?: string;
 }